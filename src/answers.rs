@@ -0,0 +1,132 @@
+use crate::parse::{Line, Lines, ParseError};
+use std::io::BufRead;
+
+pub const ANSWERS_PATH: &str = "var/answers.toml";
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Answer {
+    pub day: u8,
+    pub part: u8,
+    pub value: String,
+}
+
+pub fn parse_answers(buffer: impl BufRead) -> Result<Vec<Answer>, ParseError> {
+    let mut answers = Vec::new();
+    let mut lines = Lines::new(0, buffer);
+    let mut day = None;
+    while let Some(line) = lines.next() {
+        let line = line?;
+        let text = line.text.trim_end();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+        if let Some(section) = text.strip_prefix('[') {
+            let (name, tail) = line.split_once(section, "]")?;
+            if !tail.is_empty() {
+                return Err(line.error(tail, "end of line"));
+            }
+            day = Some(line.parse(line.strip_prefix(name, "day_")?, "day number")?);
+            continue;
+        }
+        let day = day.ok_or_else(|| line.error(text, "[day_N] section"))?;
+        let (key, value) = line.split_once(text, " = ")?;
+        let part = line.parse(line.strip_prefix(key, "part_")?, "part number")?;
+        let value = if value == "\"\"\"" {
+            read_multi_line_string(&mut lines)?
+        } else {
+            parse_string(&line, value)?
+        };
+        answers.push(Answer { day, part, value });
+    }
+    Ok(answers)
+}
+
+pub fn find_answer(answers: &[Answer], day: u8, part: u8) -> Option<&Answer> {
+    answers.iter().find(|v| v.day == day && v.part == part)
+}
+
+fn parse_string(line: &Line, text: &str) -> Result<String, ParseError> {
+    let (value, tail) = line.split_once(line.strip_prefix(text, "\"")?, "\"")?;
+    if let Some(index) = value.find('\\') {
+        return Err(line.error(&value[index..], "string without escapes"));
+    }
+    if !tail.is_empty() {
+        return Err(line.error(tail, "end of line"));
+    }
+    Ok(String::from(value))
+}
+
+fn read_multi_line_string(lines: &mut Lines<impl BufRead>) -> Result<String, ParseError> {
+    let mut value = String::new();
+    loop {
+        let line = lines.next_line("\"\"\"")?;
+        if line.text == "\"\"\"" {
+            return Ok(value);
+        }
+        value.push_str(&line.text);
+        value.push('\n');
+    }
+}
+
+#[test]
+fn parse_answers_test() {
+    let buffer = r#"[day_1]
+part_1 = "1559"
+
+# bitmap
+[day_13]
+part_2 = """
+#..
+.##
+"""
+"#
+    .as_bytes();
+    let answers = parse_answers(buffer).unwrap();
+    assert_eq!(
+        answers,
+        vec![
+            Answer {
+                day: 1,
+                part: 1,
+                value: String::from("1559"),
+            },
+            Answer {
+                day: 13,
+                part: 2,
+                value: String::from("#..\n.##\n"),
+            },
+        ]
+    );
+    assert_eq!(find_answer(&answers, 13, 2), Some(&answers[1]));
+    assert_eq!(find_answer(&answers, 13, 1), None);
+}
+
+#[test]
+fn parse_answers_error_test() {
+    assert_eq!(
+        parse_answers("part_1 = \"1\"\n".as_bytes()),
+        Err(ParseError::new(0, 1, 1, "[day_N] section"))
+    );
+    assert_eq!(
+        parse_answers("[day_1]\npart_1 = 1\n".as_bytes()),
+        Err(ParseError::new(0, 2, 10, "\"\\\"\""))
+    );
+    assert_eq!(
+        parse_answers("[day_1]\npart_1 = \"\"\"\n1\n".as_bytes()),
+        Err(ParseError::new(0, 4, 1, "\"\"\""))
+    );
+}
+
+#[test]
+fn recorded_answers_test() {
+    let file = std::fs::File::open(ANSWERS_PATH).unwrap();
+    let answers = parse_answers(std::io::BufReader::new(file)).unwrap();
+    for solver in crate::registry::SOLVERS {
+        assert!(
+            find_answer(&answers, solver.day, solver.part).is_some(),
+            "day {} part {}",
+            solver.day,
+            solver.part
+        );
+    }
+}
@@ -0,0 +1,13 @@
+pub fn take_option(args: &mut Vec<String>, name: &str, usage: &str) -> Option<String> {
+    let index = args.iter().position(|v| v == name)?;
+    if index + 1 >= args.len() {
+        exit_with_usage(&format!("missing value for {}", name), usage);
+    }
+    args.remove(index);
+    Some(args.remove(index))
+}
+
+pub fn exit_with_usage(message: &str, usage: &str) -> ! {
+    eprintln!("{}\n{}", message, usage);
+    std::process::exit(1);
+}
@@ -0,0 +1,91 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count_allocation(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count_allocation(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count_allocation(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+fn count_allocation(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    ALLOCATED_BYTES.fetch_add(size, Ordering::Relaxed);
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Allocations {
+    pub count: usize,
+    pub bytes: usize,
+}
+
+pub fn get_allocations() -> Allocations {
+    Allocations {
+        count: ALLOCATIONS.load(Ordering::Relaxed),
+        bytes: ALLOCATED_BYTES.load(Ordering::Relaxed),
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Measurement {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+    pub allocations: Allocations,
+}
+
+pub fn measure<T>(runs: usize, mut f: impl FnMut() -> T) -> Measurement {
+    assert!(runs > 0);
+    let mut durations = Vec::with_capacity(runs);
+    let mut allocations = Allocations::default();
+    for run in 0..runs {
+        let before = get_allocations();
+        let start = Instant::now();
+        std::hint::black_box(f());
+        let duration = start.elapsed();
+        let after = get_allocations();
+        if run == 0 {
+            allocations = Allocations {
+                count: after.count - before.count,
+                bytes: after.bytes - before.bytes,
+            };
+        }
+        durations.push(duration);
+    }
+    durations.sort_unstable();
+    Measurement {
+        min: durations[0],
+        median: durations[durations.len() / 2],
+        max: durations[durations.len() - 1],
+        allocations,
+    }
+}
+
+#[test]
+fn measure_test() {
+    let mut calls = 0;
+    let measurement = measure(5, || calls += 1);
+    assert_eq!(calls, 5);
+    assert!(measurement.min <= measurement.median);
+    assert!(measurement.median <= measurement.max);
+}
@@ -0,0 +1,204 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Mul};
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn bits(&self) -> usize {
+        match self.limbs.last() {
+            Some(v) => self.limbs.len() * 32 - v.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    pub fn nibble(&self, index: usize) -> u8 {
+        match self.limbs.get(index / 8) {
+            Some(v) => ((v >> (index % 8 * 4)) & 0xf) as u8,
+            None => 0,
+        }
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs[..] {
+            [] => Some(0),
+            [low] => Some(low as u64),
+            [low, high] => Some(((high as u64) << 32) | low as u64),
+            _ => None,
+        }
+    }
+
+    pub fn mul_add_small(&mut self, multiplier: u32, addend: u32) {
+        let mut carry = addend as u64;
+        for limb in self.limbs.iter_mut() {
+            let value = *limb as u64 * multiplier as u64 + carry;
+            *limb = value as u32;
+            carry = value >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+        self.normalize();
+    }
+
+    fn div_rem_small(&mut self, divisor: u32) -> u32 {
+        let mut remainder = 0u64;
+        for limb in self.limbs.iter_mut().rev() {
+            let value = (remainder << 32) | *limb as u64;
+            *limb = (value / divisor as u64) as u32;
+            remainder = value % divisor as u64;
+        }
+        self.normalize();
+        remainder as u32
+    }
+
+    fn normalize(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        let mut result = Self {
+            limbs: vec![value as u32, (value >> 32) as u32],
+        };
+        result.normalize();
+        result
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ();
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        if text.is_empty() {
+            return Err(());
+        }
+        let mut result = Self::zero();
+        for v in text.chars() {
+            result.mul_add_small(10, v.to_digit(10).ok_or(())?);
+        }
+        Ok(result)
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let mut limbs = Vec::with_capacity(self.limbs.len().max(other.limbs.len()) + 1);
+        let mut carry = 0u64;
+        for i in 0..self.limbs.len().max(other.limbs.len()) {
+            let value = *self.limbs.get(i).unwrap_or(&0) as u64
+                + *other.limbs.get(i).unwrap_or(&0) as u64
+                + carry;
+            limbs.push(value as u32);
+            carry = value >> 32;
+        }
+        limbs.push(carry as u32);
+        let mut result = BigUint { limbs };
+        result.normalize();
+        result
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, b) in other.limbs.iter().enumerate() {
+                let value = *a as u64 * *b as u64 + limbs[i + j] as u64 + carry;
+                limbs[i + j] = value as u32;
+                carry = value >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+        let mut result = BigUint { limbs };
+        result.normalize();
+        result
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut value = self.clone();
+        let mut chunks = Vec::new();
+        loop {
+            chunks.push(value.div_rem_small(1_000_000_000));
+            if value.is_zero() {
+                break;
+            }
+        }
+        write!(formatter, "{}", chunks.pop().unwrap())?;
+        for chunk in chunks.iter().rev() {
+            write!(formatter, "{:09}", chunk)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn arithmetic_test() {
+    let a = BigUint::from(u64::MAX);
+    let b = BigUint::from(1);
+    assert_eq!((&a + &b).to_string(), "18446744073709551616");
+    assert_eq!((&a + &b).to_u64(), None);
+    assert_eq!(
+        (&a * &a).to_string(),
+        "340282366920938463426481119284349108225"
+    );
+    assert_eq!((&a * &BigUint::zero()), BigUint::zero());
+    assert_eq!(BigUint::zero().to_string(), "0");
+    assert_eq!(BigUint::from(1_000_000_007).to_string(), "1000000007");
+    assert_eq!(a.to_u64(), Some(u64::MAX));
+    let c = BigUint::from_str("123456789012345678901234567890").unwrap();
+    assert_eq!(c.to_string(), "123456789012345678901234567890");
+    assert!(c > a && a > b && b > BigUint::zero());
+    assert_eq!(BigUint::from_str("12a"), Err(()));
+}
+
+#[test]
+fn bits_test() {
+    let mut value = BigUint::zero();
+    assert_eq!(value.bits(), 0);
+    for _ in 0..17 {
+        value.mul_add_small(16, 0xf);
+    }
+    assert_eq!(value.bits(), 68);
+    assert_eq!(value.nibble(0), 0xf);
+    assert_eq!(value.nibble(16), 0xf);
+    assert_eq!(value.nibble(17), 0);
+    assert_eq!(BigUint::from(0x1234).nibble(2), 2);
+}
@@ -0,0 +1,389 @@
+use adventofcode_2021::answers::{find_answer, parse_answers, Answer, ANSWERS_PATH};
+use adventofcode_2021::args::{exit_with_usage, take_option};
+use adventofcode_2021::bench::{measure, CountingAllocator};
+use adventofcode_2021::generate::generate_input;
+use adventofcode_2021::input::{find_inputs_or_exit, is_default_input, is_directory, read_or_exit};
+use adventofcode_2021::parse::{unwrap_or_exit, ParseError};
+use adventofcode_2021::pool::{map_parallel, with_jobs};
+use adventofcode_2021::registry::{find_variants, Solution, Solver, SOLVERS};
+use std::collections::BTreeMap;
+use std::io::BufReader;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const USAGE: &str = "usage: aoc [verify|bench] <day|first..last|all> [part] [path|directory|-] [--runs N] [--jobs N] [--format text|json]
+       aoc generate <day> [--seed N] [--size N]";
+const DEFAULT_RUNS: usize = 10;
+const NO_ANSWER: &str = "none";
+
+enum Mode {
+    Run,
+    Verify,
+    Bench,
+}
+
+enum Format {
+    Text,
+    Json,
+}
+
+struct Task<'a> {
+    solver: &'static Solver,
+    input: Option<&'a str>,
+    default_input: bool,
+    data: &'a [u8],
+}
+
+fn main() {
+    let mut args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(|v| v.as_str()) == Some("generate") {
+        return generate(&mut args);
+    }
+    let runs = match take_option(&mut args, "--runs", USAGE).map(|v| usize::from_str(&v)) {
+        Some(Ok(v)) if v > 0 => v,
+        Some(_) => exit_with_usage("invalid number of runs", USAGE),
+        None => DEFAULT_RUNS,
+    };
+    let jobs = match take_option(&mut args, "--jobs", USAGE).map(|v| usize::from_str(&v)) {
+        Some(Ok(v)) if v > 0 => v,
+        Some(_) => exit_with_usage("invalid number of jobs", USAGE),
+        None => 1,
+    };
+    let format = match take_option(&mut args, "--format", USAGE).as_deref() {
+        Some("text") | None => Format::Text,
+        Some("json") => Format::Json,
+        Some(v) => exit_with_usage(&format!("invalid format {:?}", v), USAGE),
+    };
+    let mode = match args.get(1).map(|v| v.as_str()) {
+        Some("verify") => Mode::Verify,
+        Some("bench") => Mode::Bench,
+        _ => Mode::Run,
+    };
+    if !matches!(mode, Mode::Run) {
+        if matches!(format, Format::Json) {
+            exit_with_usage("json format can be used only to run solvers", USAGE);
+        }
+        args.remove(1);
+    }
+    let days = match args.get(1).map(|v| parse_days(v)) {
+        Some(Ok(v)) => v,
+        Some(Err(e)) => exit_with_usage(&e, USAGE),
+        None => exit_with_usage("missing days", USAGE),
+    };
+    let mut rest = args.iter().skip(2).peekable();
+    let part = match rest.peek().map(|v| u8::from_str(v)) {
+        Some(Ok(v)) => {
+            rest.next();
+            Some(v)
+        }
+        _ => None,
+    };
+    let path = rest.next().map(|v| v.as_str());
+    if path.is_some() && days.start() != days.end() {
+        exit_with_usage("path can be used only with a single day", USAGE);
+    }
+    let solvers: Vec<&'static Solver> = SOLVERS
+        .iter()
+        .filter(|v| days.contains(&v.day) && part.map(|p| p == v.part).unwrap_or(true))
+        .collect();
+    if solvers.is_empty() {
+        exit_with_usage("no solvers found", USAGE);
+    }
+    let directory = is_directory(path);
+    let mut inputs: BTreeMap<u8, Vec<(String, bool, Vec<u8>)>> = BTreeMap::new();
+    for solver in solvers.iter() {
+        inputs.entry(solver.day).or_insert_with(|| {
+            find_inputs_or_exit(solver.day, path)
+                .iter()
+                .map(|v| (v.name(), is_default_input(solver.day, v), read_or_exit(v)))
+                .collect()
+        });
+    }
+    let tasks: Vec<Task> = solvers
+        .iter()
+        .flat_map(|solver| {
+            inputs[&solver.day]
+                .iter()
+                .map(move |(name, default_input, data)| Task {
+                    solver,
+                    input: if directory { Some(name.as_str()) } else { None },
+                    default_input: *default_input,
+                    data,
+                })
+        })
+        .collect();
+    with_jobs(jobs, || match mode {
+        Mode::Run => run_tasks(&tasks, jobs, format),
+        Mode::Verify => verify_tasks(&tasks, jobs),
+        Mode::Bench => bench_tasks(&tasks, runs),
+    });
+}
+
+fn generate(args: &mut Vec<String>) {
+    let seed = match take_option(args, "--seed", USAGE).map(|v| u64::from_str(&v)) {
+        Some(Ok(v)) => v,
+        Some(Err(e)) => exit_with_usage(&format!("invalid seed: {}", e), USAGE),
+        None => 0,
+    };
+    let size = match take_option(args, "--size", USAGE).map(|v| usize::from_str(&v)) {
+        Some(Ok(v)) if v > 0 => Some(v),
+        Some(_) => exit_with_usage("invalid size", USAGE),
+        None => None,
+    };
+    let day = match args.get(2).map(|v| u8::from_str(v)) {
+        Some(Ok(v)) => v,
+        Some(Err(e)) => exit_with_usage(&format!("invalid day: {}", e), USAGE),
+        None => exit_with_usage("missing day", USAGE),
+    };
+    if args.len() > 3 {
+        exit_with_usage(&format!("unexpected argument {:?}", args[3]), USAGE);
+    }
+    match generate_input(day, seed, size) {
+        Some(v) => print!("{}", v),
+        None => exit_with_usage(&format!("no generator for day {}", day), USAGE),
+    }
+}
+
+fn run_tasks(tasks: &[Task], jobs: usize, format: Format) {
+    let mut failed = false;
+    let results = map_parallel(jobs, tasks.len(), |i| run_task(&tasks[i]));
+    for (task, result) in tasks.iter().zip(results) {
+        let (answer, duration) = match result {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("{}: {}", format_label(task), e);
+                failed = true;
+                continue;
+            }
+        };
+        match format {
+            Format::Text => println!(
+                "{}",
+                format_answer(task, answer.as_ref().map_or(NO_ANSWER, |v| &v.answer))
+            ),
+            Format::Json => println!("{}", format_json(task, answer.as_ref(), duration)),
+        }
+    }
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+fn verify_tasks(tasks: &[Task], jobs: usize) {
+    let answers = read_answers();
+    let mut passed = 0;
+    let mut failed = 0;
+    let mut skipped = 0;
+    let mut total = Duration::default();
+    let results = map_parallel(jobs, tasks.len(), |i| {
+        if tasks[i].default_input {
+            Some(run_task(&tasks[i]))
+        } else {
+            None
+        }
+    });
+    for (task, result) in tasks.iter().zip(results) {
+        let label = format_label(task);
+        let result = match result {
+            Some(v) => v,
+            None => {
+                println!(
+                    "{}: skipped: answers are recorded only for the default input",
+                    label
+                );
+                skipped += 1;
+                continue;
+            }
+        };
+        let expected = match find_answer(&answers, task.solver.day, task.solver.part) {
+            Some(v) => v.value.trim_end(),
+            None => {
+                println!("{}: FAILED: no recorded answer", label);
+                failed += 1;
+                continue;
+            }
+        };
+        let (answer, duration) = match result {
+            Ok(v) => v,
+            Err(e) => {
+                println!("{}: FAILED: {}", label, e);
+                failed += 1;
+                continue;
+            }
+        };
+        total += duration;
+        let answer = answer.as_ref().map_or(NO_ANSWER, |v| &v.answer).trim_end();
+        let elapsed_ms = duration.as_secs_f64() * 1e3;
+        if answer == expected {
+            println!("{}: ok ({:.3} ms)", label, elapsed_ms);
+            passed += 1;
+        } else {
+            println!(
+                "{}: FAILED ({:.3} ms): expected {}, got {}",
+                label,
+                elapsed_ms,
+                format_value(expected),
+                format_value(answer)
+            );
+            failed += 1;
+        }
+    }
+    println!(
+        "{} passed, {} failed, {} skipped ({:.3} ms)",
+        passed,
+        failed,
+        skipped,
+        total.as_secs_f64() * 1e3
+    );
+    if failed > 0 {
+        std::process::exit(1);
+    }
+}
+
+fn bench_tasks(tasks: &[Task], runs: usize) {
+    for task in tasks {
+        let solver = task.solver;
+        println!("{}:", format_label(task));
+        for variant in std::iter::once(solver).chain(find_variants(solver.day, solver.part)) {
+            unwrap_or_exit((variant.solve)(&mut &task.data[..]));
+            let measurement = measure(runs, || (variant.solve)(&mut &task.data[..]));
+            println!(
+                "  {}: min {:.3} ms, median {:.3} ms, max {:.3} ms, {} allocations, {} bytes",
+                variant.name,
+                measurement.min.as_secs_f64() * 1e3,
+                measurement.median.as_secs_f64() * 1e3,
+                measurement.max.as_secs_f64() * 1e3,
+                measurement.allocations.count,
+                measurement.allocations.bytes
+            );
+        }
+    }
+}
+
+fn read_answers() -> Vec<Answer> {
+    let file = match std::fs::File::open(ANSWERS_PATH) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("failed to open {}: {}", ANSWERS_PATH, e);
+            std::process::exit(1);
+        }
+    };
+    match parse_answers(BufReader::new(file)) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!(
+                "{}:{}:{}: expected {}",
+                ANSWERS_PATH, e.line, e.column, e.expected
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_task(task: &Task) -> Result<(Option<Solution>, Duration), ParseError> {
+    let start = Instant::now();
+    let answer = (task.solver.solve)(&mut &task.data[..])?;
+    Ok((answer, start.elapsed()))
+}
+
+fn parse_days(text: &str) -> Result<RangeInclusive<u8>, String> {
+    if text == "all" {
+        return Ok(1..=25);
+    }
+    let parse_day = |v: &str| u8::from_str(v).map_err(|e| format!("invalid day {:?}: {}", v, e));
+    if let Some((first, last)) = text.split_once("..") {
+        Ok(parse_day(first)?..=parse_day(last)?)
+    } else {
+        let day = parse_day(text)?;
+        Ok(day..=day)
+    }
+}
+
+fn format_label(task: &Task) -> String {
+    match task.input {
+        Some(input) => format!(
+            "day {} part {} ({})",
+            task.solver.day, task.solver.part, input
+        ),
+        None => format!("day {} part {}", task.solver.day, task.solver.part),
+    }
+}
+
+fn format_answer(task: &Task, answer: &str) -> String {
+    let answer = answer.trim_end();
+    if answer.contains('\n') {
+        format!("{}:\n{}", format_label(task), answer)
+    } else {
+        format!("{}: {}", format_label(task), answer)
+    }
+}
+
+fn format_json(task: &Task, answer: Option<&Solution>, duration: Duration) -> String {
+    let value = match answer {
+        None => String::from("null"),
+        Some(v) if v.parts.is_empty() => to_json_value(&v.answer),
+        Some(v) => {
+            let fields: Vec<String> = v
+                .parts
+                .iter()
+                .map(|(name, value)| format!("{}:{}", to_json_string(name), to_json_value(value)))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+    };
+    let input = match task.input {
+        Some(v) => format!(",\"input\":{}", to_json_string(v)),
+        None => String::new(),
+    };
+    format!(
+        "{{\"day\":{},\"part\":{}{},\"answer\":{},\"elapsed_ms\":{:.3}}}",
+        task.solver.day,
+        task.solver.part,
+        input,
+        value,
+        duration.as_secs_f64() * 1e3
+    )
+}
+
+fn to_json_value(text: &str) -> String {
+    let text = text.trim_end();
+    if is_json_integer(text) {
+        String::from(text)
+    } else {
+        to_json_string(text)
+    }
+}
+
+fn is_json_integer(text: &str) -> bool {
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    !digits.is_empty()
+        && digits.bytes().all(|v| v.is_ascii_digit())
+        && (digits == "0" || !digits.starts_with('0'))
+}
+
+fn to_json_string(text: &str) -> String {
+    let mut result = String::from("\"");
+    for symbol in text.chars() {
+        match symbol {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            v if (v as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", v as u32)),
+            v => result.push(v),
+        }
+    }
+    result.push('"');
+    result
+}
+
+fn format_value(value: &str) -> String {
+    if value.contains('\n') {
+        format!("\n{}\n", value)
+    } else {
+        String::from(value)
+    }
+}
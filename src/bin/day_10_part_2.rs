@@ -0,0 +1,10 @@
+use adventofcode_2021::day_10_part_2::calculate_total_completion_score;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(10, path.as_deref(), |buffer| {
+        println!("{}", calculate_total_completion_score(buffer)?);
+        Ok(())
+    });
+}
@@ -0,0 +1,10 @@
+use adventofcode_2021::day_11_part_1::count_octopus_flashes;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(11, path.as_deref(), |buffer| {
+        println!("{}", count_octopus_flashes(buffer)?);
+        Ok(())
+    });
+}
@@ -0,0 +1,10 @@
+use adventofcode_2021::day_13_part_1::fold_transparent_paper;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(13, path.as_deref(), |buffer| {
+        println!("{}", fold_transparent_paper(buffer)?);
+        Ok(())
+    });
+}
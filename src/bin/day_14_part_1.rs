@@ -0,0 +1,10 @@
+use adventofcode_2021::day_14_part_1::build_polymer;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(14, path.as_deref(), |buffer| {
+        println!("{}", build_polymer(buffer)?);
+        Ok(())
+    });
+}
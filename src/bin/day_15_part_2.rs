@@ -0,0 +1,10 @@
+use adventofcode_2021::day_15_part_2::calculate_lowest_total_risk;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(15, path.as_deref(), |buffer| {
+        println!("{}", calculate_lowest_total_risk(buffer)?);
+        Ok(())
+    });
+}
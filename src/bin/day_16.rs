@@ -0,0 +1,32 @@
+use adventofcode_2021::args::{exit_with_usage, take_option};
+use adventofcode_2021::day_16_part_2::{
+    disassemble_packets, evaluate_packets, DisasmFormat, Precision,
+};
+use adventofcode_2021::input::for_each_input;
+
+const USAGE: &str =
+    "usage: day_16 [disasm] [path] [--format tree|sexp] [--precision checked|arbitrary]";
+
+fn main() {
+    let mut args: Vec<String> = std::env::args().collect();
+    let format = match take_option(&mut args, "--format", USAGE).as_deref() {
+        Some("tree") | None => DisasmFormat::Tree,
+        Some("sexp") => DisasmFormat::SExpression,
+        Some(v) => exit_with_usage(&format!("invalid format {:?}", v), USAGE),
+    };
+    let precision = match take_option(&mut args, "--precision", USAGE).as_deref() {
+        Some("checked") | None => Precision::Checked,
+        Some("arbitrary") => Precision::Arbitrary,
+        Some(v) => exit_with_usage(&format!("invalid precision {:?}", v), USAGE),
+    };
+    let disasm = args.get(1).map(|v| v == "disasm").unwrap_or(false);
+    let path = args.get(if disasm { 2 } else { 1 });
+    for_each_input(16, path.map(|v| v.as_str()), |buffer| {
+        if disasm {
+            print!("{}", disassemble_packets(buffer, format, precision)?);
+        } else {
+            println!("{}", evaluate_packets(buffer, precision)?);
+        }
+        Ok(())
+    });
+}
@@ -0,0 +1,10 @@
+use adventofcode_2021::day_16_part_2::evaluate_packet;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(16, path.as_deref(), |buffer| {
+        println!("{}", evaluate_packet(buffer)?);
+        Ok(())
+    });
+}
@@ -0,0 +1,10 @@
+use adventofcode_2021::day_17_part_1::find_max_y_for_probe;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(17, path.as_deref(), |buffer| {
+        println!("{:?}", find_max_y_for_probe(buffer)?);
+        Ok(())
+    });
+}
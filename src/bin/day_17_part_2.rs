@@ -0,0 +1,10 @@
+use adventofcode_2021::day_17_part_2::count_initial_velocities_to_hit;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(17, path.as_deref(), |buffer| {
+        println!("{}", count_initial_velocities_to_hit(buffer)?);
+        Ok(())
+    });
+}
@@ -0,0 +1,10 @@
+use adventofcode_2021::day_18_part_2::find_max_sum_snailfish_magnitude;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(18, path.as_deref(), |buffer| {
+        println!("{}", find_max_sum_snailfish_magnitude(buffer)?);
+        Ok(())
+    });
+}
@@ -0,0 +1,10 @@
+use adventofcode_2021::day_19_part_1::count_beacons;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(19, path.as_deref(), |buffer| {
+        println!("{}", count_beacons(buffer)?);
+        Ok(())
+    });
+}
@@ -0,0 +1,10 @@
+use adventofcode_2021::day_19_part_2::find_max_distance_between_scanners;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(19, path.as_deref(), |buffer| {
+        println!("{}", find_max_distance_between_scanners(buffer)?);
+        Ok(())
+    });
+}
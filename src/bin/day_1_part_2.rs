@@ -0,0 +1,10 @@
+use adventofcode_2021::day_1_part_2::count_increased_height_with_window;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(1, path.as_deref(), |buffer| {
+        println!("{}", count_increased_height_with_window(buffer)?);
+        Ok(())
+    });
+}
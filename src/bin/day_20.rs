@@ -0,0 +1,17 @@
+use adventofcode_2021::day_20::{count_enhanced_light_pixels, generate_images};
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let images = args.get(1).map(|v| v == "images").unwrap_or(false);
+    let path = args.get(if images { 2 } else { 1 });
+    for_each_input(20, path.map(|v| v.as_str()), |buffer| {
+        if images {
+            println!("generating images...");
+            generate_images(24 * 10, buffer)
+        } else {
+            println!("{:?}", count_enhanced_light_pixels(buffer)?);
+            Ok(())
+        }
+    });
+}
@@ -0,0 +1,10 @@
+use adventofcode_2021::day_21::play_dirac_dice;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(21, path.as_deref(), |buffer| {
+        println!("{:?}", play_dirac_dice(buffer)?);
+        Ok(())
+    });
+}
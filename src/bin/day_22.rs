@@ -0,0 +1,10 @@
+use adventofcode_2021::day_22::reboot_reactor;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(22, path.as_deref(), |buffer| {
+        println!("{:?}", reboot_reactor(buffer)?);
+        Ok(())
+    });
+}
@@ -0,0 +1,10 @@
+use adventofcode_2021::day_23::relocate_amphipods;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(23, path.as_deref(), |buffer| {
+        println!("{:?}", relocate_amphipods(buffer)?);
+        Ok(())
+    });
+}
@@ -0,0 +1,413 @@
+use adventofcode_2021::args::{exit_with_usage, take_option};
+use adventofcode_2021::day_24::{
+    build_data_flow, compile_program, compile_program_with_lines, data_flow_to_dot,
+    execute_program, execute_symbolic, find_possible_errors, find_the_meaning_of_monad,
+    format_execution_error, instruction_to_string, optimize_program, parse_input_values,
+    parse_pass, pass_to_string, profile_comparisons, program_to_string,
+    program_to_string_with_lines, run_pass, trace_program, trace_to_csv, trace_to_json,
+    transpile_data_flow, Alu, AluInput, Arithmetic, Debugger, FlowView, Instruction, Pass, Stop,
+    Value, Variable, DEFAULT_PASSES, DIGIT_INPUTS, INTEGER_INPUTS,
+};
+use adventofcode_2021::input::for_each_input;
+use adventofcode_2021::parse::{unwrap_or_exit, Line};
+use std::io::{BufRead, Write};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+const USAGE: &str = "usage: day_24 [execute|debug|trace DIGITS|flow|format|check|optimize|transpile|profile|symbolic] [path] [--values VALUES] [--passes PASSES] [--format csv|json] [--arithmetic checked|wrapping] [--inputs digits|integers] [--from z|INPUT] [--runs N] [--seed N]";
+
+const DEFAULT_RUNS: usize = 1000;
+
+fn main() {
+    let mut args: Vec<String> = std::env::args().collect();
+    let values = take_option(&mut args, "--values", USAGE)
+        .map(|v| unwrap_or_exit(parse_input_values(v.as_bytes())));
+    let passes = match take_option(&mut args, "--passes", USAGE) {
+        Some(v) => parse_passes(&v),
+        None => DEFAULT_PASSES.to_vec(),
+    };
+    let json = match take_option(&mut args, "--format", USAGE).as_deref() {
+        Some("csv") | None => false,
+        Some("json") => true,
+        Some(v) => exit_with_usage(&format!("invalid format {:?}", v), USAGE),
+    };
+    let arithmetic = match take_option(&mut args, "--arithmetic", USAGE).as_deref() {
+        Some("checked") | None => Arithmetic::Checked,
+        Some("wrapping") => Arithmetic::Wrapping,
+        Some(v) => exit_with_usage(&format!("invalid arithmetic {:?}", v), USAGE),
+    };
+    let inputs = match take_option(&mut args, "--inputs", USAGE).as_deref() {
+        Some("digits") => DIGIT_INPUTS,
+        Some("integers") => INTEGER_INPUTS,
+        None if values.is_some() => INTEGER_INPUTS,
+        None => DIGIT_INPUTS,
+        Some(v) => exit_with_usage(&format!("invalid inputs {:?}", v), USAGE),
+    };
+    let view = match take_option(&mut args, "--from", USAGE).as_deref() {
+        Some("z") => FlowView::Z,
+        Some(v) => match usize::from_str(v) {
+            Ok(index) => FlowView::Input(index),
+            Err(_) => exit_with_usage(&format!("invalid flow root {:?}", v), USAGE),
+        },
+        None => FlowView::All,
+    };
+    let runs = match take_option(&mut args, "--runs", USAGE).map(|v| usize::from_str(&v)) {
+        Some(Ok(v)) if v > 0 => v,
+        Some(_) => exit_with_usage("invalid number of runs", USAGE),
+        None => DEFAULT_RUNS,
+    };
+    let seed = match take_option(&mut args, "--seed", USAGE).map(|v| u64::from_str(&v)) {
+        Some(Ok(v)) => v,
+        Some(_) => exit_with_usage("invalid seed", USAGE),
+        None => 0,
+    };
+    let mode = match args.get(1).map(|v| v.as_str()) {
+        Some(
+            "execute" | "debug" | "trace" | "flow" | "format" | "check" | "optimize" | "transpile"
+            | "profile" | "symbolic",
+        ) => args.remove(1),
+        _ => String::from("solve"),
+    };
+    let digits = match mode.as_str() {
+        "execute" | "debug" | "trace" if values.is_none() => {
+            if args.len() < 2 {
+                exit_with_usage("missing input digits", USAGE);
+            }
+            args.remove(1)
+        }
+        _ => String::new(),
+    };
+    let mut failed = false;
+    for_each_input(24, args.get(1).map(|v| v.as_str()), |buffer| {
+        match mode.as_str() {
+            "execute" => {
+                let (program, lines) = compile_program_with_lines(buffer)?;
+                failed |= !match &values {
+                    Some(values) => {
+                        execute(&program, &lines, values.clone().into_iter(), arithmetic)
+                    }
+                    None => execute(&program, &lines, digits.as_bytes(), arithmetic),
+                };
+            }
+            "debug" => {
+                let (program, lines) = compile_program_with_lines(buffer)?;
+                match &values {
+                    Some(values) => debug(&program, &lines, values.clone().into_iter(), arithmetic),
+                    None => debug(&program, &lines, digits.as_bytes(), arithmetic),
+                }
+            }
+            "trace" => {
+                let (program, lines) = compile_program_with_lines(buffer)?;
+                failed |= !match &values {
+                    Some(values) => trace(
+                        &program,
+                        &lines,
+                        values.clone().into_iter(),
+                        arithmetic,
+                        json,
+                    ),
+                    None => trace(&program, &lines, digits.as_bytes(), arithmetic, json),
+                };
+            }
+            "profile" => {
+                let (program, lines) = compile_program_with_lines(buffer)?;
+                for (i, [zeros, ones]) in profile_comparisons(&program, runs, seed) {
+                    let status = match (zeros, ones) {
+                        (0, 0) => "never",
+                        (_, 0) => "always 0",
+                        (0, _) => "always 1",
+                        _ => "live",
+                    };
+                    println!(
+                        "{:>4} (line {}): {:<12} 0: {:>8} 1: {:>8} {}",
+                        i,
+                        lines[i].number,
+                        instruction_to_string(&program[i]),
+                        zeros,
+                        ones,
+                        status
+                    );
+                }
+            }
+            "symbolic" => {
+                let program = compile_program(buffer)?;
+                match execute_symbolic(&program, &inputs) {
+                    Some(paths) => {
+                        println!(
+                            "paths where z can be 0, assuming every input is {}:",
+                            format_inputs(&inputs)
+                        );
+                        for path in paths.iter() {
+                            let constraints: Vec<String> =
+                                path.constraints.iter().map(|v| v.to_string()).collect();
+                            if constraints.is_empty() {
+                                println!("always");
+                            } else {
+                                println!("when {}", constraints.join(", "));
+                            }
+                            println!("  z = {}", path.z);
+                        }
+                    }
+                    None => println!("too many paths"),
+                }
+            }
+            "format" => {
+                let (program, lines) = compile_program_with_lines(buffer)?;
+                print!("{}", program_to_string_with_lines(&program, &lines));
+            }
+            "check" => {
+                let (program, lines) = compile_program_with_lines(buffer)?;
+                let data_flow = build_data_flow(&program, inputs.clone(), arithmetic);
+                for error in find_possible_errors(&data_flow).iter() {
+                    println!(
+                        "possible error at {}",
+                        format_execution_error(error, &lines)
+                    );
+                }
+            }
+            "flow" => {
+                let program = compile_program(buffer)?;
+                if let FlowView::Input(index) = view {
+                    let count = program
+                        .iter()
+                        .filter(|v| matches!(v, Instruction::Inp(..)))
+                        .count();
+                    if index >= count {
+                        exit_with_usage(
+                            &format!(
+                                "invalid flow root {}: program reads {} inputs",
+                                index, count
+                            ),
+                            USAGE,
+                        );
+                    }
+                }
+                let data_flow = build_data_flow(&program, inputs.clone(), arithmetic);
+                print!("{}", data_flow_to_dot(&data_flow, view));
+            }
+            "optimize" => {
+                let program = compile_program(buffer)?;
+                let (optimized, removed) =
+                    optimize_program(&program, inputs.clone(), arithmetic, &passes);
+                for (pass, count) in removed.iter() {
+                    eprintln!("{}: removed {} instructions", pass_to_string(*pass), count);
+                }
+                print!("{}", program_to_string(&optimized));
+            }
+            "transpile" => {
+                let program = compile_program(buffer)?;
+                let mut data_flow = build_data_flow(&program, inputs.clone(), arithmetic);
+                for pass in passes.iter() {
+                    run_pass(*pass, &mut data_flow);
+                }
+                print!("{}", transpile_data_flow(&data_flow));
+            }
+            _ => println!("{:?}", find_the_meaning_of_monad(buffer)?),
+        }
+        Ok(())
+    });
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+fn trace(
+    program: &[Instruction],
+    lines: &[Line],
+    input: impl AluInput,
+    arithmetic: Arithmetic,
+    json: bool,
+) -> bool {
+    let mut alu = Alu::new(arithmetic);
+    let (steps, result) = trace_program(input, program, &mut alu);
+    if json {
+        println!("{}", trace_to_json(&steps));
+    } else {
+        print!("{}", trace_to_csv(&steps));
+    }
+    if let Err(e) = &result {
+        eprintln!("{}", format_execution_error(e, lines));
+    }
+    result.is_ok()
+}
+
+fn execute(
+    program: &[Instruction],
+    lines: &[Line],
+    input: impl AluInput,
+    arithmetic: Arithmetic,
+) -> bool {
+    let mut alu = Alu::new(arithmetic);
+    let result = execute_program(input, program, &mut alu);
+    match &result {
+        Ok(()) => println!("Ok(())"),
+        Err(e) => println!("{}", format_execution_error(e, lines)),
+    }
+    println!("{}", alu);
+    result.is_ok()
+}
+
+fn format_inputs(inputs: &RangeInclusive<Value>) -> String {
+    if *inputs == INTEGER_INPUTS {
+        String::from("any integer")
+    } else {
+        format!("in {}..={}", inputs.start(), inputs.end())
+    }
+}
+
+fn parse_passes(names: &str) -> Vec<Pass> {
+    let mut passes = Vec::new();
+    for name in names.split(',') {
+        match parse_pass(name) {
+            Some(pass) => passes.push(pass),
+            None => exit_with_usage(
+                &format!(
+                    "unknown pass: {}, expected constants, simplify, cse or dead",
+                    name
+                ),
+                USAGE,
+            ),
+        }
+    }
+    passes
+}
+
+const HELP: &str = r#"commands:
+  s, step [N]       execute N instructions (default 1)
+  c, continue       run until a breakpoint, watchpoint, error or the end
+  b, break N|inp    break at instruction N or at the next inp
+  d, delete N|inp   remove a breakpoint
+  w, watch VAR      stop when w, x, y or z changes
+  u, unwatch VAR    remove a watchpoint
+  r, registers      print registers
+  l, list [N]       list N instructions around the current one (default 5)
+  q, quit           exit the debugger"#;
+
+fn debug(program: &[Instruction], lines: &[Line], input: impl AluInput, arithmetic: Arithmetic) {
+    let mut debugger = Debugger::new(program, input);
+    debugger.set_arithmetic(arithmetic);
+    print_position(&debugger, lines);
+    let stdin = std::io::stdin();
+    let mut commands = stdin.lock().lines();
+    loop {
+        print!("(alu) ");
+        std::io::stdout().flush().unwrap();
+        let command = match commands.next() {
+            Some(Ok(v)) => v,
+            _ => break,
+        };
+        let words: Vec<&str> = command.split_whitespace().collect();
+        match words.as_slice() {
+            [] => (),
+            ["s" | "step"] => report(debugger.step(), &debugger, lines),
+            ["s" | "step", count] => match count.parse::<usize>() {
+                Ok(count) => {
+                    for _ in 0..count {
+                        let stop = debugger.step();
+                        if stop != Stop::Step {
+                            report(stop, &debugger, lines);
+                            break;
+                        }
+                        println!("{}", debugger.alu());
+                    }
+                    print_position(&debugger, lines);
+                }
+                Err(_) => println!("invalid count: {}", count),
+            },
+            ["c" | "continue"] => report(debugger.resume(), &debugger, lines),
+            ["b" | "break", "inp"] => debugger.set_break_on_input(true),
+            ["b" | "break", index] => match index.parse::<usize>() {
+                Ok(index) if debugger.add_breakpoint(index) => {
+                    println!("breakpoint at {}", index)
+                }
+                _ => println!("invalid breakpoint: {}", index),
+            },
+            ["b" | "break"] => println!("breakpoints: {:?}", debugger.breakpoints()),
+            ["d" | "delete", "inp"] => debugger.set_break_on_input(false),
+            ["d" | "delete", index] => match index.parse::<usize>() {
+                Ok(index) if debugger.remove_breakpoint(index) => (),
+                _ => println!("no breakpoint at {}", index),
+            },
+            ["w" | "watch", name] => match parse_variable(name) {
+                Some(variable) => {
+                    debugger.add_watchpoint(variable);
+                }
+                None => println!("invalid variable: {}", name),
+            },
+            ["u" | "unwatch", name] => match parse_variable(name) {
+                Some(variable) if debugger.remove_watchpoint(variable) => (),
+                _ => println!("no watchpoint on {}", name),
+            },
+            ["r" | "registers"] => println!("{}", debugger.alu()),
+            ["l" | "list"] => list(&debugger, program, lines, 5),
+            ["l" | "list", count] => match count.parse::<usize>() {
+                Ok(count) => list(&debugger, program, lines, count),
+                Err(_) => println!("invalid count: {}", count),
+            },
+            ["q" | "quit"] => break,
+            ["h" | "help"] => println!("{}", HELP),
+            _ => println!("unknown command: {}, try help", command),
+        }
+    }
+}
+
+fn report<R: AluInput>(stop: Stop, debugger: &Debugger<R>, lines: &[Line]) {
+    match stop {
+        Stop::Step => (),
+        Stop::Breakpoint(index) => println!("breakpoint at {}", index),
+        Stop::Input(index) => println!("input at {}", index),
+        Stop::Watchpoint(variable, old, new) => {
+            println!("{:?} changed from {} to {}", variable, old, new)
+        }
+        Stop::Finished => println!("program finished"),
+        Stop::Error(error) => println!("error at {}", format_execution_error(&error, lines)),
+    }
+    println!("{}", debugger.alu());
+    print_position(debugger, lines);
+}
+
+fn print_position<R: AluInput>(debugger: &Debugger<R>, lines: &[Line]) {
+    if let Some(line) = lines.get(debugger.position()) {
+        println!(
+            "{:>4} (line {}): {}",
+            debugger.position(),
+            line.number,
+            line.text
+        );
+    }
+}
+
+fn list<R: AluInput>(
+    debugger: &Debugger<R>,
+    program: &[Instruction],
+    lines: &[Line],
+    count: usize,
+) {
+    let start = debugger.position().saturating_sub(count / 2);
+    for (i, instruction) in program.iter().enumerate().skip(start).take(count) {
+        let marker = if i == debugger.position() {
+            "=>"
+        } else if debugger.breakpoints().contains(&i) {
+            "b "
+        } else {
+            "  "
+        };
+        println!(
+            "{} {:>4} (line {}): {}",
+            marker,
+            i,
+            lines[i].number,
+            instruction_to_string(instruction)
+        );
+    }
+}
+
+fn parse_variable(name: &str) -> Option<Variable> {
+    match name {
+        "w" => Some(Variable::W),
+        "x" => Some(Variable::X),
+        "y" => Some(Variable::Y),
+        "z" => Some(Variable::Z),
+        _ => None,
+    }
+}
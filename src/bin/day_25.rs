@@ -0,0 +1,10 @@
+use adventofcode_2021::day_25::move_sea_cucumbers;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(25, path.as_deref(), |buffer| {
+        println!("{:?}", move_sea_cucumbers(buffer)?);
+        Ok(())
+    });
+}
@@ -0,0 +1,12 @@
+use adventofcode_2021::day_2_part_1::{move_submarine, product};
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(2, path.as_deref(), |buffer| {
+        let position = move_submarine(buffer)?;
+        println!("{:?}", position);
+        println!("{}", product(&position));
+        Ok(())
+    });
+}
@@ -0,0 +1,12 @@
+use adventofcode_2021::day_2_part_2::{move_submarine_with_aim, position_product};
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(2, path.as_deref(), |buffer| {
+        let state = move_submarine_with_aim(buffer)?;
+        println!("{:?}", state);
+        println!("{}", position_product(&state));
+        Ok(())
+    });
+}
@@ -0,0 +1,10 @@
+use adventofcode_2021::day_3_part_1::calculate_power_consumption;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(3, path.as_deref(), |buffer| {
+        println!("{}", calculate_power_consumption(buffer)?);
+        Ok(())
+    });
+}
@@ -0,0 +1,10 @@
+use adventofcode_2021::day_3_part_2::calculate_life_support_rating;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(3, path.as_deref(), |buffer| {
+        println!("{}", calculate_life_support_rating(buffer)?);
+        Ok(())
+    });
+}
@@ -0,0 +1,10 @@
+use adventofcode_2021::day_4_part_2::find_last_bingo_winner_score;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(4, path.as_deref(), |buffer| {
+        println!("{:?}", find_last_bingo_winner_score(buffer)?);
+        Ok(())
+    });
+}
@@ -0,0 +1,10 @@
+use adventofcode_2021::day_5_part_2::count_points_of_intersection;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(5, path.as_deref(), |buffer| {
+        println!("{}", count_points_of_intersection(buffer)?);
+        Ok(())
+    });
+}
@@ -0,0 +1,10 @@
+use adventofcode_2021::day_6_part_1::simulate_lanternfish_population;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(6, path.as_deref(), |buffer| {
+        println!("{}", simulate_lanternfish_population(buffer)?);
+        Ok(())
+    });
+}
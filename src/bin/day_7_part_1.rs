@@ -0,0 +1,10 @@
+use adventofcode_2021::day_7_part_1::align_crabs_position;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(7, path.as_deref(), |buffer| {
+        println!("{}", align_crabs_position(buffer)?);
+        Ok(())
+    });
+}
@@ -0,0 +1,10 @@
+use adventofcode_2021::day_8_part_2::sum_decoded_numbers;
+use adventofcode_2021::input::for_each_input;
+
+fn main() {
+    let path = std::env::args().nth(1);
+    for_each_input(8, path.as_deref(), |buffer| {
+        println!("{}", sum_decoded_numbers(buffer)?);
+        Ok(())
+    });
+}
@@ -0,0 +1,148 @@
+#[derive(Clone, Debug)]
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    end: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            position: 0,
+            end: bytes.len() * 8,
+        }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.end - self.position
+    }
+
+    pub fn read_u(&mut self, bits: usize) -> Option<u64> {
+        if bits > 64 || bits > self.remaining() {
+            return None;
+        }
+        let mut value = 0;
+        for _ in 0..bits {
+            let bit = (self.bytes[self.position / 8] >> (7 - self.position % 8)) & 1;
+            value = (value << 1) | bit as u64;
+            self.position += 1;
+        }
+        Some(value)
+    }
+
+    pub fn take(&mut self, bits: usize) -> Option<BitReader<'a>> {
+        if bits > self.remaining() {
+            return None;
+        }
+        let reader = Self {
+            bytes: self.bytes,
+            position: self.position,
+            end: self.position + bits,
+        };
+        self.position += bits;
+        Some(reader)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn write_u(&mut self, value: u64, bits: usize) {
+        assert!(bits <= 64);
+        for i in (0..bits).rev() {
+            if self.len.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            let bit = ((value >> i) & 1) as u8;
+            *self.bytes.last_mut().unwrap() |= bit << (7 - self.len % 8);
+            self.len += 1;
+        }
+    }
+
+    pub fn append(&mut self, other: &BitWriter) {
+        let mut reader = BitReader::new(&other.bytes);
+        let mut remaining = other.len;
+        while remaining > 0 {
+            let bits = remaining.min(64);
+            self.write_u(reader.read_u(bits).unwrap(), bits);
+            remaining -= bits;
+        }
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.bytes.iter().map(|v| format!("{:02X}", v)).collect()
+    }
+}
+
+#[test]
+fn read_u_test() {
+    let mut reader = BitReader::new(&[0xD2, 0xFE, 0x28]);
+    assert_eq!(reader.remaining(), 24);
+    assert_eq!(reader.read_u(3), Some(6));
+    assert_eq!(reader.read_u(3), Some(4));
+    assert_eq!(reader.position(), 6);
+    assert_eq!(reader.read_u(5), Some(0b10111));
+    assert_eq!(reader.read_u(13), Some(0b1_1110_0010_1000));
+    assert_eq!(reader.remaining(), 0);
+    assert_eq!(reader.read_u(1), None);
+    let mut reader = BitReader::new(&[0xFF; 9]);
+    assert_eq!(reader.read_u(64), Some(u64::MAX));
+    assert_eq!(reader.read_u(65), None);
+}
+
+#[test]
+fn take_test() {
+    let mut reader = BitReader::new(&[0b1010_0101, 0xF0]);
+    reader.read_u(2).unwrap();
+    let mut sub_reader = reader.take(8).unwrap();
+    assert_eq!(reader.position(), 10);
+    assert_eq!(sub_reader.position(), 2);
+    assert_eq!(sub_reader.remaining(), 8);
+    assert_eq!(sub_reader.read_u(8), Some(0b1001_0111));
+    assert_eq!(sub_reader.read_u(1), None);
+    assert_eq!(reader.read_u(6), Some(0b11_0000));
+    assert!(reader.take(1).is_none());
+}
+
+#[test]
+fn write_u_test() {
+    let mut writer = BitWriter::new();
+    assert!(writer.is_empty());
+    writer.write_u(6, 3);
+    writer.write_u(4, 3);
+    writer.write_u(0b10111, 5);
+    assert_eq!(writer.len(), 11);
+    let mut other = BitWriter::new();
+    other.write_u(0b1_1110_0010_1000, 13);
+    writer.append(&other);
+    assert_eq!(writer.len(), 24);
+    assert_eq!(writer.bytes(), &[0xD2, 0xFE, 0x28]);
+    assert_eq!(writer.to_hex(), "D2FE28");
+    writer.write_u(1, 1);
+    assert_eq!(writer.to_hex(), "D2FE2880");
+}
@@ -1,35 +1,32 @@
+use crate::parse::{Line, Lines, ParseError};
 use std::io::BufRead;
 
-fn main() {
-    println!(
-        "{}",
-        calculate_total_syntax_error_score(std::io::stdin().lock())
-    );
-}
+const DAY: u8 = 10;
 
-fn calculate_total_syntax_error_score(buffer: impl BufRead) -> u64 {
-    buffer
-        .lines()
-        .map(|v| get_syntax_error_score(&v.unwrap()))
-        .sum()
+pub fn calculate_total_syntax_error_score(buffer: impl BufRead) -> Result<u64, ParseError> {
+    let mut result = 0;
+    for line in Lines::new(DAY, buffer) {
+        result += get_syntax_error_score(&line?)?;
+    }
+    Ok(result)
 }
 
-fn get_syntax_error_score(line: &str) -> u64 {
+fn get_syntax_error_score(line: &Line) -> Result<u64, ParseError> {
     let mut open = Vec::new();
-    for symbol in line.chars() {
+    for (index, symbol) in line.text.char_indices() {
         match symbol {
             '(' | '[' | '{' | '<' => open.push(symbol),
             ')' | ']' | '}' | '>' => {
                 if let Some(pair) = open.pop() {
                     if get_matching_close(pair) != symbol {
-                        return get_symbol_score(symbol);
+                        return Ok(get_symbol_score(symbol));
                     }
                 }
             }
-            _ => (),
+            _ => return Err(line.error(&line.text[index..], "bracket")),
         }
     }
-    0
+    Ok(0)
 }
 
 fn get_matching_close(open: char) -> char {
@@ -66,5 +63,5 @@ fn example_test() {
 <{([{{}}[<[[[<>{}]]]>[]]
 "#
     .as_bytes();
-    assert_eq!(calculate_total_syntax_error_score(buffer), 26397);
+    assert_eq!(calculate_total_syntax_error_score(buffer), Ok(26397));
 }
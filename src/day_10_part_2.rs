@@ -1,42 +1,45 @@
+use crate::parse::{Line, Lines, ParseError};
 use std::io::BufRead;
 
-fn main() {
-    println!(
-        "{}",
-        calculate_total_completion_score(std::io::stdin().lock())
-    );
-}
+const DAY: u8 = 10;
 
-fn calculate_total_completion_score(buffer: impl BufRead) -> u64 {
-    let mut scores: Vec<u64> = buffer
-        .lines()
-        .map(|v| get_completion_score(&v.unwrap()))
-        .filter(|v| *v != 0)
-        .collect();
+pub fn calculate_total_completion_score(buffer: impl BufRead) -> Result<u64, ParseError> {
+    let mut scores: Vec<u64> = Vec::new();
+    let mut lines = Lines::new(DAY, buffer);
+    for line in lines.by_ref() {
+        let score = get_completion_score(&line?)?;
+        if score != 0 {
+            scores.push(score);
+        }
+    }
+    if scores.is_empty() {
+        return Err(lines.error_at_end("incomplete line"));
+    }
     scores.sort_unstable();
-    scores[scores.len() / 2]
+    Ok(scores[scores.len() / 2])
 }
 
-fn get_completion_score(line: &str) -> u64 {
+fn get_completion_score(line: &Line) -> Result<u64, ParseError> {
     let mut open = Vec::new();
-    for symbol in line.chars() {
+    for (index, symbol) in line.text.char_indices() {
         match symbol {
             '(' | '[' | '{' | '<' => open.push(symbol),
             ')' | ']' | '}' | '>' => {
                 if let Some(pair) = open.last().cloned() {
                     if get_matching_close(pair) != symbol {
-                        return 0;
+                        return Ok(0);
                     }
                     open.pop();
                 }
             }
-            _ => (),
+            _ => return Err(line.error(&line.text[index..], "bracket")),
         }
     }
-    open.iter()
+    Ok(open
+        .iter()
         .rev()
         .map(|v| get_symbol_completion_score(*v))
-        .fold(0, |total, score| total * 5 + score)
+        .fold(0, |total, score| total * 5 + score))
 }
 
 fn get_matching_close(open: char) -> char {
@@ -73,5 +76,5 @@ fn example_test() {
 <{([{{}}[<[[[<>{}]]]>[]]
 "#
     .as_bytes();
-    assert_eq!(calculate_total_completion_score(buffer), 288957);
+    assert_eq!(calculate_total_completion_score(buffer), Ok(288957));
 }
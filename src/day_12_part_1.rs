@@ -1,11 +1,10 @@
+use crate::parse::{Lines, ParseError};
 use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::io::BufRead;
 
-fn main() {
-    println!("{}", count_cave_paths(std::io::stdin().lock()));
-}
+const DAY: u8 = 12;
 
-fn count_cave_paths(buffer: impl BufRead) -> usize {
+pub fn count_cave_paths(buffer: impl BufRead) -> Result<usize, ParseError> {
     let mut nodes = BTreeMap::<String, Vec<String>>::new();
     let mut add_connection = |src: &str, dst: &str| {
         nodes
@@ -13,12 +12,16 @@ fn count_cave_paths(buffer: impl BufRead) -> usize {
             .or_default()
             .push(String::from(dst));
     };
-    for line in buffer.lines() {
-        let line_str = line.unwrap();
-        let (a, b) = line_str.split_once('-').unwrap();
+    let mut lines = Lines::new(DAY, buffer);
+    for line in lines.by_ref() {
+        let line = line?;
+        let (a, b) = line.split_once(&line.text, "-")?;
         add_connection(a, b);
         add_connection(b, a);
     }
+    if !nodes.contains_key("start") {
+        return Err(lines.error_at_end("connection with start"));
+    }
     let mut paths = vec![Path {
         last: "start",
         visited: ["start"].iter().copied().collect(),
@@ -45,7 +48,7 @@ fn count_cave_paths(buffer: impl BufRead) -> usize {
             incoming.push_back(paths.len() - 1);
         }
     }
-    finished_paths
+    Ok(finished_paths)
 }
 
 #[derive(Clone)]
@@ -69,7 +72,7 @@ A-end
 b-end
 "#
     .as_bytes();
-    assert_eq!(count_cave_paths(buffer), 10);
+    assert_eq!(count_cave_paths(buffer), Ok(10));
 }
 
 #[test]
@@ -86,7 +89,7 @@ kj-HN
 kj-dc
 "#
     .as_bytes();
-    assert_eq!(count_cave_paths(buffer), 19);
+    assert_eq!(count_cave_paths(buffer), Ok(19));
 }
 
 #[test]
@@ -111,5 +114,5 @@ pj-fs
 start-RW
 "#
     .as_bytes();
-    assert_eq!(count_cave_paths(buffer), 226);
+    assert_eq!(count_cave_paths(buffer), Ok(226));
 }
@@ -1,11 +1,10 @@
+use crate::parse::{Lines, ParseError};
 use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::io::BufRead;
 
-fn main() {
-    println!("{}", count_cave_paths(std::io::stdin().lock()));
-}
+const DAY: u8 = 12;
 
-fn count_cave_paths(buffer: impl BufRead) -> usize {
+pub fn count_cave_paths(buffer: impl BufRead) -> Result<usize, ParseError> {
     let mut nodes = BTreeMap::<String, Vec<String>>::new();
     let mut add_connection = |src: &str, dst: &str| {
         nodes
@@ -13,12 +12,16 @@ fn count_cave_paths(buffer: impl BufRead) -> usize {
             .or_default()
             .push(String::from(dst));
     };
-    for line in buffer.lines() {
-        let line_str = line.unwrap();
-        let (a, b) = line_str.split_once('-').unwrap();
+    let mut lines = Lines::new(DAY, buffer);
+    for line in lines.by_ref() {
+        let line = line?;
+        let (a, b) = line.split_once(&line.text, "-")?;
         add_connection(a, b);
         add_connection(b, a);
     }
+    if !nodes.contains_key("start") {
+        return Err(lines.error_at_end("connection with start"));
+    }
     let mut paths = vec![Path {
         last: "start",
         visited: ["start"].iter().copied().collect(),
@@ -57,7 +60,7 @@ fn count_cave_paths(buffer: impl BufRead) -> usize {
             incoming.push_back(paths.len() - 1);
         }
     }
-    finished_paths
+    Ok(finished_paths)
 }
 
 #[derive(Clone)]
@@ -82,7 +85,7 @@ A-end
 b-end
 "#
     .as_bytes();
-    assert_eq!(count_cave_paths(buffer), 36);
+    assert_eq!(count_cave_paths(buffer), Ok(36));
 }
 
 #[test]
@@ -99,7 +102,7 @@ kj-HN
 kj-dc
 "#
     .as_bytes();
-    assert_eq!(count_cave_paths(buffer), 103);
+    assert_eq!(count_cave_paths(buffer), Ok(103));
 }
 
 #[test]
@@ -124,5 +127,5 @@ pj-fs
 start-RW
 "#
     .as_bytes();
-    assert_eq!(count_cave_paths(buffer), 3509);
+    assert_eq!(count_cave_paths(buffer), Ok(3509));
 }
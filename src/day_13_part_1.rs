@@ -1,20 +1,20 @@
+use crate::parse::{Line, Lines, ParseError};
 use std::collections::BTreeSet;
 use std::io::BufRead;
-use std::str::FromStr;
 
-fn main() {
-    println!("{}", fold_transparent_paper(std::io::stdin().lock()));
-}
+const DAY: u8 = 13;
 
-fn fold_transparent_paper(mut buffer: impl BufRead) -> usize {
-    let mut dots = read_dots(&mut buffer);
-    for (fold_type, coordinate) in buffer.lines().map(|v| parse_fold(&v.unwrap())).take(1) {
+pub fn fold_transparent_paper(buffer: impl BufRead) -> Result<usize, ParseError> {
+    let mut lines = Lines::new(DAY, buffer);
+    let mut dots = read_dots(&mut lines)?;
+    for line in lines.take(1) {
+        let (fold_type, coordinate) = parse_fold(&line?, &dots)?;
         match fold_type {
             FoldType::X => fold_by_x(coordinate, &mut dots),
             FoldType::Y => fold_by_y(coordinate, &mut dots),
         }
     }
-    dots.len()
+    Ok(dots.len())
 }
 
 fn fold_by_x(c: u32, dots: &mut BTreeSet<(u32, u32)>) {
@@ -22,13 +22,13 @@ fn fold_by_x(c: u32, dots: &mut BTreeSet<(u32, u32)>) {
     let mut folded = Vec::new();
     dots.retain(|(x, y)| {
         if *x > c {
-            folded.push((2 * c - *x, *y));
+            folded.push((c - (*x - c), *y));
             false
         } else {
             true
         }
     });
-    dots.extend(folded.into_iter());
+    dots.extend(folded);
 }
 
 fn fold_by_y(c: u32, dots: &mut BTreeSet<(u32, u32)>) {
@@ -36,37 +36,26 @@ fn fold_by_y(c: u32, dots: &mut BTreeSet<(u32, u32)>) {
     let mut folded = Vec::new();
     dots.retain(|(x, y)| {
         if *y > c {
-            folded.push((*x, 2 * c - *y));
+            folded.push((*x, c - (*y - c)));
             false
         } else {
             true
         }
     });
-    dots.extend(folded.into_iter());
+    dots.extend(folded);
 }
 
-fn read_dots(buffer: &mut impl BufRead) -> BTreeSet<(u32, u32)> {
+fn read_dots(lines: &mut Lines<impl BufRead>) -> Result<BTreeSet<(u32, u32)>, ParseError> {
     let mut dots = BTreeSet::new();
-    loop {
-        let mut line = String::new();
-        buffer.read_line(&mut line).unwrap();
-        strip_line_break(&mut line);
-        if line.is_empty() {
+    for line in lines.by_ref() {
+        let line = line?;
+        if line.text.is_empty() {
             break;
         }
-        let (x, y) = line.split_once(',').unwrap();
-        dots.insert((u32::from_str(x).unwrap(), u32::from_str(y).unwrap()));
-    }
-    dots
-}
-
-fn strip_line_break(line: &mut String) {
-    if line.ends_with('\n') {
-        line.pop();
-    }
-    if line.ends_with('\r') {
-        line.pop();
+        let (x, y) = line.split_once(&line.text, ",")?;
+        dots.insert((line.parse(x, "number")?, line.parse(y, "number")?));
     }
+    Ok(dots)
 }
 
 enum FoldType {
@@ -74,16 +63,28 @@ enum FoldType {
     Y,
 }
 
-fn parse_fold(line: &str) -> (FoldType, u32) {
-    let (name, value) = line.split_once('=').unwrap();
-    (
-        if name.ends_with('x') {
-            FoldType::X
-        } else {
-            FoldType::Y
-        },
-        u32::from_str(value).unwrap(),
-    )
+fn parse_fold(line: &Line, dots: &BTreeSet<(u32, u32)>) -> Result<(FoldType, u32), ParseError> {
+    let text = line.strip_prefix(&line.text, "fold along ")?;
+    let (name, value) = line.split_once(text, "=")?;
+    let fold_type = match name {
+        "x" => FoldType::X,
+        "y" => FoldType::Y,
+        _ => return Err(line.error(name, "x or y")),
+    };
+    let coordinate: u32 = line.parse(value, "number")?;
+    let farthest = dots
+        .iter()
+        .map(|(x, y)| match fold_type {
+            FoldType::X => *x,
+            FoldType::Y => *y,
+        })
+        .max()
+        .unwrap_or(0);
+    let min_coordinate = farthest.div_ceil(2).max(1);
+    if coordinate < min_coordinate {
+        return Err(line.error(value, format!("number not less than {}", min_coordinate)));
+    }
+    Ok((fold_type, coordinate))
 }
 
 #[test]
@@ -111,5 +112,17 @@ fold along y=7
 fold along x=5
 "#
     .as_bytes();
-    assert_eq!(fold_transparent_paper(buffer), 17);
+    assert_eq!(fold_transparent_paper(buffer), Ok(17));
+}
+
+#[test]
+fn parse_fold_error_test() {
+    assert_eq!(
+        fold_transparent_paper("1,0\n\nfold along x=0\n".as_bytes()),
+        Err(ParseError::new(DAY, 3, 14, "number not less than 1"))
+    );
+    assert_eq!(
+        fold_transparent_paper("0,9\n\nfold along y=4\n".as_bytes()),
+        Err(ParseError::new(DAY, 3, 14, "number not less than 5"))
+    );
 }
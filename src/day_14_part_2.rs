@@ -1,17 +1,19 @@
+use crate::parse::{Line, Lines, ParseError};
 use std::collections::BTreeMap;
 use std::io::BufRead;
 
-fn main() {
-    println!("{}", build_polymer(std::io::stdin().lock()));
-}
+const DAY: u8 = 14;
 
-fn build_polymer(mut buffer: impl BufRead) -> u64 {
-    let polymer = read_polymer(&mut buffer);
-    buffer.read_line(&mut String::new()).unwrap();
-    let rules: BTreeMap<(u8, u8), u8> = buffer
-        .lines()
-        .map(|v| parse_pair_insertion_rule(&v.unwrap()))
-        .collect();
+pub fn build_polymer(buffer: impl BufRead) -> Result<u64, ParseError> {
+    let mut lines = Lines::new(DAY, buffer);
+    let polymer = read_polymer(&mut lines)?;
+    let line = lines.next_line("empty line")?;
+    if !line.text.is_empty() {
+        return Err(line.error(&line.text, "empty line"));
+    }
+    let rules = lines
+        .map(|v| parse_pair_insertion_rule(&v?))
+        .collect::<Result<BTreeMap<(u8, u8), u8>, ParseError>>()?;
     let mut pairs: BTreeMap<(u8, u8), u64> = BTreeMap::new();
     for i in 1..polymer.len() {
         *pairs.entry((polymer[i - 1], polymer[i])).or_default() += 1;
@@ -33,31 +35,29 @@ fn build_polymer(mut buffer: impl BufRead) -> u64 {
         }
         pairs = new_pairs;
     }
-    elements.values().max().unwrap() - elements.values().min().unwrap()
+    Ok(elements.values().max().unwrap() - elements.values().min().unwrap())
 }
 
-fn read_polymer(buffer: &mut impl BufRead) -> Vec<u8> {
-    let mut line = String::new();
-    buffer.read_line(&mut line).unwrap();
-    strip_line_break(&mut line);
-    return line.as_bytes().to_vec();
+fn read_polymer(lines: &mut Lines<impl BufRead>) -> Result<Vec<u8>, ParseError> {
+    let line = lines.next_line("polymer template")?;
+    if line.text.is_empty() {
+        return Err(line.error(&line.text, "polymer template"));
+    }
+    Ok(line.text.into_bytes())
 }
 
-fn strip_line_break(line: &mut String) {
-    if line.ends_with('\n') {
-        line.pop();
+fn parse_pair_insertion_rule(line: &Line) -> Result<((u8, u8), u8), ParseError> {
+    let (pair, insert) = line.split_once(&line.text, " -> ")?;
+    if pair.len() != 2 {
+        return Err(line.error(pair, "pair of elements"));
     }
-    if line.ends_with('\r') {
-        line.pop();
+    if insert.len() != 1 {
+        return Err(line.error(insert, "single element"));
     }
-}
-
-fn parse_pair_insertion_rule(line: &str) -> ((u8, u8), u8) {
-    let (pair, insert) = line.split_once(" -> ").unwrap();
-    (
+    Ok((
         (pair.as_bytes()[0], pair.as_bytes()[1]),
         insert.as_bytes()[0],
-    )
+    ))
 }
 
 #[test]
@@ -82,5 +82,13 @@ CC -> N
 CN -> C
 "#
     .as_bytes();
-    assert_eq!(build_polymer(buffer), 2188189693529);
+    assert_eq!(build_polymer(buffer), Ok(2188189693529));
+}
+
+#[test]
+fn empty_template_test() {
+    assert_eq!(
+        build_polymer("\n\nCH -> B\n".as_bytes()),
+        Err(ParseError::new(DAY, 1, 1, "polymer template"))
+    );
 }
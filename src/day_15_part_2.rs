@@ -1,19 +1,18 @@
+use crate::grid::Grid;
+use crate::parse::{parse_digit, ParseError};
 use std::collections::BinaryHeap;
 use std::io::BufRead;
 
-fn main() {
-    println!("{}", calculate_lowest_total_risk(std::io::stdin().lock()));
-}
+const DAY: u8 = 15;
 
-fn calculate_lowest_total_risk(buffer: impl BufRead) -> i64 {
-    let grid_tile = parse_grid(buffer);
-    let mut grid = Grid {
-        values: std::iter::repeat(0)
-            .take(grid_tile.values.len() * 25)
-            .collect(),
-        width: grid_tile.width * 5,
-        height: grid_tile.height * 5,
-    };
+pub fn calculate_lowest_total_risk(buffer: impl BufRead) -> Result<i64, ParseError> {
+    let grid_tile = Grid::parse(DAY, buffer, "digit 1-9", |v| {
+        parse_digit(v).filter(|v| *v > 0)
+    })?;
+    if grid_tile.values.is_empty() {
+        return Err(ParseError::new(DAY, grid_tile.height + 1, 1, "digit 1-9"));
+    }
+    let mut grid = Grid::new(grid_tile.width * 5, grid_tile.height * 5, 0);
     for tile_y in 0..5 {
         for tile_x in 0..5 {
             for y in 0..grid_tile.height {
@@ -31,12 +30,12 @@ fn calculate_lowest_total_risk(buffer: impl BufRead) -> i64 {
             }
         }
     }
-    find_min_risk(&grid)
+    Ok(find_min_risk(&grid))
 }
 
-fn find_min_risk(grid: &Grid) -> i64 {
+fn find_min_risk(grid: &Grid<u8>) -> i64 {
     type Nodes = BinaryHeap<(i64, usize)>;
-    let mut visited: Vec<bool> = std::iter::repeat(false).take(grid.values.len()).collect();
+    let mut visited: Vec<bool> = std::iter::repeat_n(false, grid.values.len()).collect();
     visited[0] = true;
     let mut try_push = |risk, x, y, nodes: &mut Nodes| {
         let index = grid.index(x, y);
@@ -53,55 +52,13 @@ fn find_min_risk(grid: &Grid) -> i64 {
             return -node_risk;
         }
         let (node_x, node_y) = grid.position(node_index);
-        if node_x > 0 {
-            try_push(node_risk, node_x - 1, node_y, &mut nodes);
-        }
-        if node_y > 0 {
-            try_push(node_risk, node_x, node_y - 1, &mut nodes);
-        }
-        if node_x + 1 < grid.width {
-            try_push(node_risk, node_x + 1, node_y, &mut nodes);
-        }
-        if node_y + 1 < grid.height {
-            try_push(node_risk, node_x, node_y + 1, &mut nodes);
+        for (x, y) in grid.neighbours_4(node_x, node_y) {
+            try_push(node_risk, x, y, &mut nodes);
         }
     }
     0
 }
 
-struct Grid {
-    values: Vec<u8>,
-    width: usize,
-    height: usize,
-}
-
-impl Grid {
-    fn position(&self, index: usize) -> (usize, usize) {
-        (index % self.width, index / self.width)
-    }
-
-    fn index(&self, x: usize, y: usize) -> usize {
-        x + y * self.width
-    }
-}
-
-fn parse_grid(buffer: impl BufRead) -> Grid {
-    let mut values = Vec::new();
-    let mut height = 0;
-    for symbol in buffer.bytes() {
-        match symbol.unwrap() {
-            b'\r' => (),
-            b'\n' => height += 1,
-            v => values.push(v - b'0'),
-        }
-    }
-    Grid {
-        width: values.len() / height,
-        height,
-        values,
-    }
-}
-
 #[test]
 fn example_test() {
     let buffer = r#"1163751742
@@ -116,5 +73,13 @@ fn example_test() {
 2311944581
 "#
     .as_bytes();
-    assert_eq!(calculate_lowest_total_risk(buffer), 315);
+    assert_eq!(calculate_lowest_total_risk(buffer), Ok(315));
+}
+
+#[test]
+fn zero_risk_level_test() {
+    assert_eq!(
+        calculate_lowest_total_risk("19\n10\n".as_bytes()),
+        Err(ParseError::new(DAY, 2, 2, "digit 1-9"))
+    );
 }
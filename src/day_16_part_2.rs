@@ -1,140 +1,500 @@
-use std::io::{BufRead, Read};
+use crate::big_uint::BigUint;
+use crate::bits::{BitReader, BitWriter};
+use crate::parse::{Line, Lines, ParseError};
+#[cfg(test)]
+use crate::random::Random;
+#[cfg(test)]
+use proptest::prelude::*;
+use std::fmt::{Display, Formatter, Write};
+use std::io::BufRead;
 
-fn main() {
-    println!("{}", evaluate_packet(std::io::stdin().lock()));
+const DAY: u8 = 16;
+
+pub fn evaluate_packet(buffer: impl BufRead) -> Result<u64, ParseError> {
+    Ok(evaluate_packets(buffer, Precision::Checked)?
+        .to_u64()
+        .unwrap())
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Precision {
+    Checked,
+    Arbitrary,
+}
+
+pub fn evaluate_packets(buffer: impl BufRead, precision: Precision) -> Result<BigUint, ParseError> {
+    let mut result = BigUint::zero();
+    for line in Lines::new(DAY, buffer) {
+        let line = line?;
+        let (packet, _) = decode_line(&line)?;
+        let error = |e: EvalError| line.error(&line.text, format!("valid expression ({})", e));
+        result = &result + &evaluate(&packet, precision).map_err(error)?;
+        if precision == Precision::Checked && result.to_u64().is_none() {
+            return Err(error(EvalError::Overflow));
+        }
+    }
+    Ok(result)
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DisasmFormat {
+    Tree,
+    SExpression,
+}
+
+pub fn disassemble_packets(
+    buffer: impl BufRead,
+    format: DisasmFormat,
+    precision: Precision,
+) -> Result<String, ParseError> {
+    let mut result = String::new();
+    for line in Lines::new(DAY, buffer) {
+        let (packet, spans) = decode_line(&line?)?;
+        let values = evaluate_sub_packets(&packet, precision);
+        let mut nodes = spans.into_iter().zip(values.iter());
+        match format {
+            DisasmFormat::Tree => disassemble_tree(&packet, &mut nodes, 0, &mut result),
+            DisasmFormat::SExpression => {
+                disassemble_s_expression(&packet, &mut nodes, &mut result);
+                result.push('\n');
+            }
+        }
+    }
+    Ok(result)
+}
+
+pub fn decode_line(line: &Line) -> Result<(Packet, Vec<Span>), ParseError> {
+    if let Some(index) = line.text.find(|v: char| !v.is_ascii_hexdigit()) {
+        return Err(line.error(&line.text[index..], "hexadecimal digit"));
+    }
+    decode_transmission(&decode_hex_packet(&line.text)).map_err(|e| {
+        let at = &line.text[(e.offset / 4).min(line.text.len())..];
+        line.error(at, format!("valid transmission ({})", e))
+    })
+}
+
+type EvalResult = Result<BigUint, EvalError>;
+
+fn disassemble_tree<'a>(
+    packet: &Packet,
+    nodes: &mut impl Iterator<Item = (Span, &'a EvalResult)>,
+    depth: usize,
+    result: &mut String,
+) {
+    let (span, value) = nodes.next().unwrap();
+    let header = packet_header(packet, span, value);
+    writeln!(result, "{:indent$}{}", "", header, indent = depth * 2).unwrap();
+    for sub_packet in sub_packets(&packet.data).iter() {
+        disassemble_tree(sub_packet, nodes, depth + 1, result);
+    }
 }
 
-fn evaluate_packet(buffer: impl BufRead) -> u64 {
-    buffer
-        .lines()
-        .map(Result::unwrap)
-        .map(|v| decode_hex_packet(&v))
-        .map(|v| decode_bin_packet(&mut v.as_bytes()))
-        .map(|v| evaluate(&v))
-        .sum()
+fn disassemble_s_expression<'a>(
+    packet: &Packet,
+    nodes: &mut impl Iterator<Item = (Span, &'a EvalResult)>,
+    result: &mut String,
+) {
+    let (span, value) = nodes.next().unwrap();
+    let header = packet_header(packet, span, value);
+    write!(result, "({}", header).unwrap();
+    for sub_packet in sub_packets(&packet.data).iter() {
+        result.push(' ');
+        disassemble_s_expression(sub_packet, nodes, result);
+    }
+    result.push(')');
 }
 
-fn evaluate(packet: &Packet) -> u64 {
-    match &packet.data {
-        Expression::Sum(v) => v.iter().map(evaluate).sum(),
-        Expression::Product(v) => v.iter().map(evaluate).product(),
-        Expression::Minimum(v) => v.iter().map(evaluate).min().unwrap(),
-        Expression::Maximum(v) => v.iter().map(evaluate).max().unwrap(),
-        Expression::LiteralValue(v) => *v,
-        Expression::GreaterThan(v) => (evaluate(&v[0]) > evaluate(&v[1])) as u64,
-        Expression::LessThan(v) => (evaluate(&v[0]) < evaluate(&v[1])) as u64,
-        Expression::EqualTo(v) => (evaluate(&v[0]) == evaluate(&v[1])) as u64,
-        Expression::None => 0,
+fn packet_header(packet: &Packet, span: Span, value: &EvalResult) -> String {
+    let header = format!(
+        "{} v{} @{}:{}",
+        expression_name(&packet.data),
+        packet.version,
+        span.offset,
+        span.length
+    );
+    match (&packet.data, value) {
+        (Expression::LiteralValue(v), _) => format!("{} {}", header, v),
+        (_, Ok(v)) => format!("{} ={}", header, v),
+        (_, Err(e)) => format!("{} error: {}", header, e),
     }
 }
 
-fn decode_hex_packet(input: &str) -> String {
-    input
+fn expression_name(expression: &Expression) -> &'static str {
+    match expression {
+        Expression::Sum(..) => "sum",
+        Expression::Product(..) => "prod",
+        Expression::Minimum(..) => "min",
+        Expression::Maximum(..) => "max",
+        Expression::LiteralValue(..) => "lit",
+        Expression::GreaterThan(..) => "gt",
+        Expression::LessThan(..) => "lt",
+        Expression::EqualTo(..) => "eq",
+    }
+}
+
+pub fn sub_packets(expression: &Expression) -> &[Packet] {
+    match expression {
+        Expression::Sum(v)
+        | Expression::Product(v)
+        | Expression::Minimum(v)
+        | Expression::Maximum(v)
+        | Expression::GreaterThan(v)
+        | Expression::LessThan(v)
+        | Expression::EqualTo(v) => v,
+        Expression::LiteralValue(..) => &[],
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EvalError {
+    NoOperands,
+    OperandCount(usize),
+    Overflow,
+}
+
+impl Display for EvalError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvalError::NoOperands => write!(formatter, "no operands"),
+            EvalError::OperandCount(v) => write!(formatter, "{} operands instead of 2", v),
+            EvalError::Overflow => write!(formatter, "value exceeds 64 bits"),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+fn evaluate(packet: &Packet, precision: Precision) -> EvalResult {
+    evaluate_sub_packets(packet, precision).swap_remove(0)
+}
+
+fn evaluate_sub_packets(packet: &Packet, precision: Precision) -> Vec<EvalResult> {
+    let mut packets = Vec::new();
+    let mut children: Vec<Vec<usize>> = Vec::new();
+    let mut stack: Vec<(&Packet, Option<usize>)> = vec![(packet, None)];
+    while let Some((packet, parent)) = stack.pop() {
+        if let Some(parent) = parent {
+            children[parent].push(packets.len());
+        }
+        let index = Some(packets.len());
+        stack.extend(sub_packets(&packet.data).iter().rev().map(|v| (v, index)));
+        packets.push(packet);
+        children.push(Vec::new());
+    }
+    let mut values: Vec<Option<EvalResult>> = vec![None; packets.len()];
+    for i in (0..packets.len()).rev() {
+        let operands = children[i]
+            .iter()
+            .map(|v| values[*v].as_ref().unwrap().as_ref())
+            .collect::<Result<Vec<&BigUint>, &EvalError>>();
+        let value = match operands {
+            Ok(operands) => evaluate_expression(&packets[i].data, &operands, precision),
+            Err(e) => Err(e.clone()),
+        };
+        values[i] = Some(value);
+    }
+    values.into_iter().map(Option::unwrap).collect()
+}
+
+fn evaluate_expression(
+    expression: &Expression,
+    values: &[&BigUint],
+    precision: Precision,
+) -> EvalResult {
+    let pair = || match values {
+        [left, right] => Ok((left, right)),
+        _ => Err(EvalError::OperandCount(values.len())),
+    };
+    let value = match expression {
+        Expression::Sum(..) => values.iter().fold(BigUint::zero(), |a, b| &a + b),
+        Expression::Product(..) => values.iter().fold(BigUint::from(1), |a, b| &a * b),
+        Expression::Minimum(..) => (*values.iter().min().ok_or(EvalError::NoOperands)?).clone(),
+        Expression::Maximum(..) => (*values.iter().max().ok_or(EvalError::NoOperands)?).clone(),
+        Expression::LiteralValue(v) => v.clone(),
+        Expression::GreaterThan(..) => pair().map(|(l, r)| BigUint::from((l > r) as u64))?,
+        Expression::LessThan(..) => pair().map(|(l, r)| BigUint::from((l < r) as u64))?,
+        Expression::EqualTo(..) => pair().map(|(l, r)| BigUint::from((l == r) as u64))?,
+    };
+    if precision == Precision::Checked && value.to_u64().is_none() {
+        return Err(EvalError::Overflow);
+    }
+    Ok(value)
+}
+
+fn decode_hex_packet(input: &str) -> Vec<u8> {
+    let digits: Vec<u8> = input
         .chars()
-        .map(|v| format!("{:04b}", u8::from_str_radix(&String::from(v), 16).unwrap()))
+        .map(|v| v.to_digit(16).unwrap() as u8)
+        .collect();
+    digits
+        .chunks(2)
+        .map(|v| (v[0] << 4) | v.get(1).unwrap_or(&0))
         .collect()
 }
 
-#[derive(Debug, Eq, PartialEq)]
-struct Packet {
-    version: u8,
-    data: Expression,
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Packet {
+    pub version: u8,
+    pub data: Expression,
 }
 
-#[derive(Debug, Eq, PartialEq)]
-enum Expression {
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Expression {
     Sum(Vec<Packet>),
     Product(Vec<Packet>),
     Minimum(Vec<Packet>),
     Maximum(Vec<Packet>),
-    LiteralValue(u64),
+    LiteralValue(BigUint),
     GreaterThan(Vec<Packet>),
     LessThan(Vec<Packet>),
     EqualTo(Vec<Packet>),
-    None,
-}
-
-fn decode_bin_packet(buffer: &mut &[u8]) -> Packet {
-    let version = decode_bin_number::<3>(buffer) as u8;
-    let type_id = decode_bin_number::<3>(buffer) as u8;
-    match type_id {
-        4 => {
-            let mut literal_value = String::new();
-            loop {
-                let literal_group = read_bits::<5>(buffer);
-                literal_value += String::from_utf8_lossy(&literal_group[1..5]).as_ref();
-                if literal_group[0] == b'0' {
-                    break;
-                }
-            }
-            Packet {
-                version,
-                data: Expression::LiteralValue(u64::from_str_radix(&literal_value, 2).unwrap()),
-            }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Span {
+    pub offset: usize,
+    pub length: usize,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum DecodeReason {
+    Truncated,
+    NonZeroPadding,
+    TooDeep,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct DecodeError {
+    pub offset: usize,
+    pub reason: DecodeReason,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        let reason = match self.reason {
+            DecodeReason::Truncated => "truncated transmission",
+            DecodeReason::NonZeroPadding => "non-zero padding",
+            DecodeReason::TooDeep => "packets nested too deeply",
+        };
+        write!(formatter, "bit {}: {}", self.offset, reason)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+pub fn decode_transmission(bytes: &[u8]) -> Result<(Packet, Vec<Span>), DecodeError> {
+    let mut reader = BitReader::new(bytes);
+    let mut spans = Vec::new();
+    let packet = decode_bin_packet_with_spans(&mut reader, &mut spans, 0)?;
+    while reader.remaining() > 0 {
+        let offset = reader.position();
+        if reader.read_u(1) != Some(0) {
+            return Err(DecodeError {
+                offset,
+                reason: DecodeReason::NonZeroPadding,
+            });
         }
-        _ => {
-            let mut sub_packets = Vec::new();
-            let length_type_id = read_bits::<1>(buffer);
-            if length_type_id[0] == b'0' {
-                let sub_packets_length = decode_bin_number::<15>(buffer) as usize;
-                decode_sub_packets(&mut &buffer[0..sub_packets_length], &mut sub_packets);
-                buffer.consume(sub_packets_length);
-            } else if length_type_id[0] == b'1' {
-                let sub_packets_number = decode_bin_number::<11>(buffer) as usize;
-                decode_n_sub_packets(sub_packets_number, buffer, &mut sub_packets);
-            }
-            Packet {
-                version,
-                data: match type_id {
-                    0 => Expression::Sum(sub_packets),
-                    1 => Expression::Product(sub_packets),
-                    2 => Expression::Minimum(sub_packets),
-                    3 => Expression::Maximum(sub_packets),
-                    5 => Expression::GreaterThan(sub_packets),
-                    6 => Expression::LessThan(sub_packets),
-                    7 => Expression::EqualTo(sub_packets),
-                    _ => Expression::None,
-                },
+    }
+    Ok((packet, spans))
+}
+
+#[cfg(test)]
+fn decode_bin_packet(reader: &mut BitReader) -> Result<Packet, DecodeError> {
+    decode_bin_packet_with_spans(reader, &mut Vec::new(), 0)
+}
+
+const MAX_DEPTH: usize = 1000;
+
+fn decode_bin_packet_with_spans(
+    reader: &mut BitReader,
+    spans: &mut Vec<Span>,
+    depth: usize,
+) -> Result<Packet, DecodeError> {
+    if depth > MAX_DEPTH {
+        return Err(DecodeError {
+            offset: reader.position(),
+            reason: DecodeReason::TooDeep,
+        });
+    }
+    let index = spans.len();
+    spans.push(Span {
+        offset: reader.position(),
+        length: 0,
+    });
+    let packet = decode_bin_packet_data(reader, spans, depth)?;
+    spans[index].length = reader.position() - spans[index].offset;
+    Ok(packet)
+}
+
+fn decode_bin_packet_data(
+    reader: &mut BitReader,
+    spans: &mut Vec<Span>,
+    depth: usize,
+) -> Result<Packet, DecodeError> {
+    let version = read_bits(reader, 3)? as u8;
+    let type_id = read_bits(reader, 3)? as u8;
+    if type_id == 4 {
+        let mut literal_value = BigUint::zero();
+        loop {
+            let literal_group = read_bits(reader, 5)?;
+            literal_value.mul_add_small(16, (literal_group & 0xf) as u32);
+            if literal_group & 0x10 == 0 {
+                break;
             }
         }
+        return Ok(Packet {
+            version,
+            data: Expression::LiteralValue(literal_value),
+        });
+    }
+    let mut sub_packets = Vec::new();
+    if read_bits(reader, 1)? == 0 {
+        let sub_packets_length = read_bits(reader, 15)? as usize;
+        let mut sub_reader = reader.take(sub_packets_length).ok_or(DecodeError {
+            offset: reader.position(),
+            reason: DecodeReason::Truncated,
+        })?;
+        while sub_reader.remaining() > 0 {
+            sub_packets.push(decode_bin_packet_with_spans(
+                &mut sub_reader,
+                spans,
+                depth + 1,
+            )?);
+        }
+    } else {
+        let sub_packets_number = read_bits(reader, 11)?;
+        for _ in 0..sub_packets_number {
+            sub_packets.push(decode_bin_packet_with_spans(reader, spans, depth + 1)?);
+        }
     }
+    Ok(Packet {
+        version,
+        data: match type_id {
+            0 => Expression::Sum(sub_packets),
+            1 => Expression::Product(sub_packets),
+            2 => Expression::Minimum(sub_packets),
+            3 => Expression::Maximum(sub_packets),
+            5 => Expression::GreaterThan(sub_packets),
+            6 => Expression::LessThan(sub_packets),
+            _ => Expression::EqualTo(sub_packets),
+        },
+    })
 }
 
-fn decode_sub_packets(buffer: &mut &[u8], sub_packets: &mut Vec<Packet>) {
-    while !buffer.is_empty() {
-        sub_packets.push(decode_bin_packet(buffer));
-    }
+fn read_bits(reader: &mut BitReader, bits: usize) -> Result<u64, DecodeError> {
+    reader.read_u(bits).ok_or(DecodeError {
+        offset: reader.position(),
+        reason: DecodeReason::Truncated,
+    })
 }
 
-fn decode_n_sub_packets(n: usize, buffer: &mut &[u8], sub_packets: &mut Vec<Packet>) {
-    for _ in 0..n {
-        sub_packets.push(decode_bin_packet(buffer));
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LengthType {
+    Bits,
+    Count,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum EncodeError {
+    InvalidVersion(u8),
+    SubPacketsLength(usize),
+    SubPacketsCount(usize),
+}
+
+impl Display for EncodeError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EncodeError::InvalidVersion(v) => write!(formatter, "version {} exceeds 3 bits", v),
+            EncodeError::SubPacketsLength(v) => {
+                write!(formatter, "sub-packets length {} exceeds 15 bits", v)
+            }
+            EncodeError::SubPacketsCount(v) => {
+                write!(formatter, "sub-packets count {} exceeds 11 bits", v)
+            }
+        }
     }
 }
 
-fn read_bits<const BITS: usize>(buffer: &mut &[u8]) -> [u8; BITS] {
-    let mut data = [0u8; BITS];
-    buffer.read_exact(&mut data).unwrap();
-    data
+impl std::error::Error for EncodeError {}
+
+const MAX_SUB_PACKETS_LENGTH: usize = (1 << 15) - 1;
+const MAX_SUB_PACKETS_COUNT: usize = (1 << 11) - 1;
+
+pub fn encode_packet(
+    packet: &Packet,
+    choose_length_type: &mut impl FnMut(&Packet) -> LengthType,
+) -> Result<String, EncodeError> {
+    let mut writer = BitWriter::new();
+    encode_bin_packet(packet, choose_length_type, &mut writer)?;
+    Ok(writer.to_hex())
 }
 
-fn decode_bin_number<const BITS: usize>(buffer: &mut &[u8]) -> u16 {
-    let number_data: [u8; BITS] = read_bits(buffer);
-    u16::from_str_radix(String::from_utf8_lossy(&number_data).as_ref(), 2).unwrap()
+fn encode_bin_packet(
+    packet: &Packet,
+    choose_length_type: &mut impl FnMut(&Packet) -> LengthType,
+    writer: &mut BitWriter,
+) -> Result<(), EncodeError> {
+    if packet.version > 7 {
+        return Err(EncodeError::InvalidVersion(packet.version));
+    }
+    writer.write_u(packet.version as u64, 3);
+    let (type_id, sub_packets) = match &packet.data {
+        Expression::Sum(v) => (0, v),
+        Expression::Product(v) => (1, v),
+        Expression::Minimum(v) => (2, v),
+        Expression::Maximum(v) => (3, v),
+        Expression::LiteralValue(value) => {
+            writer.write_u(4, 3);
+            let groups = value.bits().div_ceil(4);
+            for group in (0..groups.max(1)).rev() {
+                writer.write_u((group > 0) as u64, 1);
+                writer.write_u(value.nibble(group) as u64, 4);
+            }
+            return Ok(());
+        }
+        Expression::GreaterThan(v) => (5, v),
+        Expression::LessThan(v) => (6, v),
+        Expression::EqualTo(v) => (7, v),
+    };
+    writer.write_u(type_id, 3);
+    let length_type = choose_length_type(packet);
+    let mut sub_writer = BitWriter::new();
+    for sub_packet in sub_packets.iter() {
+        encode_bin_packet(sub_packet, choose_length_type, &mut sub_writer)?;
+    }
+    match length_type {
+        LengthType::Bits => {
+            if sub_writer.len() > MAX_SUB_PACKETS_LENGTH {
+                return Err(EncodeError::SubPacketsLength(sub_writer.len()));
+            }
+            writer.write_u(0, 1);
+            writer.write_u(sub_writer.len() as u64, 15);
+        }
+        LengthType::Count => {
+            if sub_packets.len() > MAX_SUB_PACKETS_COUNT {
+                return Err(EncodeError::SubPacketsCount(sub_packets.len()));
+            }
+            writer.write_u(1, 1);
+            writer.write_u(sub_packets.len() as u64, 11);
+        }
+    }
+    writer.append(&sub_writer);
+    Ok(())
 }
 
 #[test]
 fn decode_hex_packet_test() {
-    assert_eq!(decode_hex_packet("D2FE28"), "110100101111111000101000");
+    assert_eq!(decode_hex_packet("D2FE28"), vec![0xD2, 0xFE, 0x28]);
+    assert_eq!(decode_hex_packet("38006F4"), vec![0x38, 0x00, 0x6F, 0x40]);
 }
 
 #[test]
 fn decode_bin_literal_packet_test() {
     assert_eq!(
-        decode_bin_packet(&mut b"110100101111111000101000".as_slice()),
+        decode_bin_packet(&mut BitReader::new(&[0b11010010, 0b11111110, 0b00101000])).unwrap(),
         Packet {
             version: 6,
-            data: Expression::LiteralValue(2021),
+            data: Expression::LiteralValue(BigUint::from(2021)),
         }
     );
 }
@@ -142,19 +502,20 @@ fn decode_bin_literal_packet_test() {
 #[test]
 fn decode_bin_operator_packet_with_limit_by_size_test() {
     assert_eq!(
-        decode_bin_packet(
-            &mut b"00111000000000000110111101000101001010010001001000000000".as_slice()
-        ),
+        decode_bin_packet(&mut BitReader::new(&[
+            0b00111000, 0b00000000, 0b01101111, 0b01000101, 0b00101001, 0b00010010, 0b00000000
+        ]))
+        .unwrap(),
         Packet {
             version: 1,
             data: Expression::LessThan(vec![
                 Packet {
                     version: 6,
-                    data: Expression::LiteralValue(10),
+                    data: Expression::LiteralValue(BigUint::from(10)),
                 },
                 Packet {
                     version: 2,
-                    data: Expression::LiteralValue(20),
+                    data: Expression::LiteralValue(BigUint::from(20)),
                 }
             ])
         }
@@ -164,23 +525,24 @@ fn decode_bin_operator_packet_with_limit_by_size_test() {
 #[test]
 fn decode_bin_operator_packet_with_limit_by_number_test() {
     assert_eq!(
-        decode_bin_packet(
-            &mut b"11101110000000001101010000001100100000100011000001100000".as_slice()
-        ),
+        decode_bin_packet(&mut BitReader::new(&[
+            0b11101110, 0b00000000, 0b11010100, 0b00001100, 0b10000010, 0b00110000, 0b01100000
+        ]))
+        .unwrap(),
         Packet {
             version: 7,
             data: Expression::Maximum(vec![
                 Packet {
                     version: 2,
-                    data: Expression::LiteralValue(1),
+                    data: Expression::LiteralValue(BigUint::from(1)),
                 },
                 Packet {
                     version: 4,
-                    data: Expression::LiteralValue(2),
+                    data: Expression::LiteralValue(BigUint::from(2)),
                 },
                 Packet {
                     version: 1,
-                    data: Expression::LiteralValue(3),
+                    data: Expression::LiteralValue(BigUint::from(3)),
                 }
             ])
         }
@@ -192,7 +554,7 @@ fn example_1_test() {
     let buffer = r#"C200B40A82
 "#
     .as_bytes();
-    assert_eq!(evaluate_packet(buffer), 3);
+    assert_eq!(evaluate_packet(buffer), Ok(3));
 }
 
 #[test]
@@ -200,7 +562,7 @@ fn example_2_test() {
     let buffer = r#"04005AC33890
 "#
     .as_bytes();
-    assert_eq!(evaluate_packet(buffer), 54);
+    assert_eq!(evaluate_packet(buffer), Ok(54));
 }
 
 #[test]
@@ -208,7 +570,7 @@ fn example_3_test() {
     let buffer = r#"880086C3E88112
 "#
     .as_bytes();
-    assert_eq!(evaluate_packet(buffer), 7);
+    assert_eq!(evaluate_packet(buffer), Ok(7));
 }
 
 #[test]
@@ -216,7 +578,7 @@ fn example_4_test() {
     let buffer = r#"CE00C43D881120
 "#
     .as_bytes();
-    assert_eq!(evaluate_packet(buffer), 9);
+    assert_eq!(evaluate_packet(buffer), Ok(9));
 }
 
 #[test]
@@ -224,7 +586,7 @@ fn example_5_test() {
     let buffer = r#"D8005AC2A8F0
 "#
     .as_bytes();
-    assert_eq!(evaluate_packet(buffer), 1);
+    assert_eq!(evaluate_packet(buffer), Ok(1));
 }
 
 #[test]
@@ -232,7 +594,7 @@ fn example_6_test() {
     let buffer = r#"F600BC2D8F
 "#
     .as_bytes();
-    assert_eq!(evaluate_packet(buffer), 0);
+    assert_eq!(evaluate_packet(buffer), Ok(0));
 }
 
 #[test]
@@ -240,7 +602,7 @@ fn example_7_test() {
     let buffer = r#"9C005AC2F8F0
 "#
     .as_bytes();
-    assert_eq!(evaluate_packet(buffer), 0);
+    assert_eq!(evaluate_packet(buffer), Ok(0));
 }
 
 #[test]
@@ -248,5 +610,288 @@ fn example_8_test() {
     let buffer = r#"9C0141080250320F1802104A08
 "#
     .as_bytes();
-    assert_eq!(evaluate_packet(buffer), 1);
+    assert_eq!(evaluate_packet(buffer), Ok(1));
+}
+
+#[test]
+fn disassemble_packets_test() {
+    let buffer = r#"9C0141080250320F1802104A08
+"#
+    .as_bytes();
+    assert_eq!(
+        disassemble_packets(buffer, DisasmFormat::Tree, Precision::Checked),
+        Ok(String::from(
+            r#"eq v4 @0:102 =1
+  sum v2 @22:40 =4
+    lit v2 @40:11 1
+    lit v4 @51:11 3
+  prod v6 @62:40 =4
+    lit v0 @80:11 2
+    lit v2 @91:11 2
+"#
+        ))
+    );
+    assert_eq!(
+        disassemble_packets(buffer, DisasmFormat::SExpression, Precision::Checked),
+        Ok(String::from(
+            "(eq v4 @0:102 =1 (sum v2 @22:40 =4 (lit v2 @40:11 1) (lit v4 @51:11 3)) \
+             (prod v6 @62:40 =4 (lit v0 @80:11 2) (lit v2 @91:11 2)))\n"
+        ))
+    );
+}
+
+#[test]
+fn decode_transmission_errors_test() {
+    let error = |offset, reason| Err(DecodeError { offset, reason });
+    assert_eq!(
+        decode_transmission(&decode_hex_packet("D2FE")),
+        error(16, DecodeReason::Truncated)
+    );
+    assert_eq!(
+        decode_transmission(&decode_hex_packet("D2FE29")),
+        error(23, DecodeReason::NonZeroPadding)
+    );
+    assert_eq!(
+        decode_transmission(&decode_hex_packet("3800FF")),
+        error(22, DecodeReason::Truncated)
+    );
+    assert_eq!(
+        evaluate_packet("D2FE29\n".as_bytes()),
+        Err(ParseError::new(
+            DAY,
+            1,
+            6,
+            "valid transmission (bit 23: non-zero padding)"
+        ))
+    );
+}
+
+#[test]
+fn decode_transmission_depth_test() {
+    let nested = |depth| {
+        let mut writer = BitWriter::new();
+        for _ in 0..depth {
+            writer.write_u(0, 6);
+            writer.write_u(1, 1);
+            writer.write_u(1, 11);
+        }
+        writer.write_u(4, 6);
+        writer.write_u(1, 5);
+        writer.to_hex()
+    };
+    let (packet, spans) = decode_transmission(&decode_hex_packet(&nested(MAX_DEPTH))).unwrap();
+    assert_eq!(spans.len(), MAX_DEPTH + 1);
+    assert_eq!(evaluate(&packet, Precision::Checked), Ok(BigUint::from(1)));
+    assert_eq!(
+        decode_transmission(&decode_hex_packet(&nested(MAX_DEPTH + 1))),
+        Err(DecodeError {
+            offset: (MAX_DEPTH + 1) * 18,
+            reason: DecodeReason::TooDeep
+        })
+    );
+}
+
+#[test]
+fn evaluate_errors_test() {
+    let literal = Packet {
+        version: 0,
+        data: Expression::LiteralValue(BigUint::from(1)),
+    };
+    let packet = |data| Packet { version: 0, data };
+    assert_eq!(
+        evaluate(&packet(Expression::Minimum(Vec::new())), Precision::Checked),
+        Err(EvalError::NoOperands)
+    );
+    assert_eq!(
+        evaluate(
+            &packet(Expression::Sum(vec![packet(Expression::GreaterThan(
+                vec![literal.clone()]
+            ))])),
+            Precision::Checked
+        ),
+        Err(EvalError::OperandCount(1))
+    );
+    let hex = encode_packet(
+        &packet(Expression::EqualTo(vec![literal.clone(); 3])),
+        &mut |_| LengthType::Count,
+    )
+    .unwrap();
+    assert_eq!(
+        evaluate_packet(format!("{}\n", hex).as_bytes()),
+        Err(ParseError::new(
+            DAY,
+            1,
+            1,
+            "valid expression (3 operands instead of 2)"
+        ))
+    );
+    assert_eq!(
+        disassemble_packets(
+            format!("{}\n", hex).as_bytes(),
+            DisasmFormat::Tree,
+            Precision::Checked
+        ),
+        Ok(String::from(
+            "eq v0 @0:51 error: 3 operands instead of 2\n  lit v0 @18:11 1\n  lit v0 @29:11 1\n  lit v0 @40:11 1\n"
+        ))
+    );
+}
+
+#[test]
+fn evaluate_precision_test() {
+    let mut writer = BitWriter::new();
+    writer.write_u(4, 6);
+    for _ in 0..17 {
+        writer.write_u(0b11111, 5);
+    }
+    writer.write_u(0, 5);
+    let (packet, _) = decode_transmission(writer.bytes()).unwrap();
+    assert_eq!(
+        evaluate(&packet, Precision::Arbitrary).map(|v| v.to_string()),
+        Ok(String::from("4722366482869645213680"))
+    );
+    assert_eq!(
+        evaluate(&packet, Precision::Checked),
+        Err(EvalError::Overflow)
+    );
+    let literal = Packet {
+        version: 0,
+        data: Expression::LiteralValue(BigUint::from(1 << 32)),
+    };
+    let product = Packet {
+        version: 0,
+        data: Expression::Product(vec![literal.clone(), literal.clone(), literal]),
+    };
+    let buffer = format!(
+        "{}\n",
+        encode_packet(&product, &mut |_| LengthType::Bits).unwrap()
+    );
+    assert_eq!(
+        evaluate_packets(buffer.as_bytes(), Precision::Arbitrary).map(|v| v.to_string()),
+        Ok(String::from("79228162514264337593543950336"))
+    );
+    assert_eq!(
+        evaluate_packet(buffer.as_bytes()),
+        Err(ParseError::new(
+            DAY,
+            1,
+            1,
+            "valid expression (value exceeds 64 bits)"
+        ))
+    );
+    let buffer = "D2FE28\n".repeat(2);
+    assert_eq!(evaluate_packet(buffer.as_bytes()), Ok(4042));
+}
+
+#[test]
+fn encode_packet_test() {
+    let literal = |version, value| Packet {
+        version,
+        data: Expression::LiteralValue(BigUint::from(value)),
+    };
+    assert_eq!(
+        encode_packet(&literal(6, 2021), &mut |_| LengthType::Bits),
+        Ok(String::from("D2FE28"))
+    );
+    let packet = Packet {
+        version: 1,
+        data: Expression::LessThan(vec![literal(6, 10), literal(2, 20)]),
+    };
+    assert_eq!(
+        encode_packet(&packet, &mut |_| LengthType::Bits),
+        Ok(String::from("38006F45291200"))
+    );
+    let packet = Packet {
+        version: 7,
+        data: Expression::Maximum(vec![literal(2, 1), literal(4, 2), literal(1, 3)]),
+    };
+    assert_eq!(
+        encode_packet(&packet, &mut |_| LengthType::Count),
+        Ok(String::from("EE00D40C823060"))
+    );
+    assert_eq!(
+        encode_packet(&literal(8, 0), &mut |_| LengthType::Bits),
+        Err(EncodeError::InvalidVersion(8))
+    );
+}
+
+#[test]
+fn encode_packet_limits_test() {
+    let packet = |n| Packet {
+        version: 0,
+        data: Expression::Sum(vec![
+            Packet {
+                version: 0,
+                data: Expression::LiteralValue(BigUint::from(u64::MAX)),
+            };
+            n
+        ]),
+    };
+    assert!(encode_packet(&packet(381), &mut |_| LengthType::Bits).is_ok());
+    assert_eq!(
+        encode_packet(&packet(382), &mut |_| LengthType::Bits),
+        Err(EncodeError::SubPacketsLength(382 * 86))
+    );
+    assert!(encode_packet(&packet(2047), &mut |_| LengthType::Count).is_ok());
+    assert_eq!(
+        encode_packet(&packet(2048), &mut |_| LengthType::Count),
+        Err(EncodeError::SubPacketsCount(2048))
+    );
+}
+
+#[cfg(test)]
+fn arbitrary_packet() -> impl Strategy<Value = Packet> {
+    let literal = (0..=7u8, prop::collection::vec(0..16u32, 1..40)).prop_map(|(version, v)| {
+        let mut value = BigUint::zero();
+        for nibble in v.iter() {
+            value.mul_add_small(16, *nibble);
+        }
+        Packet {
+            version,
+            data: Expression::LiteralValue(value),
+        }
+    });
+    literal.prop_recursive(4, 64, 5, |inner| {
+        prop_oneof![
+            (0..=7u8, 0..=3u8, prop::collection::vec(inner.clone(), 0..5)).prop_map(
+                |(version, type_id, v)| Packet {
+                    version,
+                    data: match type_id {
+                        0 => Expression::Sum(v),
+                        1 => Expression::Product(v),
+                        2 => Expression::Minimum(v),
+                        _ => Expression::Maximum(v),
+                    },
+                }
+            ),
+            (0..=7u8, 5..=7u8, inner.clone(), inner).prop_map(|(version, type_id, a, b)| {
+                Packet {
+                    version,
+                    data: match type_id {
+                        5 => Expression::GreaterThan(vec![a, b]),
+                        6 => Expression::LessThan(vec![a, b]),
+                        _ => Expression::EqualTo(vec![a, b]),
+                    },
+                }
+            }),
+        ]
+    })
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn encode_packet_roundtrip_test(packet in arbitrary_packet(), seed in any::<u64>()) {
+        let mut random = Random::new(seed);
+        let hex = encode_packet(&packet, &mut |_| {
+            if random.chance(1, 2) {
+                LengthType::Bits
+            } else {
+                LengthType::Count
+            }
+        })
+        .unwrap();
+        let (decoded, _) = decode_transmission(&decode_hex_packet(&hex)).unwrap();
+        prop_assert_eq!(decoded, packet);
+    }
 }
@@ -1,130 +1,116 @@
+use crate::parse::{Line, Lines, ParseError};
+use crate::vector::{add, Vec2};
 use std::io::BufRead;
-use std::str::FromStr;
 
-fn main() {
-    println!("{}", find_max_y_for_probe(std::io::stdin().lock()));
-    println!("SIMULATIONS={}", unsafe { SIMULATIONS });
-}
+const DAY: u8 = 17;
 
-fn find_max_y_for_probe(buffer: impl BufRead) -> i64 {
-    let line = buffer.lines().next().unwrap().unwrap();
-    let (x_area_str, y_area_str) = line.split_once(": ").unwrap().1.split_once(", ").unwrap();
-    let (min_x, max_x) = parse_segment(x_area_str);
-    let (min_y, max_y) = parse_segment(y_area_str);
+pub fn find_max_y_for_probe(buffer: impl BufRead) -> Result<Option<i64>, ParseError> {
+    let line = Lines::new(DAY, buffer).next_line("target area")?;
+    let area_str = line.strip_prefix(&line.text, "target area: ")?;
+    let (x_area_str, y_area_str) = line.split_once(area_str, ", ")?;
+    let (min_x, max_x) = parse_segment(&line, x_area_str, "x=")?;
+    let (min_y, max_y) = parse_segment(&line, y_area_str, "y=")?;
     let target_area = Rect {
-        min: Vec2 { x: min_x, y: min_y },
-        max: Vec2 { x: max_x, y: max_y },
+        min: [min_x, min_y],
+        max: [max_x, max_y],
     };
-    let initial_position = Vec2 { x: 0, y: 0 };
-    find_first_hit_velocity(initial_position, &target_area)
-        .map(|(first_velocity, max_y)| {
+    let initial_position = [0, 0];
+    Ok(
+        find_first_hit_velocity(initial_position, &target_area).map(|(first_velocity, max_y)| {
             find_max_y(initial_position, first_velocity, max_y, &target_area)
-        })
-        .unwrap()
+        }),
+    )
 }
 
-fn find_first_hit_velocity(initial_position: Vec2, target_area: &Rect) -> Option<(Vec2, i64)> {
-    let y_range = -target_area.max.x.abs()..=target_area.max.x.abs();
-    (0..=target_area.min.x.abs()).find_map(|x_shift| {
-        let x = x_shift * target_area.min.x.signum();
+fn find_first_hit_velocity(
+    initial_position: Vec2<i64>,
+    target_area: &Rect,
+) -> Option<(Vec2<i64>, i64)> {
+    let y_range = -target_area.max[0].abs()..=target_area.max[0].abs();
+    (0..=target_area.min[0].abs()).find_map(|x_shift| {
+        let x = x_shift * target_area.min[0].signum();
         y_range
             .clone()
-            .find_map(|y| {
-                simulate_probe(initial_position, Vec2 { x, y }, target_area).map(|v| (y, v))
-            })
-            .map(|(y, v)| (Vec2 { x, y }, v))
+            .find_map(|y| simulate_probe(initial_position, [x, y], target_area).map(|v| (y, v)))
+            .map(|(y, v)| ([x, y], v))
     })
 }
 
 fn find_max_y(
-    initial_position: Vec2,
-    first_velocity: Vec2,
+    initial_position: Vec2<i64>,
+    first_velocity: Vec2<i64>,
     mut max_y: i64,
     target_area: &Rect,
 ) -> i64 {
-    for y in first_velocity.y..=target_area.max.x.abs() {
-        if let Some(v) = simulate_probe(
-            initial_position,
-            Vec2 {
-                x: first_velocity.x,
-                y,
-            },
-            target_area,
-        ) {
+    for y in first_velocity[1]..=target_area.max[0].abs() {
+        if let Some(v) = simulate_probe(initial_position, [first_velocity[0], y], target_area) {
             max_y = max_y.max(v);
         }
     }
     max_y
 }
 
-#[derive(Copy, Clone)]
-struct Vec2 {
-    x: i64,
-    y: i64,
-}
-
 struct Rect {
-    min: Vec2,
-    max: Vec2,
+    min: Vec2<i64>,
+    max: Vec2<i64>,
 }
 
 impl Rect {
-    fn contains_point(&self, point: &Vec2) -> bool {
-        self.min.x <= point.x
-            && point.x <= self.max.x
-            && self.min.y <= point.y
-            && point.y <= self.max.y
+    fn contains_point(&self, point: &Vec2<i64>) -> bool {
+        self.min[0] <= point[0]
+            && point[0] <= self.max[0]
+            && self.min[1] <= point[1]
+            && point[1] <= self.max[1]
     }
 }
 
-static mut SIMULATIONS: usize = 0;
-
-fn simulate_probe(mut position: Vec2, mut velocity: Vec2, target: &Rect) -> Option<i64> {
-    unsafe {
-        SIMULATIONS += 1;
-    }
-    let mut max_y = position.y;
+fn simulate_probe(mut position: Vec2<i64>, mut velocity: Vec2<i64>, target: &Rect) -> Option<i64> {
+    let mut max_y = position[1];
     while can_reach_target_area(position, velocity, target) {
         if target.contains_point(&position) {
             return Some(max_y);
         }
-        position.x += velocity.x;
-        position.y += velocity.y;
-        match velocity.x.cmp(&0) {
-            std::cmp::Ordering::Less => velocity.x += 1,
+        position = add(position, velocity);
+        match velocity[0].cmp(&0) {
+            std::cmp::Ordering::Less => velocity[0] += 1,
             std::cmp::Ordering::Equal => (),
-            std::cmp::Ordering::Greater => velocity.x -= 1,
+            std::cmp::Ordering::Greater => velocity[0] -= 1,
         }
-        velocity.y -= 1;
-        max_y = max_y.max(position.y);
+        velocity[1] -= 1;
+        max_y = max_y.max(position[1]);
     }
     None
 }
 
-fn can_reach_target_area(position: Vec2, velocity: Vec2, target_area: &Rect) -> bool {
-    match velocity.x.cmp(&0) {
+fn can_reach_target_area(position: Vec2<i64>, velocity: Vec2<i64>, target_area: &Rect) -> bool {
+    match velocity[0].cmp(&0) {
         std::cmp::Ordering::Less => {
-            if position.x < target_area.min.x {
+            if position[0] < target_area.min[0] {
                 return false;
             }
         }
         std::cmp::Ordering::Equal => {
-            if position.x < target_area.min.x || target_area.max.x < position.x {
+            if position[0] < target_area.min[0] || target_area.max[0] < position[0] {
                 return false;
             }
         }
         std::cmp::Ordering::Greater => {
-            if position.x > target_area.max.x {
+            if position[0] > target_area.max[0] {
                 return false;
             }
         }
     }
-    velocity.y >= 0 || position.y >= target_area.min.y
+    velocity[1] >= 0 || position[1] >= target_area.min[1]
 }
 
-fn parse_segment(s: &str) -> (i64, i64) {
-    let (min, max) = s.split_once('=').unwrap().1.split_once("..").unwrap();
-    (i64::from_str(min).unwrap(), i64::from_str(max).unwrap())
+fn parse_segment(line: &Line, s: &str, prefix: &str) -> Result<(i64, i64), ParseError> {
+    let (min, max) = line.split_once(line.strip_prefix(s, prefix)?, "..")?;
+    let min_value = line.parse(min, "number")?;
+    let max_value = line.parse(max, "number")?;
+    if min_value > max_value {
+        return Err(line.error(max, format!("number not less than {}", min_value)));
+    }
+    Ok((min_value, max_value))
 }
 
 #[test]
@@ -132,7 +118,7 @@ fn example_1_test() {
     let buffer = r#"target area: x=20..30, y=-10..-5
 "#
     .as_bytes();
-    assert_eq!(find_max_y_for_probe(buffer), 45);
+    assert_eq!(find_max_y_for_probe(buffer), Ok(Some(45)));
 }
 
 #[test]
@@ -140,7 +126,7 @@ fn example_2_test() {
     let buffer = r#"target area: x=-30..-20, y=-10..-5
 "#
     .as_bytes();
-    assert_eq!(find_max_y_for_probe(buffer), 45);
+    assert_eq!(find_max_y_for_probe(buffer), Ok(Some(45)));
 }
 
 #[test]
@@ -148,7 +134,7 @@ fn example_3_test() {
     let buffer = r#"target area: x=20..30, y=5..10
 "#
     .as_bytes();
-    assert_eq!(find_max_y_for_probe(buffer), 55);
+    assert_eq!(find_max_y_for_probe(buffer), Ok(Some(55)));
 }
 
 #[test]
@@ -156,7 +142,7 @@ fn example_4_test() {
     let buffer = r#"target area: x=-30..-20, y=5..10
 "#
     .as_bytes();
-    assert_eq!(find_max_y_for_probe(buffer), 55);
+    assert_eq!(find_max_y_for_probe(buffer), Ok(Some(55)));
 }
 
 #[test]
@@ -164,5 +150,20 @@ fn example_5_test() {
     let buffer = r#"target area: x=3000..3010, y=-1010..-1000
 "#
     .as_bytes();
-    assert_eq!(find_max_y_for_probe(buffer), 509545);
+    assert_eq!(find_max_y_for_probe(buffer), Ok(Some(509545)));
+}
+
+#[test]
+fn parse_reversed_range_test() {
+    let buffer = "target area: x=91..11, y=-5..-4\n".as_bytes();
+    assert_eq!(
+        find_max_y_for_probe(buffer),
+        Err(ParseError::new(DAY, 1, 20, "number not less than 91"))
+    );
+}
+
+#[test]
+fn unreachable_target_area_test() {
+    let buffer = "target area: x=1..1, y=100..100\n".as_bytes();
+    assert_eq!(find_max_y_for_probe(buffer), Ok(None));
 }
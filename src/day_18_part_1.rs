@@ -1,8 +1,7 @@
+use crate::parse::{Line, Lines, ParseError};
 use std::io::BufRead;
 
-fn main() {
-    println!("{}", calculate_snailfish_magnitude(std::io::stdin().lock()));
-}
+const DAY: u8 = 18;
 
 #[derive(Debug, Eq, PartialEq)]
 struct Tree {
@@ -36,18 +35,22 @@ enum Value {
     },
 }
 
-fn calculate_snailfish_magnitude(buffer: impl BufRead) -> u64 {
+pub fn calculate_snailfish_magnitude(buffer: impl BufRead) -> Result<u64, ParseError> {
     let mut nodes = Vec::new();
     let mut sum_tree = None;
-    for line in buffer.lines() {
-        let tree = parse_tree(&line.unwrap(), &mut nodes);
+    let mut lines = Lines::new(DAY, buffer);
+    for line in lines.by_ref() {
+        let tree = parse_line(&line?, &mut nodes)?;
         if let Some(left) = sum_tree {
             sum_tree = Some(sum_trees(&left, &tree, &mut nodes));
         } else {
             sum_tree = Some(tree);
         }
     }
-    calculate_magnitude(sum_tree.unwrap().root, &nodes)
+    match sum_tree {
+        Some(v) => Ok(calculate_magnitude(v.root, &nodes)),
+        None => Err(lines.error_at_end("snailfish number")),
+    }
 }
 
 fn calculate_magnitude(node: usize, nodes: &[Node]) -> u64 {
@@ -125,7 +128,7 @@ fn reduce_tree(tree: &mut Tree, nodes: &mut Vec<Node>) {
     }
 }
 
-fn explode_node(pair_index: usize, nodes: &mut Vec<Node>) {
+fn explode_node(pair_index: usize, nodes: &mut [Node]) {
     let prev_regular;
     let next_regular;
     match nodes[pair_index].value.clone() {
@@ -258,6 +261,26 @@ fn find_splittable_node(tree: &Tree, nodes: &[Node]) -> Option<usize> {
     None
 }
 
+fn check_tree<'a>(line: &Line, text: &'a str) -> Result<&'a str, ParseError> {
+    if let Some(tail) = text.strip_prefix('[') {
+        let tail = check_tree(line, tail)?;
+        let tail = check_tree(line, line.strip_prefix(tail, ",")?)?;
+        line.strip_prefix(tail, "]")
+    } else if text.starts_with(|v: char| v.is_ascii_digit()) {
+        Ok(&text[1..])
+    } else {
+        Err(line.error(text, "snailfish number"))
+    }
+}
+
+fn parse_line(line: &Line, nodes: &mut Vec<Node>) -> Result<Tree, ParseError> {
+    let tail = check_tree(line, &line.text)?;
+    if !tail.is_empty() {
+        return Err(line.error(tail, "end of line"));
+    }
+    Ok(parse_tree(&line.text, nodes))
+}
+
 fn parse_tree(line: &str, nodes: &mut Vec<Node>) -> Tree {
     let mut states: Vec<(usize, Side)> = Vec::new();
     let root = nodes.len();
@@ -313,7 +336,7 @@ fn parse_tree(line: &str, nodes: &mut Vec<Node>) -> Tree {
     }
 }
 
-fn update_parent(index: usize, states: &[(usize, Side)], nodes: &mut Vec<Node>) {
+fn update_parent(index: usize, states: &[(usize, Side)], nodes: &mut [Node]) {
     if let Some((parent, side)) = states.last().cloned() {
         match &mut nodes[parent].value {
             Value::Pair { left, right } => match side {
@@ -336,11 +359,11 @@ fn node_to_string(node: usize, nodes: &[Node]) -> String {
 fn write_node(node: usize, nodes: &[Node], buffer: &mut impl std::io::Write) {
     match nodes[node].value {
         Value::Pair { left, right } => {
-            buffer.write_all(&[b'[']).unwrap();
+            buffer.write_all(b"[").unwrap();
             write_node(left, nodes, buffer);
-            buffer.write_all(&[b',']).unwrap();
+            buffer.write_all(b",").unwrap();
             write_node(right, nodes, buffer);
-            buffer.write_all(&[b']']).unwrap();
+            buffer.write_all(b"]").unwrap();
         }
         Value::Regular { number, .. } => {
             buffer.write_all(format!("{}", number).as_bytes()).unwrap();
@@ -616,7 +639,7 @@ fn example_1_test() {
 [1,1]
 "#
     .as_bytes();
-    assert_eq!(calculate_snailfish_magnitude(buffer), 1384);
+    assert_eq!(calculate_snailfish_magnitude(buffer), Ok(1384));
 }
 
 #[test]
@@ -633,7 +656,7 @@ fn example_2_test() {
 [[[[4,2],2],6],[8,7]]
 "#
     .as_bytes();
-    assert_eq!(calculate_snailfish_magnitude(buffer), 3488);
+    assert_eq!(calculate_snailfish_magnitude(buffer), Ok(3488));
 }
 
 #[test]
@@ -650,5 +673,5 @@ fn example_3_test() {
 [[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]
 "#
     .as_bytes();
-    assert_eq!(calculate_snailfish_magnitude(buffer), 4140);
+    assert_eq!(calculate_snailfish_magnitude(buffer), Ok(4140));
 }
@@ -1,11 +1,8 @@
+use crate::parse::{Line, Lines, ParseError};
+use crate::pool::{get_jobs, map_parallel};
 use std::io::BufRead;
 
-fn main() {
-    println!(
-        "{}",
-        find_max_sum_snailfish_magnitude(std::io::stdin().lock())
-    );
-}
+const DAY: u8 = 18;
 
 #[derive(Debug, Eq, PartialEq)]
 struct Tree {
@@ -39,35 +36,27 @@ enum Value {
     },
 }
 
-fn find_max_sum_snailfish_magnitude(buffer: impl BufRead) -> u64 {
+pub fn find_max_sum_snailfish_magnitude(buffer: impl BufRead) -> Result<u64, ParseError> {
     let mut nodes = Vec::new();
-    let trees: Vec<Tree> = buffer
-        .lines()
-        .map(|v| parse_tree(&v.unwrap(), &mut nodes))
-        .collect();
-    let mut max_magnitude = 0;
-    for i in 0..trees.len() - 1 {
-        let left = &trees[i];
-        for right in &trees[i + 1..trees.len()] {
-            {
-                let mut nodes_copy = nodes.clone();
-                let magnitude =
-                    calculate_magnitude(sum_trees(left, right, &mut nodes_copy).root, &nodes_copy);
-                if max_magnitude < magnitude {
-                    max_magnitude = magnitude;
-                }
-            }
-            {
-                let mut nodes_copy = nodes.clone();
-                let magnitude =
-                    calculate_magnitude(sum_trees(right, left, &mut nodes_copy).root, &nodes_copy);
-                if max_magnitude < magnitude {
-                    max_magnitude = magnitude;
-                }
+    let trees = Lines::new(DAY, buffer)
+        .map(|v| parse_line(&v?, &mut nodes))
+        .collect::<Result<Vec<Tree>, ParseError>>()?;
+    let magnitudes = map_parallel(get_jobs(), trees.len(), |i| {
+        let mut max_magnitude = 0;
+        for (j, right) in trees.iter().enumerate() {
+            if i == j {
+                continue;
             }
+            let mut nodes_copy = nodes.clone();
+            let magnitude = calculate_magnitude(
+                sum_trees(&trees[i], right, &mut nodes_copy).root,
+                &nodes_copy,
+            );
+            max_magnitude = max_magnitude.max(magnitude);
         }
-    }
-    max_magnitude
+        max_magnitude
+    });
+    Ok(magnitudes.into_iter().max().unwrap_or(0))
 }
 
 fn calculate_magnitude(node: usize, nodes: &[Node]) -> u64 {
@@ -145,7 +134,7 @@ fn reduce_tree(tree: &mut Tree, nodes: &mut Vec<Node>) {
     }
 }
 
-fn explode_node(pair_index: usize, nodes: &mut Vec<Node>) {
+fn explode_node(pair_index: usize, nodes: &mut [Node]) {
     let prev_regular;
     let next_regular;
     match nodes[pair_index].value.clone() {
@@ -278,6 +267,26 @@ fn find_splittable_node(tree: &Tree, nodes: &[Node]) -> Option<usize> {
     None
 }
 
+fn check_tree<'a>(line: &Line, text: &'a str) -> Result<&'a str, ParseError> {
+    if let Some(tail) = text.strip_prefix('[') {
+        let tail = check_tree(line, tail)?;
+        let tail = check_tree(line, line.strip_prefix(tail, ",")?)?;
+        line.strip_prefix(tail, "]")
+    } else if text.starts_with(|v: char| v.is_ascii_digit()) {
+        Ok(&text[1..])
+    } else {
+        Err(line.error(text, "snailfish number"))
+    }
+}
+
+fn parse_line(line: &Line, nodes: &mut Vec<Node>) -> Result<Tree, ParseError> {
+    let tail = check_tree(line, &line.text)?;
+    if !tail.is_empty() {
+        return Err(line.error(tail, "end of line"));
+    }
+    Ok(parse_tree(&line.text, nodes))
+}
+
 fn parse_tree(line: &str, nodes: &mut Vec<Node>) -> Tree {
     let mut states: Vec<(usize, Side)> = Vec::new();
     let root = nodes.len();
@@ -333,7 +342,7 @@ fn parse_tree(line: &str, nodes: &mut Vec<Node>) -> Tree {
     }
 }
 
-fn update_parent(index: usize, states: &[(usize, Side)], nodes: &mut Vec<Node>) {
+fn update_parent(index: usize, states: &[(usize, Side)], nodes: &mut [Node]) {
     if let Some((parent, side)) = states.last().cloned() {
         match &mut nodes[parent].value {
             Value::Pair { left, right } => match side {
@@ -356,11 +365,11 @@ fn node_to_string(node: usize, nodes: &[Node]) -> String {
 fn write_node(node: usize, nodes: &[Node], buffer: &mut impl std::io::Write) {
     match nodes[node].value {
         Value::Pair { left, right } => {
-            buffer.write_all(&[b'[']).unwrap();
+            buffer.write_all(b"[").unwrap();
             write_node(left, nodes, buffer);
-            buffer.write_all(&[b',']).unwrap();
+            buffer.write_all(b",").unwrap();
             write_node(right, nodes, buffer);
-            buffer.write_all(&[b']']).unwrap();
+            buffer.write_all(b"]").unwrap();
         }
         Value::Regular { number, .. } => {
             buffer.write_all(format!("{}", number).as_bytes()).unwrap();
@@ -644,5 +653,5 @@ fn example_test() {
 [[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]
 "#
     .as_bytes();
-    assert_eq!(find_max_sum_snailfish_magnitude(buffer), 3993);
+    assert_eq!(find_max_sum_snailfish_magnitude(buffer), Ok(3993));
 }
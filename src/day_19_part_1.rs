@@ -1,23 +1,26 @@
+use crate::parse::{Lines, ParseError};
+use crate::pool::{get_jobs, map_parallel};
+use crate::vector::{add, mat3_product, mat3_vec3_product, sub, Mat3, Vec3};
 use std::collections::{BTreeSet, HashMap};
 use std::io::BufRead;
-use std::str::FromStr;
 
-fn main() {
-    println!("{}", count_beacons(std::io::stdin().lock()));
-}
+const DAY: u8 = 19;
 
-fn count_beacons(buffer: impl BufRead) -> usize {
-    let mut scanners = parse_scanners(buffer);
+pub fn count_beacons(buffer: impl BufRead) -> Result<usize, ParseError> {
+    let mut scanners = parse_scanners(buffer)?;
     let (head, tail) = scanners.split_at_mut(1);
     let first = &mut head[0];
     let rotations = generate_rotations();
     loop {
         let mut aggregated = false;
-        for scanner in tail.iter_mut() {
-            if scanner.is_empty() {
-                continue;
+        let transforms = map_parallel(get_jobs(), tail.len(), |i| {
+            if tail[i].is_empty() {
+                return None;
             }
-            if let Some(transform) = find_relative_transformation(scanner, first, &rotations, 12) {
+            find_relative_transformation(&tail[i], first, &rotations, 12)
+        });
+        for (scanner, transform) in tail.iter_mut().zip(transforms) {
+            if let Some(transform) = transform {
                 for pos in scanner.iter() {
                     first.push(apply_transform(*pos, &transform));
                 }
@@ -31,47 +34,47 @@ fn count_beacons(buffer: impl BufRead) -> usize {
             break;
         }
     }
-    first.len()
+    Ok(first.len())
 }
 
-fn apply_transform(vec: Vec3, transform: &Transform) -> Vec3 {
-    add_vec3(mat3_vec3_product(transform.rot, vec), transform.shift)
+fn apply_transform(vec: Vec3<i16>, transform: &Transform) -> Vec3<i16> {
+    add(mat3_vec3_product(transform.rot, vec), transform.shift)
 }
 
-type Vec3 = [i16; 3];
-type Mat3 = [Vec3; 3];
-
 #[derive(Clone, Debug)]
 struct Transform<'a> {
-    shift: Vec3,
-    rot: &'a Mat3,
+    shift: Vec3<i16>,
+    rot: &'a Mat3<i16>,
 }
 
-fn parse_scanners(buffer: impl BufRead) -> Vec<Vec<Vec3>> {
-    let mut scanners: Vec<Vec<Vec3>> = Vec::new();
-    for line in buffer.lines() {
-        let line_str = line.unwrap();
-        if line_str.starts_with("--- ") {
+fn parse_scanners(buffer: impl BufRead) -> Result<Vec<Vec<Vec3<i16>>>, ParseError> {
+    let mut scanners: Vec<Vec<Vec3<i16>>> = Vec::new();
+    let mut lines = Lines::new(DAY, buffer);
+    for line in lines.by_ref() {
+        let line = line?;
+        if line.text.starts_with("--- ") {
             scanners.push(Vec::new());
-        } else if !line_str.is_empty() {
-            let mut position = [0; 3];
-            for (i, coordinate) in line_str
-                .splitn(3, ',')
-                .map(|v| i16::from_str(v).unwrap())
-                .enumerate()
-            {
-                position[i] = coordinate;
+        } else if !line.text.is_empty() {
+            let position: Vec<i16> = line.parse_numbers(&line.text, ',')?;
+            if position.len() != 3 {
+                return Err(line.error_at_end(&line.text, "3 coordinates"));
+            }
+            match scanners.last_mut() {
+                Some(scanner) => scanner.push([position[0], position[1], position[2]]),
+                None => return Err(line.error(&line.text, "scanner header")),
             }
-            scanners.last_mut().unwrap().push(position);
         }
     }
-    scanners
+    if scanners.is_empty() {
+        return Err(lines.error_at_end("scanner header"));
+    }
+    Ok(scanners)
 }
 
 fn find_relative_transformation<'a>(
-    src: &[Vec3],
-    dst: &[Vec3],
-    rotations: &'a [Mat3; 24],
+    src: &[Vec3<i16>],
+    dst: &[Vec3<i16>],
+    rotations: &'a [Mat3<i16>; 24],
     min_count: usize,
 ) -> Option<Transform<'a>> {
     for rot in rotations {
@@ -82,12 +85,17 @@ fn find_relative_transformation<'a>(
     None
 }
 
-fn find_overlap(src: &[Vec3], dst: &[Vec3], rot: &Mat3, min_count: usize) -> Option<Vec3> {
-    let mut candidates: HashMap<Vec3, usize> = HashMap::new();
+fn find_overlap(
+    src: &[Vec3<i16>],
+    dst: &[Vec3<i16>],
+    rot: &Mat3<i16>,
+    min_count: usize,
+) -> Option<Vec3<i16>> {
+    let mut candidates: HashMap<Vec3<i16>, usize> = HashMap::new();
     for src_pos in src {
         let rotated_src_pos = mat3_vec3_product(rot, *src_pos);
         for dst_pos in dst {
-            let shift = sub_vec3(*dst_pos, rotated_src_pos);
+            let shift = sub(*dst_pos, rotated_src_pos);
             let count = candidates.entry(shift).or_default();
             *count += 1;
             if *count >= min_count {
@@ -98,7 +106,7 @@ fn find_overlap(src: &[Vec3], dst: &[Vec3], rot: &Mat3, min_count: usize) -> Opt
     None
 }
 
-fn generate_rotations() -> [Mat3; 24] {
+fn generate_rotations() -> [Mat3<i16>; 24] {
     let mut unique = BTreeSet::new();
     for rot_x in 0u8..4 {
         for rot_y in 0u8..4 {
@@ -107,25 +115,25 @@ fn generate_rotations() -> [Mat3; 24] {
             }
         }
     }
-    let mut result: [Mat3; 24] = [[[0; 3]; 3]; 24];
+    let mut result: [Mat3<i16>; 24] = [[[0; 3]; 3]; 24];
     for (i, v) in unique.iter().enumerate() {
         result[i] = *v;
     }
     result
 }
 
-fn make_rotation_matrix(rot_x: u8, rot_y: u8, rot_z: u8) -> Mat3 {
-    let x: Mat3 = [
+fn make_rotation_matrix(rot_x: u8, rot_y: u8, rot_z: u8) -> Mat3<i16> {
+    let x: Mat3<i16> = [
         [1, 0, 0],
         [0, cos(rot_x), -sin(rot_x)],
         [0, sin(rot_x), cos(rot_x)],
     ];
-    let y: Mat3 = [
+    let y: Mat3<i16> = [
         [cos(rot_y), 0, sin(rot_y)],
         [0, 1, 0],
         [-sin(rot_y), 0, cos(rot_y)],
     ];
-    let z: Mat3 = [
+    let z: Mat3<i16> = [
         [cos(rot_z), -sin(rot_z), 0],
         [sin(rot_z), cos(rot_z), 0],
         [0, 0, 1],
@@ -133,44 +141,6 @@ fn make_rotation_matrix(rot_x: u8, rot_y: u8, rot_z: u8) -> Mat3 {
     mat3_product(&mat3_product(&z, &y), &x)
 }
 
-fn add_vec3(mut a: Vec3, b: Vec3) -> Vec3 {
-    for i in 0..3 {
-        a[i] += b[i];
-    }
-    a
-}
-
-fn sub_vec3(mut a: Vec3, b: Vec3) -> Vec3 {
-    for i in 0..3 {
-        a[i] -= b[i];
-    }
-    a
-}
-
-fn mat3_vec3_product(mat: &Mat3, vec: Vec3) -> Vec3 {
-    let mut result: Vec3 = [0; 3];
-    for i in 0..3 {
-        result[i] = dot_product(mat[i], vec);
-    }
-    result
-}
-
-fn mat3_product(a: &Mat3, b: &Mat3) -> Mat3 {
-    let mut result: Mat3 = [[0; 3]; 3];
-    for i in 0..3 {
-        for j in 0..3 {
-            for (k, bv) in b.iter().enumerate() {
-                result[i][j] += a[i][k] * bv[j];
-            }
-        }
-    }
-    result
-}
-
-fn dot_product(a: Vec3, b: Vec3) -> i16 {
-    a.iter().zip(b.iter()).map(|(a, b)| *a * *b).sum()
-}
-
 fn cos(v: u8) -> i16 {
     match v {
         0 => 1,
@@ -327,5 +297,5 @@ fn example_test() {
 30,-46,-14
 "#
     .as_bytes();
-    assert_eq!(count_beacons(buffer), 79);
+    assert_eq!(count_beacons(buffer), Ok(79));
 }
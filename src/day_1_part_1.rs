@@ -1,21 +1,20 @@
+use crate::parse::{Lines, ParseError};
 use std::io::BufRead;
-use std::str::FromStr;
 
-fn main() {
-    println!("{}", count_increased_height(std::io::stdin().lock()));
-}
+const DAY: u8 = 1;
 
-fn count_increased_height(buffer: impl BufRead) -> usize {
+pub fn count_increased_height(buffer: impl BufRead) -> Result<usize, ParseError> {
     let mut prev = None;
     let mut increased: usize = 0;
-    for line in buffer.lines() {
-        let height = i64::from_str(line.unwrap().as_str()).unwrap();
+    for line in Lines::new(DAY, buffer) {
+        let line = line?;
+        let height: i64 = line.parse(&line.text, "number")?;
         if let Some(prev_height) = prev {
             increased += (prev_height < height) as usize;
         }
         prev = Some(height);
     }
-    increased
+    Ok(increased)
 }
 
 #[test]
@@ -32,5 +31,5 @@ fn example_test() {
 263
 "#
     .as_bytes();
-    assert_eq!(count_increased_height(buffer), 7);
+    assert_eq!(count_increased_height(buffer), Ok(7));
 }
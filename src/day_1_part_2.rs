@@ -1,21 +1,17 @@
+use crate::parse::{Lines, ParseError};
 use std::io::BufRead;
-use std::str::FromStr;
 
-fn main() {
-    println!(
-        "{}",
-        count_increased_height_with_window(std::io::stdin().lock())
-    );
-}
+const DAY: u8 = 1;
 
-fn count_increased_height_with_window(buffer: impl BufRead) -> usize {
+pub fn count_increased_height_with_window(buffer: impl BufRead) -> Result<usize, ParseError> {
     let mut prev_window_sum = None;
     let mut window: [i64; 3] = [0; 3];
     let mut window_sum: i64 = 0;
     let mut count: usize = 0;
     let mut increased: usize = 0;
-    for line in buffer.lines() {
-        let height = i64::from_str(line.unwrap().as_str()).unwrap();
+    for line in Lines::new(DAY, buffer) {
+        let line = line?;
+        let height: i64 = line.parse(&line.text, "number")?;
         add_to_window(height, &mut window, &mut window_sum);
         count += 1;
         if count < 3 {
@@ -26,7 +22,7 @@ fn count_increased_height_with_window(buffer: impl BufRead) -> usize {
         }
         prev_window_sum = Some(window_sum);
     }
-    increased
+    Ok(increased)
 }
 
 fn add_to_window(value: i64, window: &mut [i64], sum: &mut i64) {
@@ -52,5 +48,5 @@ fn example_test() {
 263
 "#
     .as_bytes();
-    assert_eq!(count_increased_height_with_window(buffer), 5);
+    assert_eq!(count_increased_height_with_window(buffer), Ok(5));
 }
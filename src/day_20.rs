@@ -1,22 +1,13 @@
+use crate::parse::{Lines, ParseError};
 use image::{ImageBuffer, Rgb};
 use std::collections::BTreeMap;
 use std::io::BufRead;
 
-fn main() {
-    if std::env::args()
-        .nth(1)
-        .map(|v| v == "images")
-        .unwrap_or(false)
-    {
-        println!("generating images...");
-        generate_images(24 * 10, std::io::stdin().lock());
-    } else {
-        println!("{:?}", count_enhanced_light_pixels(std::io::stdin().lock()));
-    }
-}
+const DAY: u8 = 20;
+const ENHANCEMENT_SIZE: usize = 512;
 
-fn generate_images(number: usize, buffer: impl BufRead) {
-    let (enhancement, mut image) = parse_image(buffer);
+pub fn generate_images(number: usize, buffer: impl BufRead) -> Result<(), ParseError> {
+    let (enhancement, mut image) = parse_image(buffer)?;
     let mut images = Vec::with_capacity(number);
     let mut default_pixel = '0';
     images.push((image.clone(), default_pixel));
@@ -25,10 +16,11 @@ fn generate_images(number: usize, buffer: impl BufRead) {
         images.push((image.clone(), default_pixel));
     }
     let (min_x, min_y) = *images.last().unwrap().0.keys().next().unwrap();
-    let (max_x, max_y) = *images.last().unwrap().0.keys().rev().next().unwrap();
+    let (max_x, max_y) = *images.last().unwrap().0.keys().next_back().unwrap();
     for (n, (image, default_pixel)) in images.iter().enumerate() {
         save_image(n, min_x, min_y, max_x, max_y, image, *default_pixel);
     }
+    Ok(())
 }
 
 fn save_image(
@@ -63,17 +55,35 @@ fn save_image(
 const FIRST_LIMIT: usize = 2;
 const SECOND_LIMIT: usize = 50;
 
-fn count_enhanced_light_pixels(buffer: impl BufRead) -> (usize, usize) {
-    let (enhancement, mut image) = parse_image(buffer);
+pub fn count_enhanced_light_pixels_2_times(buffer: impl BufRead) -> Result<usize, ParseError> {
+    count_light_pixels_after([FIRST_LIMIT], buffer).map(|[v]| v)
+}
+
+pub fn count_enhanced_light_pixels_50_times(buffer: impl BufRead) -> Result<usize, ParseError> {
+    count_light_pixels_after([SECOND_LIMIT], buffer).map(|[v]| v)
+}
+
+pub fn count_enhanced_light_pixels(buffer: impl BufRead) -> Result<(usize, usize), ParseError> {
+    count_light_pixels_after([FIRST_LIMIT, SECOND_LIMIT], buffer)
+        .map(|[first, second]| (first, second))
+}
+
+fn count_light_pixels_after<const N: usize>(
+    limits: [usize; N],
+    buffer: impl BufRead,
+) -> Result<[usize; N], ParseError> {
+    let (enhancement, mut image) = parse_image(buffer)?;
     let mut default_pixel = '0';
-    for _ in 0..FIRST_LIMIT {
-        image = enhance_image(&enhancement, &image, &mut default_pixel);
-    }
-    let first = image.values().filter(|v| **v == b'#').count();
-    for _ in FIRST_LIMIT..SECOND_LIMIT {
-        image = enhance_image(&enhancement, &image, &mut default_pixel);
+    let mut steps = 0;
+    let mut counts = [0; N];
+    for (count, limit) in counts.iter_mut().zip(limits) {
+        for _ in steps..limit {
+            image = enhance_image(&enhancement, &image, &mut default_pixel);
+        }
+        steps = limit;
+        *count = image.values().filter(|v| **v == b'#').count();
     }
-    (first, image.values().filter(|v| **v == b'#').count())
+    Ok(counts)
 }
 
 fn enhance_image(
@@ -89,7 +99,7 @@ fn enhance_image(
         );
     }
     let (min_x, min_y) = *image.keys().next().unwrap();
-    let (max_x, max_y) = *image.keys().rev().next().unwrap();
+    let (max_x, max_y) = *image.keys().next_back().unwrap();
     let mut shift = 1;
     loop {
         let mut light_count = 0;
@@ -143,22 +153,40 @@ fn enhance_pixel(
     enhancement[usize::from_str_radix(&pixels, 2).unwrap()]
 }
 
-fn parse_image(buffer: impl BufRead) -> (Vec<u8>, BTreeMap<(isize, isize), u8>) {
+type Image = BTreeMap<(isize, isize), u8>;
+
+fn parse_image(buffer: impl BufRead) -> Result<(Vec<u8>, Image), ParseError> {
     let mut enhancement = Vec::new();
     let mut image = BTreeMap::new();
     let mut y = 0isize;
-    for line in buffer.lines() {
-        let line_str = line.unwrap();
+    let mut lines = Lines::new(DAY, buffer);
+    for line in lines.by_ref() {
+        let line = line?;
+        if let Some(index) = line.text.find(|v| v != '#' && v != '.') {
+            return Err(line.error(&line.text[index..], "\"#\" or \".\""));
+        }
         if enhancement.is_empty() {
-            enhancement = line_str.as_bytes().iter().copied().collect();
-        } else if !line_str.is_empty() {
-            for (x, byte) in line_str.as_bytes().iter().enumerate() {
+            if line.text.len() != ENHANCEMENT_SIZE {
+                return Err(line.error_at_end(
+                    &line.text,
+                    format!("{} enhancement pixels", ENHANCEMENT_SIZE),
+                ));
+            }
+            enhancement = line.text.into_bytes();
+        } else if !line.text.is_empty() {
+            for (x, byte) in line.text.as_bytes().iter().enumerate() {
                 image.insert((x as isize, y), *byte);
             }
             y += 1;
         }
     }
-    (enhancement, image)
+    if enhancement.is_empty() {
+        return Err(lines.error_at_end(format!("{} enhancement pixels", ENHANCEMENT_SIZE)));
+    }
+    if image.is_empty() {
+        return Err(lines.error_at_end("image row"));
+    }
+    Ok((enhancement, image))
 }
 
 #[test]
@@ -172,5 +200,5 @@ fn example_test() {
 ..###
 "#
     .as_bytes();
-    assert_eq!(count_enhanced_light_pixels(buffer), (35, 3351));
+    assert_eq!(count_enhanced_light_pixels(buffer), Ok((35, 3351)));
 }
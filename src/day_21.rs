@@ -1,29 +1,47 @@
+use crate::parse::{Line, Lines, ParseError};
 use std::collections::HashMap;
 use std::io::BufRead;
-use std::str::FromStr;
 
-fn main() {
-    println!("{:?}", play_dirac_dice(std::io::stdin().lock()));
+const DAY: u8 = 21;
+
+pub fn play_dirac_dice(buffer: impl BufRead) -> Result<(u64, u64), ParseError> {
+    let positions = parse_positions(buffer)?;
+    Ok((
+        play_with_deterministic_dice_from(&positions),
+        play_with_quantum_dice(&positions),
+    ))
+}
+
+pub fn play_dirac_dice_deterministic(buffer: impl BufRead) -> Result<u64, ParseError> {
+    Ok(play_with_deterministic_dice_from(&parse_positions(buffer)?))
 }
 
-fn play_dirac_dice(buffer: impl BufRead) -> (u64, u64) {
-    let positions: Vec<u8> = buffer
-        .lines()
-        .map(|v| parse_position(&v.unwrap()))
+pub fn play_dirac_dice_quantum(buffer: impl BufRead) -> Result<u64, ParseError> {
+    Ok(play_with_quantum_dice(&parse_positions(buffer)?))
+}
+
+fn parse_positions(buffer: impl BufRead) -> Result<Vec<u8>, ParseError> {
+    let mut lines = Lines::new(DAY, buffer);
+    let mut positions: Vec<u8> = Vec::new();
+    for _ in 0..2 {
+        positions.push(parse_position(
+            &lines.next_line("player starting position")?,
+        )?);
+    }
+    Ok(positions)
+}
+
+fn play_with_deterministic_dice_from(positions: &[u8]) -> u64 {
+    let mut die = DeterministicDice::default();
+    let mut players: Vec<Player> = positions
+        .iter()
+        .map(|v| Player {
+            position: *v,
+            score: 0,
+        })
         .collect();
-    let deterministic = {
-        let mut die = DeterministicDice::default();
-        let mut players: Vec<Player> = positions
-            .iter()
-            .map(|v| Player {
-                position: *v,
-                score: 0,
-            })
-            .collect();
-        play_with_deterministic_dice(&mut players, &mut die);
-        players.iter().map(|v| v.score).min().unwrap() as u64 * die.count
-    };
-    (deterministic, play_with_quantum_dice(&positions))
+    play_with_deterministic_dice(&mut players, &mut die);
+    players.iter().map(|v| v.score).min().unwrap() as u64 * die.count
 }
 
 const QUANTUM_DICE_WIN_SCORE: u8 = 21;
@@ -229,9 +247,13 @@ struct Player {
     score: u16,
 }
 
-fn parse_position(line: &str) -> u8 {
-    let (_, position) = line.split_once("starting position: ").unwrap();
-    u8::from_str(position).unwrap()
+fn parse_position(line: &Line) -> Result<u8, ParseError> {
+    let (_, position_str) = line.split_once(&line.text, "starting position: ")?;
+    let position = line.parse(position_str, "number")?;
+    if !(1..=10).contains(&position) {
+        return Err(line.error(position_str, "position from 1 to 10"));
+    }
+    Ok(position)
 }
 
 #[test]
@@ -240,5 +262,7 @@ fn example_test() {
 Player 2 starting position: 8
 "#
     .as_bytes();
-    assert_eq!(play_dirac_dice(buffer), (739785, 444356092776315));
+    assert_eq!(play_dirac_dice(buffer), Ok((739785, 444356092776315)));
+    assert_eq!(play_dirac_dice_deterministic(buffer), Ok(739785));
+    assert_eq!(play_dirac_dice_quantum(buffer), Ok(444356092776315));
 }
@@ -1,20 +1,31 @@
+use crate::parse::{Line, Lines, ParseError};
+use crate::vector::Vec3;
 use itertools::partition;
+#[cfg(test)]
+use proptest::prelude::*;
 use std::io::BufRead;
-use std::str::FromStr;
 
-fn main() {
-    println!("{:?}", reboot_reactor(std::io::stdin().lock()));
+const DAY: u8 = 22;
+
+pub fn reboot_reactor(buffer: impl BufRead) -> Result<(usize, usize), ParseError> {
+    let cubes = parse_cubes(buffer)?;
+    Ok((init_reactor_reboot(&cubes), full_reactor_reboot(&cubes)))
+}
+
+pub fn reboot_reactor_init_region(buffer: impl BufRead) -> Result<usize, ParseError> {
+    Ok(init_reactor_reboot(&parse_cubes(buffer)?))
 }
 
-fn reboot_reactor(buffer: impl BufRead) -> (usize, usize) {
-    let cubes: Vec<Cube> = buffer.lines().map(|v| parse_cube(&v.unwrap())).collect();
-    (init_reactor_reboot(&cubes), full_reactor_reboot(&cubes))
+pub fn reboot_reactor_full(buffer: impl BufRead) -> Result<usize, ParseError> {
+    Ok(full_reactor_reboot(&parse_cubes(buffer)?))
+}
+
+fn parse_cubes(buffer: impl BufRead) -> Result<Vec<Cube>, ParseError> {
+    Lines::new(DAY, buffer).map(|v| parse_cube(&v?)).collect()
 }
 
 fn init_reactor_reboot(cubes: &[Cube]) -> usize {
-    let mut reactor: Vec<State> = std::iter::repeat(State::None)
-        .take(101 * 101 * 101)
-        .collect();
+    let mut reactor: Vec<State> = std::iter::repeat_n(State::None, 101 * 101 * 101).collect();
     for cube in cubes.iter() {
         let ranges: [_; 3] = [
             cube.aabb.lower[0].max(-50)..=cube.aabb.upper[0].min(50),
@@ -24,7 +35,7 @@ fn init_reactor_reboot(cubes: &[Cube]) -> usize {
         for x in ranges[0].clone() {
             for y in ranges[1].clone() {
                 for z in ranges[2].clone() {
-                    reactor[((z + 50) * 101 * 101 + (y + 50) * 101 + x - 50) as usize] = cube.state;
+                    reactor[((z + 50) * 101 * 101 + (y + 50) * 101 + x + 50) as usize] = cube.state;
                 }
             }
         }
@@ -112,7 +123,7 @@ fn has_intersection(a: &Aabb, b: &Aabb) -> bool {
     true
 }
 
-fn get_intersection(a: &Aabb, b: &Aabb) -> (Vec3, Vec3) {
+fn get_intersection(a: &Aabb, b: &Aabb) -> (Vec3<i32>, Vec3<i32>) {
     let mut lower = a.lower;
     let mut upper = a.upper;
     for i in 0..3 {
@@ -122,7 +133,7 @@ fn get_intersection(a: &Aabb, b: &Aabb) -> (Vec3, Vec3) {
     (lower, upper)
 }
 
-fn get_area(min: &Vec3, max: &Vec3) -> i64 {
+fn get_area(min: &Vec3<i32>, max: &Vec3<i32>) -> i64 {
     let mut result = 1i64;
     for i in 0..3 {
         result *= (max[i] - min[i]) as i64 + 1;
@@ -130,35 +141,39 @@ fn get_area(min: &Vec3, max: &Vec3) -> i64 {
     result
 }
 
-type Vec3 = [i32; 3];
-
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 enum State {
     None,
     On,
     Off,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 struct Aabb {
-    lower: Vec3,
-    upper: Vec3,
+    lower: Vec3<i32>,
+    upper: Vec3<i32>,
 }
 
+#[derive(Debug)]
 struct Cube {
     state: State,
     aabb: Aabb,
 }
 
-fn parse_cube(line: &str) -> Cube {
-    let (state, tail) = line.split_once(" ").unwrap();
+fn parse_cube(line: &Line) -> Result<Cube, ParseError> {
+    let (state_str, tail) = line.split_once(&line.text, " ")?;
+    let state = match state_str {
+        "on" => State::On,
+        "off" => State::Off,
+        _ => return Err(line.error(state_str, "on or off")),
+    };
     let mut lower = [0; 3];
     let mut upper = [0; 3];
     for v in tail.split(',') {
-        let (coordinate, ranges) = v.split_once("=").unwrap();
-        let (min_str, max_str) = ranges.split_once("..").unwrap();
-        let min = i32::from_str(min_str).unwrap();
-        let max = i32::from_str(max_str).unwrap();
+        let (coordinate, ranges) = line.split_once(v, "=")?;
+        let (min_str, max_str) = line.split_once(ranges, "..")?;
+        let min = line.parse(min_str, "number")?;
+        let max = line.parse(max_str, "number")?;
         match coordinate {
             "x" => {
                 lower[0] = min;
@@ -172,17 +187,13 @@ fn parse_cube(line: &str) -> Cube {
                 lower[2] = min;
                 upper[2] = max;
             }
-            _ => (),
+            _ => return Err(line.error(coordinate, "x, y or z")),
         }
     }
-    Cube {
-        state: match state {
-            "on" => State::On,
-            "off" => State::Off,
-            _ => State::None,
-        },
+    Ok(Cube {
+        state,
         aabb: Aabb { lower, upper },
-    }
+    })
 }
 
 #[test]
@@ -193,7 +204,7 @@ off x=9..11,y=9..11,z=9..11
 on x=10..10,y=10..10,z=10..10
 "#
     .as_bytes();
-    assert_eq!(reboot_reactor(buffer), (39, 39));
+    assert_eq!(reboot_reactor(buffer), Ok((39, 39)));
 }
 
 #[test]
@@ -222,7 +233,7 @@ on x=-54112..-39298,y=-85059..-49293,z=-27449..7877
 on x=967..23432,y=45373..81175,z=27513..53682
 "#
     .as_bytes();
-    assert_eq!(reboot_reactor(buffer), (590784, 39769202357779));
+    assert_eq!(reboot_reactor(buffer), Ok((590784, 39769202357779)));
 }
 
 #[test]
@@ -289,5 +300,45 @@ on x=-53470..21291,y=-120233..-33476,z=-44150..38147
 off x=-93533..-4276,y=-16170..68771,z=-104985..-24507
 "#
     .as_bytes();
-    assert_eq!(reboot_reactor(buffer), (474140, 2758514936282235));
+    assert_eq!(reboot_reactor(buffer), Ok((474140, 2758514936282235)));
+}
+
+#[cfg(test)]
+fn init_region_cube() -> impl Strategy<Value = Cube> {
+    (
+        any::<bool>(),
+        [(-70..=60, 0..=30), (-70..=60, 0..=30), (-70..=60, 0..=30)],
+    )
+        .prop_map(|(on, ranges)| Cube {
+            state: if on { State::On } else { State::Off },
+            aabb: Aabb {
+                lower: ranges.map(|(min, _)| min),
+                upper: ranges.map(|(min, length)| min + length),
+            },
+        })
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn init_matches_full_reactor_reboot_test(
+        cubes in prop::collection::vec(init_region_cube(), 1..10),
+    ) {
+        let region = Aabb {
+            lower: [-50; 3],
+            upper: [50; 3],
+        };
+        let clipped: Vec<Cube> = cubes
+            .iter()
+            .filter(|v| has_intersection(&v.aabb, &region))
+            .map(|v| {
+                let (lower, upper) = get_intersection(&v.aabb, &region);
+                Cube {
+                    state: v.state,
+                    aabb: Aabb { lower, upper },
+                }
+            })
+            .collect();
+        prop_assert_eq!(init_reactor_reboot(&cubes), full_reactor_reboot(&clipped));
+    }
 }
@@ -1,36 +1,53 @@
+use crate::grid::Grid;
+use crate::parse::{Lines, ParseError};
+use crate::vector::{add, Vec2};
 use std::cmp::Reverse;
 use std::collections::{BTreeMap, BinaryHeap, HashMap};
 use std::io::BufRead;
 
-fn main() {
-    println!("{:?}", relocate_amphipods(std::io::stdin().lock()));
+const DAY: u8 = 23;
+const MAX_STATES: usize = 2_000_000;
+const MAX_TILES: usize = u8::MAX as usize + 1;
+
+pub fn relocate_amphipods(buffer: impl BufRead) -> Result<(u32, u32), ParseError> {
+    let input = read_input(buffer)?;
+    Ok((relocate_folded(&input)?, relocate_unfolded(&input)?))
 }
 
-const MAX_STATES: usize = 2_000_000;
+pub fn relocate_amphipods_folded(buffer: impl BufRead) -> Result<u32, ParseError> {
+    relocate_folded(&read_input(buffer)?)
+}
 
-fn relocate_amphipods(buffer: impl BufRead) -> (u32, u32) {
-    let input: String = buffer.lines().map(|v| v.unwrap() + "\n").collect();
-    let first = {
-        let World { env, state } = parse_world(input.as_bytes());
-        find_min_energy(&env, state)
-    };
-    let second = {
-        let extended_input: String = input
-            .as_bytes()
-            .lines()
-            .enumerate()
-            .map(|(i, v)| {
-                if i == 2 {
-                    v.unwrap() + "\n  #D#C#B#A#\n  #D#B#A#C#\n"
-                } else {
-                    v.unwrap() + "\n"
-                }
-            })
-            .collect();
-        let World { env, state } = parse_world(extended_input.as_bytes());
-        find_min_energy(&env, state)
-    };
-    (first, second)
+pub fn relocate_amphipods_unfolded(buffer: impl BufRead) -> Result<u32, ParseError> {
+    relocate_unfolded(&read_input(buffer)?)
+}
+
+fn read_input(buffer: impl BufRead) -> Result<String, ParseError> {
+    Lines::new(DAY, buffer)
+        .map(|v| v.map(|v| v.text + "\n"))
+        .collect()
+}
+
+fn relocate_folded(input: &str) -> Result<u32, ParseError> {
+    let World { env, state } = parse_world(input.as_bytes())?;
+    Ok(find_min_energy(&env, state))
+}
+
+fn relocate_unfolded(input: &str) -> Result<u32, ParseError> {
+    let extended_input: String = input
+        .as_bytes()
+        .lines()
+        .enumerate()
+        .map(|(i, v)| {
+            if i == 2 {
+                v.unwrap() + "\n  #D#C#B#A#\n  #D#B#A#C#\n"
+            } else {
+                v.unwrap() + "\n"
+            }
+        })
+        .collect();
+    let World { env, state } = parse_world(extended_input.as_bytes())?;
+    Ok(find_min_energy(&env, state))
 }
 
 fn find_min_energy(env: &Env, mut initial_state: State) -> u32 {
@@ -78,13 +95,13 @@ fn make_state_key(state: &State, map: &Map) -> StateKey {
         positions: state
             .amphipods
             .iter()
-            .map(|v| map.index(v.position) as u8)
+            .map(|v| get_map_index(map, v.position) as u8)
             .collect(),
     }
 }
 
 fn is_amphipod_in_final_state(amphipod: &Amphipod, env: &Env, state: &State) -> bool {
-    if let Tile::Room(room_index) = env.map.tile(amphipod.position) {
+    if let Tile::Room(room_index) = get_tile(&env.map, amphipod.position) {
         if room_index != amphipod.kind as u8 {
             return false;
         }
@@ -108,7 +125,7 @@ fn is_amphipod_in_final_state(amphipod: &Amphipod, env: &Env, state: &State) ->
     }
 }
 
-const STEPS: [Vec2; 4] = [[1, 0], [-1, 0], [0, 1], [0, -1]];
+const STEPS: [Vec2<i8>; 4] = [[1, 0], [-1, 0], [0, 1], [0, -1]];
 
 struct Ctx<'a> {
     env: &'a Env,
@@ -124,10 +141,10 @@ fn try_move_amphipod(amphipod_index: usize, state_index: usize, g_score: u32, ct
     let kind = ctx.states[state_index].amphipods[amphipod_index].kind;
     let src = ctx.states[state_index].amphipods[amphipod_index].position;
     let room_x = ctx.env.rooms[kind as usize].x;
-    let tile = ctx
-        .env
-        .map
-        .tile(ctx.states[state_index].amphipods[amphipod_index].position);
+    let tile = get_tile(
+        &ctx.env.map,
+        ctx.states[state_index].amphipods[amphipod_index].position,
+    );
     if tile == Tile::Hallway {
         for room_y in (2..ctx.env.rooms[kind as usize].depth + 2).rev() {
             let dst = [room_x, room_y];
@@ -161,7 +178,7 @@ fn try_move_amphipod(amphipod_index: usize, state_index: usize, g_score: u32, ct
     } else {
         for hallway_x in 0..ctx.env.map.width as i8 {
             let dst = [hallway_x, 1];
-            if ctx.env.map.tile(dst) != Tile::Hallway {
+            if get_tile(&ctx.env.map, dst) != Tile::Hallway {
                 continue;
             }
             if ctx.states[state_index]
@@ -188,7 +205,7 @@ fn try_move_amphipod(amphipod_index: usize, state_index: usize, g_score: u32, ct
 
 fn try_apply_transition(
     amphipod_index: usize,
-    next_position: Vec2,
+    next_position: Vec2<i8>,
     state_index: usize,
     g_score: u32,
     length: u32,
@@ -234,7 +251,7 @@ fn get_h_score(state: &State, env: &Env) -> u32 {
         .filter(|v| !v.done)
         .map(|v| {
             let room = &env.rooms[v.kind as usize];
-            let length = if env.map.tile(v.position) == Tile::Hallway {
+            let length = if get_tile(&env.map, v.position) == Tile::Hallway {
                 (v.position[0] - room.x).abs()
             } else if room.x == v.position[0] {
                 v.position[1] + 1
@@ -249,32 +266,30 @@ fn get_h_score(state: &State, env: &Env) -> u32 {
 
 fn find_shortest_path(
     kind: AmphipodType,
-    src: Vec2,
-    dst: Vec2,
+    src: Vec2<i8>,
+    dst: Vec2<i8>,
     state: &State,
     env: &Env,
 ) -> Option<u8> {
     let mut nodes = BinaryHeap::new();
-    let mut distances: Vec<u8> = std::iter::repeat(u8::MAX)
-        .take(env.map.width * env.map.height)
-        .collect();
-    let mut predecessors: Vec<u8> = std::iter::repeat(u8::MAX)
-        .take(env.map.width * env.map.height)
-        .collect();
-    distances[env.map.index(src)] = 0;
+    let mut distances: Vec<u8> =
+        std::iter::repeat_n(u8::MAX, env.map.width * env.map.height).collect();
+    let mut predecessors: Vec<u8> =
+        std::iter::repeat_n(u8::MAX, env.map.width * env.map.height).collect();
+    distances[get_map_index(&env.map, src)] = 0;
     nodes.push((Reverse(0u8), src));
     while let Some((Reverse(cost), position)) = nodes.pop() {
         if position == dst {
             return Some(cost);
         }
-        let index = env.map.index(position) as u8;
+        let index = get_map_index(&env.map, position) as u8;
         for step in STEPS {
             if !can_move_amphipod(kind, position, step, env, state) {
                 continue;
             }
-            let next_position = add_vec2(position, step);
+            let next_position = add(position, step);
             let new_cost = cost + 1;
-            let next_index = env.map.index(next_position);
+            let next_index = get_map_index(&env.map, next_position);
             if distances[next_index] <= new_cost {
                 continue;
             }
@@ -286,15 +301,23 @@ fn find_shortest_path(
     None
 }
 
-fn parse_world(buffer: impl BufRead) -> World {
+fn parse_world(buffer: impl BufRead) -> Result<World, ParseError> {
     let mut tiles = BTreeMap::new();
     let mut x = 0i8;
     let mut y = 0i8;
     let mut width = 0usize;
     let mut amphipods = Vec::new();
     let mut rooms: Vec<Room> = Vec::with_capacity(4);
-    for line in buffer.lines() {
-        for symbol in line.unwrap().as_bytes() {
+    let mut lines = Lines::new(DAY, buffer);
+    for line in lines.by_ref() {
+        let line = line?;
+        if y == i8::MAX {
+            return Err(line.error(&line.text, format!("at most {} lines", i8::MAX)));
+        }
+        for (index, symbol) in line.text.bytes().enumerate() {
+            if x == i8::MAX {
+                return Err(line.error(&line.text[index..], format!("at most {} columns", i8::MAX)));
+            }
             match symbol {
                 b'#' => {
                     tiles.insert([x, y], Tile::Wall);
@@ -303,11 +326,22 @@ fn parse_world(buffer: impl BufRead) -> World {
                     tiles.insert([x, y], Tile::Hallway);
                 }
                 b'A' | b'B' | b'C' | b'D' => {
-                    if let Some(Tile::Room(index)) = tiles.get(&[x, y - 1]).cloned() {
-                        rooms[index as usize].depth += 1;
-                        tiles.insert([x, y], Tile::Room(index));
-                    } else {
-                        tiles.insert([x, y], Tile::Room(rooms.len() as u8));
+                    match tiles.get(&[x, y - 1]).cloned() {
+                        Some(Tile::Room(index)) => {
+                            rooms[index as usize].depth += 1;
+                            tiles.insert([x, y], Tile::Room(index));
+                        }
+                        Some(Tile::Hallway) if y == 2 && rooms.len() < ENERGY_COST.len() => {
+                            tiles.insert([x, y - 1], Tile::HallwayRestricted);
+                            tiles.insert([x, y], Tile::Room(rooms.len() as u8));
+                            rooms.push(Room { x, depth: 1 });
+                        }
+                        _ => {
+                            return Err(line.error(
+                                &line.text[index..],
+                                "amphipod in a room under the hallway",
+                            ))
+                        }
                     }
                     amphipods.push(Amphipod {
                         position: [x, y],
@@ -320,12 +354,9 @@ fn parse_world(buffer: impl BufRead) -> World {
                         },
                         done: false,
                     });
-                    if matches!(tiles.get(&[x, y - 1]), Some(&Tile::Hallway)) {
-                        tiles.insert([x, y - 1], Tile::HallwayRestricted);
-                        rooms.push(Room { x, depth: 1 });
-                    }
                 }
-                _ => (),
+                b' ' => (),
+                _ => return Err(line.error(&line.text[index..], "\"#\", \".\", \" \" or amphipod")),
             }
             x += 1;
             width = width.max(x as usize);
@@ -333,40 +364,53 @@ fn parse_world(buffer: impl BufRead) -> World {
         x = 0;
         y += 1;
     }
+    if rooms.is_empty() {
+        return Err(lines.error_at_end("room under the hallway"));
+    }
+    for (room_index, room) in rooms.iter().enumerate() {
+        let count = amphipods
+            .iter()
+            .filter(|v| v.kind as usize == room_index)
+            .count();
+        if count != room.depth as usize {
+            return Err(lines.error_at_end(format!(
+                "{} amphipods of kind {}",
+                room.depth,
+                (b'A' + room_index as u8) as char
+            )));
+        }
+    }
     let height = y as usize;
-    let mut map = Map {
-        tiles: std::iter::repeat(Tile::Wall).take(width * height).collect(),
-        width,
-        height,
-    };
+    if width * height > MAX_TILES {
+        return Err(lines.error_at_end(format!("at most {} tiles", MAX_TILES)));
+    }
+    let mut map = Grid::new(width, height, Tile::Wall);
     for (position, tile) in tiles.iter() {
-        let index = map.index(*position);
-        map.tiles[index] = *tile;
+        map.set(position[0] as usize, position[1] as usize, *tile);
     }
     let env = Env { map, rooms };
     let state = State { amphipods };
-    World { env, state }
+    Ok(World { env, state })
 }
 
 fn can_move_amphipod(
     kind: AmphipodType,
-    position: Vec2,
-    step: Vec2,
+    position: Vec2<i8>,
+    step: Vec2<i8>,
     env: &Env,
     state: &State,
 ) -> bool {
-    let next_position = add_vec2(position, step);
-    if next_position[0] < 0
-        || next_position[1] < 0
-        || next_position[0] as usize >= env.map.width
-        || next_position[1] as usize >= env.map.height
+    let next_position = add(position, step);
+    if !env
+        .map
+        .contains(next_position[0] as isize, next_position[1] as isize)
     {
         return false;
     }
-    let next_tile_index = env.map.index(next_position);
-    if let Tile::Room(next_room) = env.map.tiles[next_tile_index] {
+    let next_tile_index = get_map_index(&env.map, next_position);
+    if let Tile::Room(next_room) = env.map.values[next_tile_index] {
         if next_room != kind as u8 {
-            if let Tile::Room(current_room) = env.map.tile(position) {
+            if let Tile::Room(current_room) = get_tile(&env.map, position) {
                 if next_room != current_room {
                     return false;
                 }
@@ -374,10 +418,10 @@ fn can_move_amphipod(
         }
     }
     state.amphipods.iter().all(|v| v.position != next_position)
-        && env.map.tiles[next_tile_index] != Tile::Wall
+        && env.map.values[next_tile_index] != Tile::Wall
 }
 
-fn move_amphipod(amphipod_index: usize, next_position: Vec2, env: &Env, state: &mut State) {
+fn move_amphipod(amphipod_index: usize, next_position: Vec2<i8>, env: &Env, state: &mut State) {
     state.amphipods[amphipod_index].position = next_position;
     if is_amphipod_in_final_state(&state.amphipods[amphipod_index], env, state) {
         state.amphipods[amphipod_index].done = true;
@@ -390,13 +434,6 @@ fn get_energy_cost(kind: AmphipodType) -> u32 {
     ENERGY_COST[kind as usize]
 }
 
-fn add_vec2(mut a: Vec2, b: Vec2) -> Vec2 {
-    for i in 0..2 {
-        a[i] += b[i];
-    }
-    a
-}
-
 struct World {
     env: Env,
     state: State,
@@ -422,8 +459,6 @@ struct Room {
     depth: i8,
 }
 
-type Vec2 = [i8; 2];
-
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 enum AmphipodType {
     Amber,
@@ -436,7 +471,7 @@ enum AmphipodType {
 struct Amphipod {
     kind: AmphipodType,
     done: bool,
-    position: Vec2,
+    position: Vec2<i8>,
 }
 
 #[derive(Copy, Clone, PartialEq, Debug)]
@@ -447,20 +482,14 @@ enum Tile {
     Room(u8),
 }
 
-struct Map {
-    tiles: Vec<Tile>,
-    width: usize,
-    height: usize,
-}
+type Map = Grid<Tile>;
 
-impl Map {
-    fn index(&self, position: Vec2) -> usize {
-        position[0] as usize + position[1] as usize * self.width
-    }
+fn get_map_index(map: &Map, position: Vec2<i8>) -> usize {
+    map.index(position[0] as usize, position[1] as usize)
+}
 
-    fn tile(&self, position: Vec2) -> Tile {
-        self.tiles[self.index(position)]
-    }
+fn get_tile(map: &Map, position: Vec2<i8>) -> Tile {
+    *map.get(position[0] as usize, position[1] as usize)
 }
 
 #[test]
@@ -474,7 +503,7 @@ fn example_scenario_test() {
   #########
 "#
     .as_bytes();
-    let World { env, mut state } = parse_world(buffer);
+    let World { env, mut state } = parse_world(buffer).unwrap();
     for i in 0..state.amphipods.len() {
         if is_amphipod_in_final_state(&state.amphipods[i], &env, &state) {
             state.amphipods[i].done = true;
@@ -534,7 +563,7 @@ fn find_min_energy_0_test() {
   #########
 "#
     .as_bytes();
-    let World { env, state } = parse_world(buffer);
+    let World { env, state } = parse_world(buffer).unwrap();
     assert_eq!(find_min_energy(&env, state), 0);
 }
 
@@ -547,7 +576,7 @@ fn find_min_energy_1_test() {
   #########
 "#
     .as_bytes();
-    let World { env, state } = parse_world(buffer);
+    let World { env, state } = parse_world(buffer).unwrap();
     assert_eq!(find_min_energy(&env, state), 46);
 }
 
@@ -560,7 +589,7 @@ fn find_min_energy_2_test() {
   #########
 "#
     .as_bytes();
-    let World { env, state } = parse_world(buffer);
+    let World { env, state } = parse_world(buffer).unwrap();
     assert_eq!(find_min_energy(&env, state), 448);
 }
 
@@ -573,7 +602,7 @@ fn find_min_energy_3_test() {
   #########
 "#
     .as_bytes();
-    let World { env, state } = parse_world(buffer);
+    let World { env, state } = parse_world(buffer).unwrap();
     assert_eq!(find_min_energy(&env, state), 4450);
 }
 
@@ -588,7 +617,7 @@ fn find_min_energy_4_test() {
   #########
 "#
     .as_bytes();
-    let World { env, state } = parse_world(buffer);
+    let World { env, state } = parse_world(buffer).unwrap();
     assert_eq!(find_min_energy(&env, state), 0);
 }
 
@@ -603,7 +632,7 @@ fn find_min_energy_5_test() {
   #########
 "#
     .as_bytes();
-    let World { env, state } = parse_world(buffer);
+    let World { env, state } = parse_world(buffer).unwrap();
     assert_eq!(find_min_energy(&env, state), 46);
 }
 
@@ -618,7 +647,7 @@ fn find_min_energy_6_test() {
   #########
 "#
     .as_bytes();
-    let World { env, state } = parse_world(buffer);
+    let World { env, state } = parse_world(buffer).unwrap();
     assert_eq!(find_min_energy(&env, state), 448);
 }
 
@@ -633,7 +662,7 @@ fn find_min_energy_7_test() {
   #########
 "#
     .as_bytes();
-    let World { env, state } = parse_world(buffer);
+    let World { env, state } = parse_world(buffer).unwrap();
     assert_eq!(find_min_energy(&env, state), 206);
 }
 
@@ -648,7 +677,7 @@ fn find_min_energy_8_test() {
   #########
 "#
     .as_bytes();
-    let World { env, state } = parse_world(buffer);
+    let World { env, state } = parse_world(buffer).unwrap();
     assert_eq!(find_min_energy(&env, state), 322);
 }
 
@@ -663,7 +692,7 @@ fn find_min_energy_9_test() {
   #########
 "#
     .as_bytes();
-    let World { env, state } = parse_world(buffer);
+    let World { env, state } = parse_world(buffer).unwrap();
     assert_eq!(find_min_energy(&env, state), 4714);
 }
 
@@ -678,7 +707,7 @@ fn find_min_energy_10_test() {
   #########
 "#
     .as_bytes();
-    let World { env, state } = parse_world(buffer);
+    let World { env, state } = parse_world(buffer).unwrap();
     assert_eq!(find_min_energy(&env, state), 11514);
 }
 
@@ -691,7 +720,7 @@ fn find_min_energy_part_1_example_test() {
   #########
 "#
     .as_bytes();
-    let World { env, state } = parse_world(buffer);
+    let World { env, state } = parse_world(buffer).unwrap();
     assert_eq!(find_min_energy(&env, state), 12521);
 }
 
@@ -706,7 +735,7 @@ fn find_min_energy_part_2_example_test() {
   #########
 "#
     .as_bytes();
-    let World { env, state } = parse_world(buffer);
+    let World { env, state } = parse_world(buffer).unwrap();
     assert_eq!(find_min_energy(&env, state), 44169);
 }
 
@@ -719,5 +748,36 @@ fn example_test() {
   #########
 "#
     .as_bytes();
-    assert_eq!(relocate_amphipods(buffer), (12521, 44169));
+    assert_eq!(relocate_amphipods(buffer), Ok((12521, 44169)));
+}
+
+#[test]
+fn parse_error_test() {
+    assert_eq!(
+        relocate_amphipods_folded("#A#\n#A#\n".as_bytes()),
+        Err(ParseError::new(
+            DAY,
+            1,
+            2,
+            "amphipod in a room under the hallway"
+        ))
+    );
+    assert_eq!(
+        relocate_amphipods_folded("#####\n#...#\n#####\n".as_bytes()),
+        Err(ParseError::new(DAY, 4, 1, "room under the hallway"))
+    );
+    assert_eq!(
+        relocate_amphipods_folded("#####\n#...#\n###B#\n  ###\n".as_bytes()),
+        Err(ParseError::new(DAY, 5, 1, "1 amphipods of kind A"))
+    );
+    let buffer = format!("{}\n", " ".repeat(200));
+    assert_eq!(
+        relocate_amphipods_folded(buffer.as_bytes()),
+        Err(ParseError::new(DAY, 1, 128, "at most 127 columns"))
+    );
+    let buffer = "#\n".repeat(130);
+    assert_eq!(
+        relocate_amphipods_folded(buffer.as_bytes()),
+        Err(ParseError::new(DAY, 128, 1, "at most 127 lines"))
+    );
 }
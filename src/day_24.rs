@@ -1,93 +1,490 @@
-use std::collections::{HashMap, HashSet, VecDeque};
-use std::io::{BufRead, Read};
+use crate::parse::{parse_digit, Line, Lines, ParseError};
+use crate::random::Random;
+#[cfg(test)]
+use proptest::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter, Write};
+use std::io::BufRead;
+use std::ops::RangeInclusive;
+use std::rc::Rc;
 use std::str::FromStr;
 
-fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() >= 3 && args[1] == "execute" {
-        let program = compile_program(std::io::stdin().lock()).unwrap();
-        let mut alu = Alu::default();
-        println!(
-            "{:?}",
-            execute_program(args[2].as_bytes(), &program, &mut alu)
-        );
-        println!("{:?}", alu);
-    } else if args.len() >= 2 && args[1] == "flow" {
-        let program = compile_program(std::io::stdin().lock()).unwrap();
-        let data_flow = build_data_flow(&program);
-        let mut buffer = Vec::new();
-        dot::render(&data_flow, &mut buffer).unwrap();
-        println!("{}", String::from_utf8(buffer).unwrap());
-    } else if args.len() >= 2 && args[1] == "optimize" {
-        let program = compile_program(std::io::stdin().lock()).unwrap();
-        let mut data_flow = build_data_flow(&program);
-        propagate_constants(&mut data_flow);
-        let optimized = generate_program(&data_flow);
-        print!("{}", program_to_string(&optimized));
-    } else {
-        println!("{:?}", find_the_meaning_of_monad(std::io::stdin().lock()));
-    }
+const DAY: u8 = 24;
+
+const MAX_DIGITS: usize = 19;
+
+pub fn find_the_meaning_of_monad(buffer: impl BufRead) -> Result<Option<(u64, u64)>, ParseError> {
+    let program = compile_program(buffer)?;
+    let numbers = find_model_numbers_by_pairs(&program)
+        .filter(|(max, min)| is_model_number(&program, *max) && is_model_number(&program, *min));
+    Ok(numbers.or_else(|| search_model_numbers(&program)))
 }
 
-fn find_the_meaning_of_monad(buffer: impl BufRead) -> (u64, u64) {
-    let program = compile_program(buffer).unwrap();
-    let mut start = 0;
-    let mut block = 0;
+pub fn find_max_model_number(buffer: impl BufRead) -> Result<Option<u64>, ParseError> {
+    let program = compile_program(buffer)?;
+    let number = find_model_numbers_by_pairs(&program)
+        .map(|v| v.0)
+        .filter(|v| is_model_number(&program, *v));
+    Ok(number.or_else(|| search_model_number(&program, b"987654321")))
+}
+
+pub fn find_min_model_number(buffer: impl BufRead) -> Result<Option<u64>, ParseError> {
+    let program = compile_program(buffer)?;
+    let number = find_model_numbers_by_pairs(&program)
+        .map(|v| v.1)
+        .filter(|v| is_model_number(&program, *v));
+    Ok(number.or_else(|| search_model_number(&program, b"123456789")))
+}
+
+pub fn find_model_numbers_by_pairs(program: &[Instruction]) -> Option<(u64, u64)> {
+    let blocks = find_blocks(program);
+    if blocks.first() != Some(&0) || blocks.len() > MAX_DIGITS {
+        return None;
+    }
     let mut stack = Vec::new();
-    let mut min_number = [b'0'; 14];
-    let mut max_number = [b'0'; 14];
-    while start < program.len() {
-        let next_inp = program
-            .iter()
-            .enumerate()
-            .skip(start + 1)
-            .find(|(_, v)| matches!(v, Instruction::Inp(..)))
-            .map(|(i, _)| i)
-            .unwrap_or(program.len());
-        let block_program = &program[start..next_inp];
-        if let Instruction::DivConst(Variable::Z, z_divisor) = &block_program[4] {
-            match z_divisor {
-                1 => {
-                    let y_term =
-                        if let Instruction::AddConst(Variable::Y, y_term) = &block_program[15] {
-                            *y_term
-                        } else {
-                            unreachable!()
-                        };
-                    stack.push((block, y_term));
-                }
-                26 => {
-                    let (prev_block, y_term) = stack.pop().unwrap();
-                    let diff = if let Instruction::AddConst(Variable::X, x_term) = &block_program[5]
-                    {
-                        y_term + *x_term
-                    } else {
-                        unreachable!();
-                    };
-                    let (i, j, shift) = if diff < 0 {
-                        (prev_block, block, -diff)
-                    } else {
-                        (block, prev_block, diff)
-                    };
-                    max_number[i] = b'9';
-                    max_number[j] = b'9' - shift as u8;
-                    min_number[i] = b'1' + shift as u8;
-                    min_number[j] = b'1';
+    let mut min_number = vec![b'0'; blocks.len()];
+    let mut max_number = vec![b'0'; blocks.len()];
+    for (block, start) in blocks.iter().enumerate() {
+        let block_program = &program[*start..];
+        match block_program.get(4)? {
+            Instruction::DivConst(Variable::Z, 1) => match block_program.get(15)? {
+                Instruction::AddConst(Variable::Y, y_term) => stack.push((block, *y_term)),
+                _ => return None,
+            },
+            Instruction::DivConst(Variable::Z, 26) => {
+                let (prev_block, y_term) = stack.pop()?;
+                let diff = match block_program.get(5)? {
+                    Instruction::AddConst(Variable::X, x_term) => y_term + *x_term,
+                    _ => return None,
+                };
+                if diff.abs() > 8 {
+                    return None;
                 }
-                _ => unreachable!(),
+                let (i, j, shift) = if diff < 0 {
+                    (prev_block, block, -diff)
+                } else {
+                    (block, prev_block, diff)
+                };
+                max_number[i] = b'9';
+                max_number[j] = b'9' - shift as u8;
+                min_number[i] = b'1' + shift as u8;
+                min_number[j] = b'1';
             }
+            _ => return None,
         }
-        start = next_inp;
-        block += 1;
     }
-    (
+    if !stack.is_empty() {
+        return None;
+    }
+    Some((
         u64::from_str(String::from_utf8_lossy(&max_number).as_ref()).unwrap(),
         u64::from_str(String::from_utf8_lossy(&min_number).as_ref()).unwrap(),
-    )
+    ))
+}
+
+pub fn search_model_numbers(program: &[Instruction]) -> Option<(u64, u64)> {
+    let max = search_model_number(program, b"987654321")?;
+    let min = search_model_number(program, b"123456789")?;
+    Some((max, min))
+}
+
+fn search_model_number(program: &[Instruction], digits: &[u8]) -> Option<u64> {
+    let blocks = find_blocks(program);
+    if blocks.is_empty() || blocks.len() > MAX_DIGITS {
+        return None;
+    }
+    let mut alu = Alu::default();
+    execute_program(&[][..], &program[..blocks[0]], &mut alu).ok()?;
+    let mut search = ModelSearch {
+        program,
+        blocks,
+        digits,
+        rejected: HashSet::new(),
+        number: Vec::new(),
+    };
+    if !search.find_digits(alu) {
+        return None;
+    }
+    u64::from_str(String::from_utf8_lossy(&search.number).as_ref()).ok()
+}
+
+struct ModelSearch<'a> {
+    program: &'a [Instruction],
+    blocks: Vec<usize>,
+    digits: &'a [u8],
+    rejected: HashSet<(usize, Alu)>,
+    number: Vec<u8>,
+}
+
+impl<'a> ModelSearch<'a> {
+    fn find_digits(&mut self, alu: Alu) -> bool {
+        let block = self.number.len();
+        if block == self.blocks.len() {
+            return alu.z == 0;
+        }
+        let start = self.blocks[block];
+        let end = self
+            .blocks
+            .get(block + 1)
+            .copied()
+            .unwrap_or(self.program.len());
+        if self.rejected.contains(&(block, alu.clone()))
+            || !find_register_ranges(&self.program[start..], alu.ranges(), &DIGIT_INPUTS)
+                [Variable::Z as usize]
+                .contains(&0)
+        {
+            return false;
+        }
+        for digit in self.digits.iter() {
+            let mut next = alu.clone();
+            let input = [*digit];
+            if execute_program(&input[..], &self.program[start..end], &mut next).is_err() {
+                continue;
+            }
+            self.number.push(*digit);
+            if self.find_digits(next) {
+                return true;
+            }
+            self.number.pop();
+        }
+        self.rejected.insert((block, alu));
+        false
+    }
+}
+
+fn find_blocks(program: &[Instruction]) -> Vec<usize> {
+    program
+        .iter()
+        .enumerate()
+        .filter(|(_, v)| matches!(v, Instruction::Inp(..)))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+fn is_model_number(program: &[Instruction], number: u64) -> bool {
+    let mut alu = Alu::default();
+    execute_program(number.to_string().as_bytes(), program, &mut alu).is_ok() && alu.z == 0
+}
+
+fn find_register_ranges(
+    program: &[Instruction],
+    mut ranges: [RangeInclusive<Value>; 4],
+    inputs: &RangeInclusive<Value>,
+) -> [RangeInclusive<Value>; 4] {
+    for instruction in program.iter() {
+        let (operation, left, right) = match instruction {
+            Instruction::Inp(variable) => {
+                ranges[*variable as usize] = inputs.clone();
+                continue;
+            }
+            Instruction::Add(l, r) => (OperationType::Add, *l, ranges[*r as usize].clone()),
+            Instruction::Mul(l, r) => (OperationType::Mul, *l, ranges[*r as usize].clone()),
+            Instruction::Div(l, r) => (OperationType::Div, *l, ranges[*r as usize].clone()),
+            Instruction::Mod(l, r) => (OperationType::Mod, *l, ranges[*r as usize].clone()),
+            Instruction::Eql(l, r) => (OperationType::Eql, *l, ranges[*r as usize].clone()),
+            Instruction::AddConst(l, r) => (OperationType::Add, *l, *r..=*r),
+            Instruction::MulConst(l, r) => (OperationType::Mul, *l, *r..=*r),
+            Instruction::DivConst(l, r) => (OperationType::Div, *l, *r..=*r),
+            Instruction::ModConst(l, r) => (OperationType::Mod, *l, *r..=*r),
+            Instruction::EqlConst(l, r) => (OperationType::Eql, *l, *r..=*r),
+        };
+        ranges[left as usize] = find_operation_range(operation, &ranges[left as usize], &right);
+    }
+    ranges
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Expression {
+    Const(Value),
+    Input(usize),
+    Operation(OperationType, Rc<Expression>, Rc<Expression>),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Constraint {
+    pub left: Rc<Expression>,
+    pub right: Rc<Expression>,
+    pub equal: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct SymbolicPath {
+    pub z: Rc<Expression>,
+    pub constraints: Vec<Constraint>,
+}
+
+struct SymbolicState {
+    position: usize,
+    registers: [Rc<Expression>; 4],
+    inputs: usize,
+    constraints: Vec<Constraint>,
+}
+
+const MAX_SYMBOLIC_PATHS: usize = 1 << 12;
+
+pub fn execute_symbolic(
+    program: &[Instruction],
+    inputs: &RangeInclusive<Value>,
+) -> Option<Vec<SymbolicPath>> {
+    let zero = Rc::new(Expression::Const(0));
+    let mut states = vec![SymbolicState {
+        position: 0,
+        registers: [zero.clone(), zero.clone(), zero.clone(), zero],
+        inputs: 0,
+        constraints: Vec::new(),
+    }];
+    let mut paths = Vec::new();
+    while let Some(mut state) = states.pop() {
+        if state.position == program.len() {
+            paths.push(SymbolicPath {
+                z: state.registers[Variable::Z as usize].clone(),
+                constraints: state.constraints,
+            });
+            continue;
+        }
+        let (operation, left, right) = match &program[state.position] {
+            Instruction::Inp(variable) => {
+                state.registers[*variable as usize] = Rc::new(Expression::Input(state.inputs));
+                state.inputs += 1;
+                state.position += 1;
+                states.push(state);
+                continue;
+            }
+            Instruction::Add(l, r) => {
+                (OperationType::Add, *l, state.registers[*r as usize].clone())
+            }
+            Instruction::Mul(l, r) => {
+                (OperationType::Mul, *l, state.registers[*r as usize].clone())
+            }
+            Instruction::Div(l, r) => {
+                (OperationType::Div, *l, state.registers[*r as usize].clone())
+            }
+            Instruction::Mod(l, r) => {
+                (OperationType::Mod, *l, state.registers[*r as usize].clone())
+            }
+            Instruction::Eql(l, r) => {
+                (OperationType::Eql, *l, state.registers[*r as usize].clone())
+            }
+            Instruction::AddConst(l, r) => (OperationType::Add, *l, Rc::new(Expression::Const(*r))),
+            Instruction::MulConst(l, r) => (OperationType::Mul, *l, Rc::new(Expression::Const(*r))),
+            Instruction::DivConst(l, r) => (OperationType::Div, *l, Rc::new(Expression::Const(*r))),
+            Instruction::ModConst(l, r) => (OperationType::Mod, *l, Rc::new(Expression::Const(*r))),
+            Instruction::EqlConst(l, r) => (OperationType::Eql, *l, Rc::new(Expression::Const(*r))),
+        };
+        let left_value = state.registers[left as usize].clone();
+        let value = make_expression(operation, left_value.clone(), right.clone(), inputs);
+        state.position += 1;
+        if !matches!(operation, OperationType::Eql) || matches!(*value, Expression::Const(..)) {
+            state.registers[left as usize] = value;
+            states.push(state);
+            continue;
+        }
+        for equal in [false, true] {
+            let mut registers = state.registers.clone();
+            registers[left as usize] = Rc::new(Expression::Const(equal as Value));
+            let ranges = registers.clone().map(|v| find_expression_range(&v, inputs));
+            if !find_register_ranges(&program[state.position..], ranges, inputs)
+                [Variable::Z as usize]
+                .contains(&0)
+            {
+                continue;
+            }
+            let mut constraints = state.constraints.clone();
+            constraints.push(Constraint {
+                left: left_value.clone(),
+                right: right.clone(),
+                equal,
+            });
+            states.push(SymbolicState {
+                position: state.position,
+                registers,
+                inputs: state.inputs,
+                constraints,
+            });
+        }
+        if paths.len() + states.len() > MAX_SYMBOLIC_PATHS {
+            return None;
+        }
+    }
+    Some(paths)
+}
+
+fn make_expression(
+    operation: OperationType,
+    left: Rc<Expression>,
+    right: Rc<Expression>,
+    inputs: &RangeInclusive<Value>,
+) -> Rc<Expression> {
+    let constant = |v: &Expression| match v {
+        Expression::Const(value) => Some(*value),
+        _ => None,
+    };
+    let (left_const, right_const) = (constant(&left), constant(&right));
+    if let (Some(a), Some(b)) = (left_const, right_const) {
+        if let Some(value) = checked_operation(operation, a, b) {
+            return Rc::new(Expression::Const(value));
+        }
+    }
+    match operation {
+        OperationType::Add | OperationType::Mul
+            if left_const.is_some() && right_const.is_none() =>
+        {
+            return make_expression(operation, right, left, inputs);
+        }
+        OperationType::Add if right_const == Some(0) => return left,
+        OperationType::Mul if right_const == Some(0) => return right,
+        OperationType::Mul | OperationType::Div if right_const == Some(1) => return left,
+        OperationType::Add | OperationType::Mul => {
+            if let (Expression::Operation(op, a, b), Some(c)) = (&*left, right_const) {
+                if *op == operation {
+                    if let Some(value) =
+                        constant(b).and_then(|d| checked_operation(operation, d, c))
+                    {
+                        return make_expression(
+                            operation,
+                            a.clone(),
+                            Rc::new(Expression::Const(value)),
+                            inputs,
+                        );
+                    }
+                }
+            }
+        }
+        OperationType::Div | OperationType::Mod if right_const.is_some_and(|v| v > 0) => {
+            let divisor = right_const.unwrap();
+            let range = find_expression_range(&left, inputs);
+            if *range.start() >= 0 && *range.end() < divisor {
+                return match operation {
+                    OperationType::Div => Rc::new(Expression::Const(0)),
+                    _ => left,
+                };
+            }
+            if let Expression::Operation(OperationType::Add, a, b) = &*left {
+                if let Expression::Operation(OperationType::Mul, c, d) = &**a {
+                    let (c_range, b_range) = (
+                        find_expression_range(c, inputs),
+                        find_expression_range(b, inputs),
+                    );
+                    if constant(d) == Some(divisor)
+                        && *c_range.start() >= 0
+                        && *b_range.start() >= 0
+                    {
+                        if matches!(operation, OperationType::Mod) {
+                            return make_expression(operation, b.clone(), right, inputs);
+                        }
+                        if *b_range.end() < divisor {
+                            return c.clone();
+                        }
+                    }
+                }
+            }
+        }
+        OperationType::Eql => {
+            if left == right {
+                return Rc::new(Expression::Const(1));
+            }
+            if is_disjoint(
+                &find_expression_range(&left, inputs),
+                &find_expression_range(&right, inputs),
+            ) {
+                return Rc::new(Expression::Const(0));
+            }
+        }
+        _ => (),
+    }
+    Rc::new(Expression::Operation(operation, left, right))
+}
+
+fn checked_operation(operation: OperationType, left: Value, right: Value) -> Option<Value> {
+    perform_operation(Arithmetic::Checked, operation, left, right).ok()
+}
+
+fn find_expression_range(
+    expression: &Expression,
+    inputs: &RangeInclusive<Value>,
+) -> RangeInclusive<Value> {
+    match expression {
+        Expression::Const(value) => *value..=*value,
+        Expression::Input(..) => inputs.clone(),
+        Expression::Operation(op, left, right) => find_operation_range(
+            *op,
+            &find_expression_range(left, inputs),
+            &find_expression_range(right, inputs),
+        ),
+    }
+}
+
+impl Expression {
+    pub fn evaluate(&self, inputs: &[Value]) -> Option<Value> {
+        match self {
+            Expression::Const(value) => Some(*value),
+            Expression::Input(index) => inputs.get(*index).copied(),
+            Expression::Operation(op, left, right) => {
+                checked_operation(*op, left.evaluate(inputs)?, right.evaluate(inputs)?)
+            }
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Expression::Operation(OperationType::Eql, ..) => 0,
+            Expression::Operation(OperationType::Add, ..) => 1,
+            Expression::Operation(..) => 2,
+            Expression::Const(..) | Expression::Input(..) => 3,
+        }
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        let (op, left, right) = match self {
+            Expression::Const(value) => return write!(formatter, "{}", value),
+            Expression::Input(index) => return write!(formatter, "in{}", index),
+            Expression::Operation(op, left, right) => (*op, left, right),
+        };
+        let precedence = self.precedence();
+        if left.precedence() < precedence
+            || (op == OperationType::Eql && precedence == 0 && left.precedence() == 0)
+        {
+            write!(formatter, "({})", left)?;
+        } else {
+            write!(formatter, "{}", left)?;
+        }
+        match (op, &**right) {
+            (OperationType::Add, Expression::Const(value)) if *value < 0 => {
+                return write!(formatter, " - {}", -(*value as i128));
+            }
+            (OperationType::Add, _) => write!(formatter, " + ")?,
+            (OperationType::Mul, _) => write!(formatter, " * ")?,
+            (OperationType::Div, _) => write!(formatter, " / ")?,
+            (OperationType::Mod, _) => write!(formatter, " % ")?,
+            (OperationType::Eql, _) => write!(formatter, " == ")?,
+        }
+        let associative = matches!(
+            (op, &**right),
+            (OperationType::Add, _)
+                | (
+                    OperationType::Mul,
+                    Expression::Operation(OperationType::Mul, ..)
+                )
+        );
+        if right.precedence() < precedence || (right.precedence() == precedence && !associative) {
+            write!(formatter, "({})", right)
+        } else {
+            write!(formatter, "{}", right)
+        }
+    }
+}
+
+impl Display for Constraint {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        let operator = if self.equal { "==" } else { "!=" };
+        write!(formatter, "{} {} {}", self.left, operator, self.right)
+    }
 }
 
-#[derive(Copy, Clone, Debug)]
-enum OperationType {
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum OperationType {
     Add,
     Mul,
     Div,
@@ -96,7 +493,7 @@ enum OperationType {
 }
 
 #[derive(Clone, Debug)]
-enum DataFlowNode {
+pub enum DataFlowNode {
     ConstInput(Variable, Value),
     Input(Variable, usize),
     RightConstOperation(OperationType, Variable, Value),
@@ -106,155 +503,197 @@ enum DataFlowNode {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
-enum Side {
+pub enum Side {
     Left,
     Right,
 }
 
 #[derive(Clone)]
-struct DataFlowEdge {
+pub struct DataFlowEdge {
     src: usize,
     dst: usize,
     side: Side,
 }
 
-struct DataFlow {
+pub struct DataFlow {
     nodes: Vec<DataFlowNode>,
     edges: Vec<DataFlowEdge>,
+    inputs: RangeInclusive<Value>,
+    arithmetic: Arithmetic,
+    ranges: Vec<RangeInclusive<Value>>,
 }
 
-impl<'a> dot::GraphWalk<'a, (usize, DataFlowNode), DataFlowEdge> for DataFlow {
-    fn nodes(&self) -> dot::Nodes<(usize, DataFlowNode)> {
-        self.nodes.iter().cloned().enumerate().collect()
-    }
-
-    fn edges(&self) -> dot::Edges<DataFlowEdge> {
-        self.edges.iter().cloned().collect()
-    }
-
-    fn source(&self, edge: &DataFlowEdge) -> (usize, DataFlowNode) {
-        (edge.src, self.nodes[edge.src].clone())
-    }
-
-    fn target(&self, edge: &DataFlowEdge) -> (usize, DataFlowNode) {
-        (edge.dst, self.nodes[edge.dst].clone())
-    }
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FlowView {
+    All,
+    Z,
+    Input(usize),
 }
 
-impl<'a> dot::Labeller<'a, (usize, DataFlowNode), DataFlowEdge> for DataFlow {
-    fn graph_id(&self) -> dot::Id {
-        dot::Id::new("data_flow").unwrap()
-    }
-
-    fn node_id(&self, node: &(usize, DataFlowNode)) -> dot::Id {
-        dot::Id::new(format!("node_{}", node.0)).unwrap()
+const DOT_LEGEND: &str = r#"    subgraph cluster_legend {
+        label="legend";
+        legend_source[label="source", shape=plaintext];
+        legend_left[label="left operand", shape=plaintext];
+        legend_right[label="right operand", shape=plaintext];
+        legend_source -> legend_left[label="Left"];
+        legend_source -> legend_right[label="Right", style=dashed];
     }
+"#;
 
-    fn node_shape(&self, (_, node): &(usize, DataFlowNode)) -> Option<dot::LabelText> {
+pub fn data_flow_to_dot(data_flow: &DataFlow, view: FlowView) -> String {
+    let nodes = &data_flow.nodes;
+    let selected = match view {
+        FlowView::All => (0..nodes.len()).collect(),
+        FlowView::Z => {
+            let out = nodes
+                .iter()
+                .position(|v| matches!(v, DataFlowNode::Out(Variable::Z)))
+                .unwrap();
+            find_reachable_nodes(out, data_flow)
+        }
+        FlowView::Input(index) => {
+            match nodes
+                .iter()
+                .position(|v| matches!(v, DataFlowNode::Input(_, i) if *i == index))
+            {
+                Some(input) => find_dependent_nodes(input, data_flow),
+                None => HashSet::new(),
+            }
+        }
+    };
+    let visible =
+        |i: usize| selected.contains(&i) && !matches!(nodes[i], DataFlowNode::ConstInput(..));
+    let sources = find_sources(data_flow);
+    let mut clusters: BTreeMap<Option<usize>, Vec<usize>> = BTreeMap::new();
+    let mut block = None;
+    for (i, node) in nodes.iter().enumerate() {
         match node {
-            DataFlowNode::Input(..) | DataFlowNode::Out(..) => {
-                Some(dot::LabelText::LabelStr("box".into()))
+            DataFlowNode::Input(_, index) => block = Some(*index),
+            DataFlowNode::Out(..) => block = None,
+            _ => (),
+        }
+        if visible(i) {
+            clusters.entry(block).or_default().push(i);
+        }
+    }
+    let mut result = format!("digraph data_flow {{\n{}", DOT_LEGEND);
+    for (block, cluster) in clusters.iter() {
+        let indent = match block {
+            Some(index) => {
+                writeln!(
+                    result,
+                    "    subgraph cluster_{} {{\n        label=\"inp {}\";",
+                    index, index
+                )
+                .unwrap();
+                "        "
             }
-            _ => None,
+            None => "    ",
+        };
+        for i in cluster.iter() {
+            let shape = match nodes[*i] {
+                DataFlowNode::Input(..) | DataFlowNode::Out(..) => ", shape=box",
+                _ => "",
+            };
+            writeln!(
+                result,
+                "{}node_{}[label={:?}{}];",
+                indent,
+                i,
+                node_to_label(data_flow, &sources, *i),
+                shape
+            )
+            .unwrap();
+        }
+        if block.is_some() {
+            result += "    }\n";
+        }
+    }
+    for edge in data_flow.edges.iter() {
+        if visible(edge.src) && visible(edge.dst) {
+            let style = match edge.side {
+                Side::Left => "",
+                Side::Right => "[style=dashed]",
+            };
+            writeln!(
+                result,
+                "    node_{} -> node_{}{};",
+                edge.src, edge.dst, style
+            )
+            .unwrap();
         }
     }
+    result + "}\n"
+}
 
-    fn node_label(&self, node: &(usize, DataFlowNode)) -> dot::LabelText {
-        let label = match node.1 {
-            DataFlowNode::ConstInput(variable, value) => {
-                format!("set {} {}", variable_to_char(variable), value)
-            }
-            DataFlowNode::Input(variable, index) => {
-                format!("inp {} : {}", variable_to_char(variable), index)
-            }
-            DataFlowNode::RightConstOperation(op, left, right) => format!(
+fn node_to_label(data_flow: &DataFlow, sources: &[[Option<usize>; 2]], i: usize) -> String {
+    let constant = |side: usize| match sources[i][side].map(|v| &data_flow.nodes[v]) {
+        Some(DataFlowNode::ConstInput(.., value)) => Some(*value),
+        _ => None,
+    };
+    let (text, mut constants) = match data_flow.nodes[i] {
+        DataFlowNode::ConstInput(variable, value) => (
+            format!("set {} {}", variable_to_char(variable), value),
+            Vec::new(),
+        ),
+        DataFlowNode::Input(variable, index) => (
+            format!("inp {} : {}", variable_to_char(variable), index),
+            Vec::new(),
+        ),
+        DataFlowNode::RightConstOperation(op, left, right) => (
+            format!(
                 "{} {} {}",
                 operation_to_string(op),
                 variable_to_char(left),
                 right
             ),
-            DataFlowNode::LeftConstOperation(op, value, left, right) => format!(
-                "{} {} {} ; {} = {}",
+            vec![(left, constant(0))],
+        ),
+        DataFlowNode::LeftConstOperation(op, value, left, right) => (
+            format!(
+                "{} {} {}",
                 operation_to_string(op),
                 variable_to_char(left),
-                variable_to_char(right),
-                variable_to_char(left),
-                value
+                variable_to_char(right)
             ),
-            DataFlowNode::Operation(op, left, right) => format!(
+            vec![(left, Some(value)), (right, constant(1))],
+        ),
+        DataFlowNode::Operation(op, left, right) => (
+            format!(
                 "{} {} {}",
                 operation_to_string(op),
                 variable_to_char(left),
                 variable_to_char(right)
             ),
-            DataFlowNode::Out(v) => format!("out {}", variable_to_char(v)),
-        };
-        dot::LabelText::LabelStr(format!("#{} {}", node.0, label).into())
-    }
-
-    fn edge_label(&self, edge: &DataFlowEdge) -> dot::LabelText {
-        dot::LabelText::LabelStr(format!("{:?}", edge.side).into())
-    }
-}
-
-impl<'a> dot::GraphWalk<'a, (usize, DataFlowNode), DataFlowEdge>
-    for (&'a DataFlow, &'a HashSet<usize>)
-{
-    fn nodes(&self) -> dot::Nodes<(usize, DataFlowNode)> {
-        self.0.nodes()
-    }
-
-    fn edges(&self) -> dot::Edges<DataFlowEdge> {
-        self.0.edges()
-    }
-
-    fn source(&self, edge: &DataFlowEdge) -> (usize, DataFlowNode) {
-        self.0.source(edge)
-    }
-
-    fn target(&self, edge: &DataFlowEdge) -> (usize, DataFlowNode) {
-        self.0.target(edge)
+            vec![(left, constant(0)), (right, constant(1))],
+        ),
+        DataFlowNode::Out(variable) => (
+            format!("out {}", variable_to_char(variable)),
+            vec![(variable, constant(0))],
+        ),
+    };
+    constants.dedup_by_key(|(variable, _)| *variable);
+    let mut label = format!("#{} {}", i, text);
+    for (variable, value) in constants.iter() {
+        if let Some(value) = value {
+            write!(label, " ; {} = {}", variable_to_char(*variable), value).unwrap();
+        }
     }
+    let range = &data_flow.ranges[i];
+    format!("{}\n[{}, {}]", label, range.start(), range.end())
 }
 
-impl<'a> dot::Labeller<'a, (usize, DataFlowNode), DataFlowEdge>
-    for (&'a DataFlow, &'a HashSet<usize>)
-{
-    fn graph_id(&self) -> dot::Id {
-        self.0.graph_id()
-    }
-
-    fn node_id(&self, node: &(usize, DataFlowNode)) -> dot::Id {
-        self.0.node_id(node)
-    }
-
-    fn node_shape(&self, node: &(usize, DataFlowNode)) -> Option<dot::LabelText> {
-        self.0.node_shape(node)
-    }
-
-    fn node_label(&self, node: &(usize, DataFlowNode)) -> dot::LabelText {
-        self.0.node_label(node)
-    }
-
-    fn edge_label(&self, edge: &DataFlowEdge) -> dot::LabelText {
-        self.0.edge_label(edge)
-    }
-
-    fn node_style(&self, _: &(usize, DataFlowNode)) -> dot::Style {
-        dot::Style::Filled
-    }
+const VARIABLE_COUNT: usize = 4;
 
-    fn node_color(&self, (index, _): &(usize, DataFlowNode)) -> Option<dot::LabelText> {
-        if self.1.contains(index) {
-            Some(dot::LabelText::LabelStr("green".into()))
-        } else {
-            Some(dot::LabelText::LabelStr("red".into()))
-        }
-    }
-}
+pub const DIGIT_INPUTS: RangeInclusive<Value> = 1..=9;
+pub const INTEGER_INPUTS: RangeInclusive<Value> = FULL_RANGE;
 
-fn build_data_flow(program: &[Instruction]) -> DataFlow {
-    let mut variables = [0; 4];
+pub fn build_data_flow(
+    program: &[Instruction],
+    inputs: RangeInclusive<Value>,
+    arithmetic: Arithmetic,
+) -> DataFlow {
+    let mut variables = [0; VARIABLE_COUNT];
     let mut nodes = Vec::new();
     for (i, node) in variables.iter_mut().enumerate() {
         *node = nodes.len();
@@ -353,35 +792,215 @@ fn build_data_flow(program: &[Instruction]) -> DataFlow {
             side: Side::Left,
         });
     }
-    DataFlow { nodes, edges }
+    let mut data_flow = DataFlow {
+        nodes,
+        edges,
+        inputs,
+        arithmetic,
+        ranges: Vec::new(),
+    };
+    data_flow.ranges = find_value_ranges(&data_flow);
+    data_flow
 }
 
-fn find_reachable_nodes(src: usize, data_flow: &DataFlow) -> HashSet<usize> {
-    let mut nodes = vec![src];
-    let mut reachable = HashSet::new();
-    let mut edges = HashMap::new();
-    for edge in data_flow.edges.iter() {
-        edges
-            .entry(edge.dst)
-            .or_insert_with(Vec::new)
-            .push(edge.src);
+const FULL_RANGE: RangeInclusive<Value> = Value::MIN..=Value::MAX;
+
+pub fn find_value_ranges(data_flow: &DataFlow) -> Vec<RangeInclusive<Value>> {
+    let sources = find_sources(data_flow);
+    let mut ranges: Vec<RangeInclusive<Value>> = Vec::with_capacity(data_flow.nodes.len());
+    for (i, node) in data_flow.nodes.iter().enumerate() {
+        let [left, right] = sources[i].map(|v| v.map(|v| ranges[v].clone()));
+        let range = match node {
+            DataFlowNode::ConstInput(.., value) => Some(*value..=*value),
+            DataFlowNode::Input(..) => Some(data_flow.inputs.clone()),
+            DataFlowNode::RightConstOperation(op, .., value) => {
+                left.map(|left| find_operation_range(*op, &left, &(*value..=*value)))
+            }
+            DataFlowNode::LeftConstOperation(op, value, ..) => {
+                right.map(|right| find_operation_range(*op, &(*value..=*value), &right))
+            }
+            DataFlowNode::Operation(op, ..) => left
+                .zip(right)
+                .map(|(left, right)| find_operation_range(*op, &left, &right)),
+            DataFlowNode::Out(..) => left,
+        };
+        ranges.push(range.unwrap_or(FULL_RANGE));
     }
-    reachable.insert(src);
-    while let Some(node) = nodes.pop() {
-        if let Some(neighbours) = edges.get(&node) {
-            for neighbour in neighbours.iter() {
-                if reachable.insert(*neighbour) {
-                    nodes.push(*neighbour);
+    ranges
+}
+
+fn find_operation_range(
+    operation: OperationType,
+    left: &RangeInclusive<Value>,
+    right: &RangeInclusive<Value>,
+) -> RangeInclusive<Value> {
+    let (a, b, c, d) = (*left.start(), *left.end(), *right.start(), *right.end());
+    let corners = |f: fn(Value, Value) -> Option<Value>, c: Value, d: Value| {
+        let values = [f(a, c), f(a, d), f(b, c), f(b, d)];
+        let values: Option<Vec<Value>> = values.iter().copied().collect();
+        values.map(|v| *v.iter().min().unwrap()..=*v.iter().max().unwrap())
+    };
+    let range = match operation {
+        OperationType::Add => a.checked_add(c).zip(b.checked_add(d)).map(|(l, r)| l..=r),
+        OperationType::Mul => corners(Value::checked_mul, c, d),
+        OperationType::Div => {
+            let negative = (c <= -1).then(|| corners(Value::checked_div, c, d.min(-1)));
+            let positive = (d >= 1).then(|| corners(Value::checked_div, c.max(1), d));
+            match (negative, positive) {
+                (Some(Some(n)), Some(Some(p))) => {
+                    Some(*n.start().min(p.start())..=*n.end().max(p.end()))
                 }
+                (Some(v), None) | (None, Some(v)) => v,
+                _ => None,
+            }
+        }
+        OperationType::Mod => {
+            if b < 0 || d < 1 {
+                None
+            } else if b < c.max(1) {
+                Some(a.max(0)..=b)
+            } else {
+                Some(0..=b.min(d - 1))
+            }
+        }
+        OperationType::Eql => {
+            if is_disjoint(left, right) {
+                Some(0..=0)
+            } else if a == b && c == d {
+                Some(1..=1)
+            } else {
+                Some(0..=1)
+            }
+        }
+    };
+    range.unwrap_or(FULL_RANGE)
+}
+
+pub fn find_possible_errors(data_flow: &DataFlow) -> Vec<ExecutionError> {
+    let sources = find_sources(data_flow);
+    let mut errors = Vec::new();
+    for (i, node) in data_flow.nodes.iter().enumerate().skip(VARIABLE_COUNT) {
+        let range = |side: usize| sources[i][side].map(|v| data_flow.ranges[v].clone());
+        let operands = match node {
+            DataFlowNode::RightConstOperation(op, .., value) => {
+                range(0).map(|left| (*op, left, *value..=*value))
             }
+            DataFlowNode::LeftConstOperation(op, value, ..) => {
+                range(1).map(|right| (*op, *value..=*value, right))
+            }
+            DataFlowNode::Operation(op, ..) => range(0).zip(range(1)).map(|(l, r)| (*op, l, r)),
+            _ => None,
+        };
+        let (op, left, right) = match operands {
+            Some(v) => v,
+            None => continue,
+        };
+        let index = i - VARIABLE_COUNT;
+        match op {
+            OperationType::Div if right.contains(&0) => {
+                errors.push(ExecutionError::DivByZero(index))
+            }
+            OperationType::Mod if *left.start() < 0 => {
+                errors.push(ExecutionError::ModNegativeDividend(index))
+            }
+            OperationType::Mod if *right.start() <= 0 => {
+                errors.push(ExecutionError::ModNonPositiveModulus(index))
+            }
+            _ if data_flow.arithmetic == Arithmetic::Checked && may_overflow(op, &left, &right) => {
+                errors.push(ExecutionError::Overflow(index))
+            }
+            _ => (),
         }
     }
-    reachable
+    errors
 }
 
-fn propagate_constants(data_flow: &mut DataFlow) {
-    let mut nodes: Vec<usize> = data_flow
-        .nodes
+fn may_overflow(
+    operation: OperationType,
+    left: &RangeInclusive<Value>,
+    right: &RangeInclusive<Value>,
+) -> bool {
+    let (a, b, c, d) = (*left.start(), *left.end(), *right.start(), *right.end());
+    match operation {
+        OperationType::Add => a.checked_add(c).is_none() || b.checked_add(d).is_none(),
+        OperationType::Mul => [(a, c), (a, d), (b, c), (b, d)]
+            .iter()
+            .any(|(l, r)| l.checked_mul(*r).is_none()),
+        OperationType::Div => a == Value::MIN && right.contains(&-1),
+        OperationType::Mod | OperationType::Eql => false,
+    }
+}
+
+fn find_fallible_nodes(data_flow: &DataFlow) -> HashSet<usize> {
+    find_possible_errors(data_flow)
+        .iter()
+        .map(|v| v.index() + VARIABLE_COUNT)
+        .collect()
+}
+
+fn find_reachable_nodes(src: usize, data_flow: &DataFlow) -> HashSet<usize> {
+    find_connected_nodes(src, data_flow, |edge| (edge.dst, edge.src))
+}
+
+fn find_dependent_nodes(src: usize, data_flow: &DataFlow) -> HashSet<usize> {
+    find_connected_nodes(src, data_flow, |edge| (edge.src, edge.dst))
+}
+
+fn find_connected_nodes(
+    src: usize,
+    data_flow: &DataFlow,
+    direction: fn(&DataFlowEdge) -> (usize, usize),
+) -> HashSet<usize> {
+    let mut nodes = vec![src];
+    let mut reachable = HashSet::new();
+    let mut edges = HashMap::new();
+    for (from, to) in data_flow.edges.iter().map(direction) {
+        edges.entry(from).or_insert_with(Vec::new).push(to);
+    }
+    reachable.insert(src);
+    while let Some(node) = nodes.pop() {
+        if let Some(neighbours) = edges.get(&node) {
+            for neighbour in neighbours.iter() {
+                if reachable.insert(*neighbour) {
+                    nodes.push(*neighbour);
+                }
+            }
+        }
+    }
+    reachable
+}
+
+pub fn propagate_constants(data_flow: &mut DataFlow) {
+    loop {
+        propagate_constant_nodes(data_flow);
+        data_flow.ranges = find_value_ranges(data_flow);
+        let fallible = find_fallible_nodes(data_flow);
+        let mut changed = false;
+        for i in 0..data_flow.nodes.len() {
+            let range = data_flow.ranges[i].clone();
+            match data_flow.nodes[i] {
+                DataFlowNode::ConstInput(..) | DataFlowNode::Input(..) | DataFlowNode::Out(..) => {
+                    continue
+                }
+                DataFlowNode::RightConstOperation(_, variable, _)
+                | DataFlowNode::LeftConstOperation(_, _, variable, _)
+                | DataFlowNode::Operation(_, variable, _) => {
+                    if range.start() == range.end() && !fallible.contains(&i) {
+                        make_constant(data_flow, i, variable, *range.start());
+                        changed = true;
+                    }
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+}
+
+fn propagate_constant_nodes(data_flow: &mut DataFlow) {
+    let mut nodes: Vec<usize> = data_flow
+        .nodes
         .iter()
         .enumerate()
         .filter(|(_, v)| matches!(v, DataFlowNode::ConstInput(..)))
@@ -404,11 +1023,10 @@ fn propagate_constants(data_flow: &mut DataFlow) {
                 match side {
                     Side::Left => match data_flow.nodes[*neighbour] {
                         DataFlowNode::RightConstOperation(op, left, right) => {
-                            data_flow.nodes[*neighbour] = DataFlowNode::ConstInput(
-                                left,
-                                perform_operation(op, node_value, right),
-                            );
-                            nodes.push(*neighbour);
+                            if let Some(value) = checked_operation(op, node_value, right) {
+                                data_flow.nodes[*neighbour] = DataFlowNode::ConstInput(left, value);
+                                nodes.push(*neighbour);
+                            }
                         }
                         DataFlowNode::Operation(op, left, right) => {
                             data_flow.nodes[*neighbour] =
@@ -418,11 +1036,10 @@ fn propagate_constants(data_flow: &mut DataFlow) {
                     },
                     Side::Right => match data_flow.nodes[*neighbour] {
                         DataFlowNode::LeftConstOperation(op, value, left, ..) => {
-                            data_flow.nodes[*neighbour] = DataFlowNode::ConstInput(
-                                left,
-                                perform_operation(op, value, node_value),
-                            );
-                            nodes.push(*neighbour);
+                            if let Some(value) = checked_operation(op, value, node_value) {
+                                data_flow.nodes[*neighbour] = DataFlowNode::ConstInput(left, value);
+                                nodes.push(*neighbour);
+                            }
                         }
                         DataFlowNode::Operation(op, left, ..) => {
                             data_flow.nodes[*neighbour] =
@@ -440,17 +1057,273 @@ fn propagate_constants(data_flow: &mut DataFlow) {
         .retain(|edge| !matches!(&nodes[edge.dst], DataFlowNode::ConstInput(..)));
 }
 
-fn perform_operation(operation: OperationType, left: Value, right: Value) -> Value {
+pub fn perform_operation(
+    arithmetic: Arithmetic,
+    operation: OperationType,
+    left: Value,
+    right: Value,
+) -> Result<Value, fn(usize) -> ExecutionError> {
+    let value = match (operation, arithmetic) {
+        (OperationType::Div, _) if right == 0 => return Err(ExecutionError::DivByZero),
+        (OperationType::Mod, _) if left < 0 => return Err(ExecutionError::ModNegativeDividend),
+        (OperationType::Mod, _) if right <= 0 => return Err(ExecutionError::ModNonPositiveModulus),
+        (OperationType::Add, Arithmetic::Checked) => left.checked_add(right),
+        (OperationType::Add, Arithmetic::Wrapping) => Some(left.wrapping_add(right)),
+        (OperationType::Mul, Arithmetic::Checked) => left.checked_mul(right),
+        (OperationType::Mul, Arithmetic::Wrapping) => Some(left.wrapping_mul(right)),
+        (OperationType::Div, Arithmetic::Checked) => left.checked_div(right),
+        (OperationType::Div, Arithmetic::Wrapping) => Some(left.wrapping_div(right)),
+        (OperationType::Mod, _) => Some(left % right),
+        (OperationType::Eql, _) => Some((left == right) as Value),
+    };
+    value.ok_or(ExecutionError::Overflow)
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Pass {
+    Constants,
+    DeadNodes,
+    CommonSubexpressions,
+    Simplify,
+}
+
+pub const DEFAULT_PASSES: [Pass; 5] = [
+    Pass::Constants,
+    Pass::Simplify,
+    Pass::Constants,
+    Pass::CommonSubexpressions,
+    Pass::DeadNodes,
+];
+
+pub fn parse_pass(name: &str) -> Option<Pass> {
+    match name {
+        "constants" => Some(Pass::Constants),
+        "dead" => Some(Pass::DeadNodes),
+        "cse" => Some(Pass::CommonSubexpressions),
+        "simplify" => Some(Pass::Simplify),
+        _ => None,
+    }
+}
+
+pub fn pass_to_string(pass: Pass) -> &'static str {
+    match pass {
+        Pass::Constants => "constants",
+        Pass::DeadNodes => "dead",
+        Pass::CommonSubexpressions => "cse",
+        Pass::Simplify => "simplify",
+    }
+}
+
+pub fn run_pass(pass: Pass, data_flow: &mut DataFlow) {
+    match pass {
+        Pass::Constants => propagate_constants(data_flow),
+        Pass::DeadNodes => eliminate_dead_nodes(data_flow),
+        Pass::CommonSubexpressions => eliminate_common_subexpressions(data_flow),
+        Pass::Simplify => simplify_operations(data_flow),
+    }
+}
+
+pub fn optimize_program(
+    program: &[Instruction],
+    inputs: RangeInclusive<Value>,
+    arithmetic: Arithmetic,
+    passes: &[Pass],
+) -> (Program, Vec<(Pass, usize)>) {
+    let mut data_flow = build_data_flow(program, inputs, arithmetic);
+    let mut optimized = generate_program(&data_flow);
+    let mut removed = Vec::new();
+    for pass in passes.iter() {
+        run_pass(*pass, &mut data_flow);
+        let next = generate_program(&data_flow);
+        removed.push((*pass, optimized.len().saturating_sub(next.len())));
+        optimized = next;
+    }
+    (optimized, removed)
+}
+
+pub fn eliminate_dead_nodes(data_flow: &mut DataFlow) {
+    let out = data_flow
+        .nodes
+        .iter()
+        .position(|v| matches!(v, DataFlowNode::Out(Variable::Z)))
+        .unwrap();
+    let mut reachable = find_reachable_nodes(out, data_flow);
+    for node in find_fallible_nodes(data_flow) {
+        reachable.extend(find_reachable_nodes(node, data_flow));
+    }
+    data_flow.edges.retain(|edge| reachable.contains(&edge.dst));
+    data_flow.ranges = find_value_ranges(data_flow);
+}
+
+pub fn simplify_operations(data_flow: &mut DataFlow) {
+    let sources = find_sources(data_flow);
+    let mut forward: Vec<usize> = (0..data_flow.nodes.len()).collect();
+    for i in 0..data_flow.nodes.len() {
+        let [left_source, right_source] = sources[i].map(|v| v.map(|v| forward[v]));
+        let range = |source: Option<usize>| match source {
+            Some(v) => data_flow.ranges[v].clone(),
+            None => FULL_RANGE,
+        };
+        let (left_range, right_range) = (range(left_source), range(right_source));
+        match data_flow.nodes[i] {
+            DataFlowNode::RightConstOperation(OperationType::Mul, left, 0)
+            | DataFlowNode::LeftConstOperation(OperationType::Mul, 0, left, ..) => {
+                make_constant(data_flow, i, left, 0)
+            }
+            DataFlowNode::RightConstOperation(OperationType::Add, .., 0)
+            | DataFlowNode::RightConstOperation(OperationType::Mul, .., 1)
+            | DataFlowNode::RightConstOperation(OperationType::Div, .., 1) => {
+                if let Some(source) = left_source {
+                    replace_node(data_flow, i, source);
+                    forward[i] = source;
+                }
+            }
+            DataFlowNode::RightConstOperation(OperationType::Eql, left, value)
+                if is_disjoint(&left_range, &(value..=value)) =>
+            {
+                make_constant(data_flow, i, left, 0)
+            }
+            DataFlowNode::LeftConstOperation(OperationType::Eql, value, left, ..)
+                if is_disjoint(&(value..=value), &right_range) =>
+            {
+                make_constant(data_flow, i, left, 0)
+            }
+            DataFlowNode::Operation(OperationType::Eql, left, ..)
+                if is_disjoint(&left_range, &right_range) =>
+            {
+                make_constant(data_flow, i, left, 0)
+            }
+            _ => (),
+        }
+    }
+    data_flow.ranges = find_value_ranges(data_flow);
+}
+
+fn is_disjoint(left: &RangeInclusive<Value>, right: &RangeInclusive<Value>) -> bool {
+    left.end() < right.start() || right.end() < left.start()
+}
+
+#[derive(Eq, PartialEq, Hash)]
+enum ValueKey {
+    Const(Value),
+    Input(usize),
+    Operation(OperationType, usize, usize),
+    Unknown(usize),
+}
+
+pub fn eliminate_common_subexpressions(data_flow: &mut DataFlow) {
+    let sources = find_sources(data_flow);
+    let mut users = vec![Vec::new(); data_flow.nodes.len()];
+    for edge in data_flow.edges.iter() {
+        users[edge.src].push(edge.dst);
+    }
+    let fallible = find_fallible_nodes(data_flow);
+    let mut keys = HashMap::new();
+    let mut numbers = Vec::with_capacity(data_flow.nodes.len());
+    let mut writers: [Vec<usize>; 4] = Default::default();
+    for i in 0..data_flow.nodes.len() {
+        let key = find_value_key(&data_flow.nodes[i], &sources[i], &numbers, &mut keys)
+            .unwrap_or(ValueKey::Unknown(i));
+        let len = keys.len();
+        let number = *keys.entry(key).or_insert(len);
+        numbers.push(number);
+        let writers = match node_variable(&data_flow.nodes[i]) {
+            Some(variable) => &mut writers[variable as usize],
+            None => continue,
+        };
+        let mut chain = vec![i];
+        let mut found = None;
+        for writer in writers.iter().rev() {
+            if numbers[*writer] == number {
+                found = Some(*writer);
+                break;
+            }
+            if matches!(data_flow.nodes[*writer], DataFlowNode::Input(..))
+                || fallible.contains(writer)
+                || users[*writer].iter().any(|v| !chain.contains(v))
+            {
+                break;
+            }
+            chain.push(*writer);
+        }
+        match found {
+            Some(writer) => {
+                let moved = std::mem::take(&mut users[i]);
+                users[writer].extend(moved);
+                replace_node(data_flow, i, writer);
+                writers.truncate(writers.len() + 1 - chain.len());
+            }
+            None => writers.push(i),
+        }
+    }
+    data_flow.ranges = find_value_ranges(data_flow);
+}
+
+fn find_value_key(
+    node: &DataFlowNode,
+    sources: &[Option<usize>; 2],
+    numbers: &[usize],
+    keys: &mut HashMap<ValueKey, usize>,
+) -> Option<ValueKey> {
+    let mut constant = |value| {
+        let len = keys.len();
+        *keys.entry(ValueKey::Const(value)).or_insert(len)
+    };
+    let [left, right] = sources.map(|v| v.map(|v| numbers[v]));
+    let (operation, left, right) = match node {
+        DataFlowNode::ConstInput(.., value) => return Some(ValueKey::Const(*value)),
+        DataFlowNode::Input(.., index) => return Some(ValueKey::Input(*index)),
+        DataFlowNode::RightConstOperation(op, .., value) => (*op, left?, constant(*value)),
+        DataFlowNode::LeftConstOperation(op, value, ..) => (*op, constant(*value), right?),
+        DataFlowNode::Operation(op, ..) => (*op, left?, right?),
+        DataFlowNode::Out(..) => return None,
+    };
     match operation {
-        OperationType::Add => left + right,
-        OperationType::Mul => left * right,
-        OperationType::Div => left / right,
-        OperationType::Mod => left % right,
-        OperationType::Eql => (left == right) as Value,
+        OperationType::Add | OperationType::Mul | OperationType::Eql => Some(ValueKey::Operation(
+            operation,
+            left.min(right),
+            left.max(right),
+        )),
+        OperationType::Div | OperationType::Mod => {
+            Some(ValueKey::Operation(operation, left, right))
+        }
+    }
+}
+
+fn find_sources(data_flow: &DataFlow) -> Vec<[Option<usize>; 2]> {
+    let mut sources = vec![[None; 2]; data_flow.nodes.len()];
+    for edge in data_flow.edges.iter() {
+        sources[edge.dst][edge.side as usize] = Some(edge.src);
+    }
+    sources
+}
+
+fn node_variable(node: &DataFlowNode) -> Option<Variable> {
+    match node {
+        DataFlowNode::ConstInput(variable, ..)
+        | DataFlowNode::Input(variable, ..)
+        | DataFlowNode::RightConstOperation(_, variable, _)
+        | DataFlowNode::LeftConstOperation(_, _, variable, _)
+        | DataFlowNode::Operation(_, variable, _) => Some(*variable),
+        DataFlowNode::Out(..) => None,
+    }
+}
+
+fn make_constant(data_flow: &mut DataFlow, node: usize, variable: Variable, value: Value) {
+    data_flow.nodes[node] = DataFlowNode::ConstInput(variable, value);
+    data_flow.edges.retain(|edge| edge.dst != node);
+}
+
+fn replace_node(data_flow: &mut DataFlow, node: usize, by: usize) {
+    data_flow.edges.retain(|edge| edge.dst != node);
+    for edge in data_flow.edges.iter_mut() {
+        if edge.src == node {
+            edge.src = by;
+        }
     }
 }
 
-fn generate_program(data_flow: &DataFlow) -> Vec<Instruction> {
+pub fn generate_program(data_flow: &DataFlow) -> Vec<Instruction> {
     let mut result = Vec::new();
     let mut edges = HashMap::new();
     for edge in data_flow.edges.iter() {
@@ -468,8 +1341,11 @@ fn generate_program(data_flow: &DataFlow) -> Vec<Instruction> {
             reachable.extend(find_reachable_nodes(i, data_flow).iter());
         }
     }
+    for node in find_fallible_nodes(data_flow) {
+        reachable.extend(find_reachable_nodes(node, data_flow));
+    }
     for (i, node) in data_flow.nodes.iter().enumerate() {
-        if !reachable.contains(&i) {
+        if !reachable.contains(&i) && !matches!(node, DataFlowNode::Input(..)) {
             continue;
         }
         match node {
@@ -483,7 +1359,10 @@ fn generate_program(data_flow: &DataFlow) -> Vec<Instruction> {
                     result.push(Instruction::AddConst(*variable, *value));
                 }
             }
-            DataFlowNode::Input(variable, ..) => result.push(Instruction::Inp(*variable)),
+            DataFlowNode::Input(variable, ..) => {
+                zero_variables[*variable as usize] = false;
+                result.push(Instruction::Inp(*variable));
+            }
             DataFlowNode::RightConstOperation(op, left, right) => {
                 let instruction = match op {
                     OperationType::Add => Instruction::AddConst(*left, *right),
@@ -511,6 +1390,183 @@ fn generate_program(data_flow: &DataFlow) -> Vec<Instruction> {
     result
 }
 
+pub fn transpile_data_flow(data_flow: &DataFlow) -> String {
+    let inputs = data_flow
+        .nodes
+        .iter()
+        .filter(|v| matches!(v, DataFlowNode::Input(..)))
+        .count();
+    let out = data_flow
+        .nodes
+        .iter()
+        .position(|v| matches!(v, DataFlowNode::Out(Variable::Z)))
+        .unwrap();
+    let mut sources = find_sources(data_flow);
+    let mut forward: Vec<usize> = (0..data_flow.nodes.len()).collect();
+    for i in 0..data_flow.nodes.len() {
+        sources[i] = sources[i].map(|v| v.map(|v| forward[v]));
+        let [left, right] = sources[i];
+        let constant = |source: Option<usize>| match source.map(|v| &data_flow.nodes[v]) {
+            Some(DataFlowNode::ConstInput(.., value)) => Some(*value),
+            _ => None,
+        };
+        let identity = match data_flow.nodes[i] {
+            DataFlowNode::RightConstOperation(OperationType::Add, .., 0)
+            | DataFlowNode::RightConstOperation(OperationType::Mul, .., 1)
+            | DataFlowNode::RightConstOperation(OperationType::Div, .., 1) => left,
+            DataFlowNode::LeftConstOperation(OperationType::Add, 0, ..)
+            | DataFlowNode::LeftConstOperation(OperationType::Mul, 1, ..) => right,
+            DataFlowNode::Operation(op, ..) => match (op, constant(left), constant(right)) {
+                (OperationType::Add, _, Some(0))
+                | (OperationType::Mul, _, Some(1))
+                | (OperationType::Div, _, Some(1)) => left,
+                (OperationType::Add, Some(0), _) | (OperationType::Mul, Some(1), _) => right,
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(source) = identity {
+            forward[i] = source;
+        }
+    }
+    let mut used = find_fallible_nodes(data_flow);
+    used.insert(out);
+    let mut referenced = HashSet::new();
+    for (i, node) in data_flow.nodes.iter().enumerate().rev() {
+        if !used.contains(&i) {
+            continue;
+        }
+        if forward[i] != i {
+            used.insert(forward[i]);
+            referenced.insert(forward[i]);
+            continue;
+        }
+        let [left, right] = sources[i];
+        let operands = match node {
+            DataFlowNode::ConstInput(..) | DataFlowNode::Input(..) => [None, None],
+            DataFlowNode::RightConstOperation(..) | DataFlowNode::Out(..) => [left, None],
+            DataFlowNode::LeftConstOperation(..) => [right, None],
+            DataFlowNode::Operation(..) => [left, right],
+        };
+        used.extend(operands.iter().flatten());
+        referenced.extend(operands.iter().flatten());
+    }
+    let name = |source: Option<usize>| format!("n{}", source.unwrap());
+    let binding = |i: usize| {
+        if referenced.contains(&i) {
+            format!("n{}", i)
+        } else {
+            format!("_n{}", i)
+        }
+    };
+    let arithmetic = data_flow.arithmetic;
+    let mut helpers = HashSet::new();
+    let mut result = format!(
+        "fn monad(input: [i64; {}]) -> Result<i64, &'static str> {{\n",
+        inputs
+    );
+    for (i, node) in data_flow.nodes.iter().enumerate() {
+        if !used.contains(&i) || forward[i] != i {
+            continue;
+        }
+        let [left, right] = sources[i];
+        let (op, left, right) = match node {
+            DataFlowNode::ConstInput(.., value) => {
+                writeln!(result, "    let n{} = {};", i, value).unwrap();
+                continue;
+            }
+            DataFlowNode::Input(.., index) => {
+                writeln!(result, "    let n{} = input[{}];", i, index).unwrap();
+                continue;
+            }
+            DataFlowNode::RightConstOperation(op, .., value) => {
+                (*op, name(left), value.to_string())
+            }
+            DataFlowNode::LeftConstOperation(op, value, ..) => {
+                (*op, value.to_string(), name(right))
+            }
+            DataFlowNode::Operation(op, ..) => (*op, name(left), name(right)),
+            DataFlowNode::Out(..) => {
+                writeln!(result, "    Ok({})", name(left)).unwrap();
+                continue;
+            }
+        };
+        if matches!(op, OperationType::Div | OperationType::Mod) {
+            helpers.insert(op);
+        }
+        let value = operation_to_rust(arithmetic, op, &left, &right);
+        writeln!(result, "    let {} = {};", binding(i), value).unwrap();
+    }
+    result += "}\n";
+    for op in [OperationType::Div, OperationType::Mod] {
+        if helpers.contains(&op) {
+            result += &operation_helper_to_rust(arithmetic, op);
+        }
+    }
+    result
+}
+
+fn operation_to_rust(
+    arithmetic: Arithmetic,
+    operation: OperationType,
+    left: &str,
+    right: &str,
+) -> String {
+    let overflow = ExecutionError::Overflow(0).reason();
+    match (operation, arithmetic) {
+        (OperationType::Add, Arithmetic::Checked) => {
+            format!(
+                "i64::checked_add({}, {}).ok_or({:?})?",
+                left, right, overflow
+            )
+        }
+        (OperationType::Add, Arithmetic::Wrapping) => {
+            format!("i64::wrapping_add({}, {})", left, right)
+        }
+        (OperationType::Mul, Arithmetic::Checked) => {
+            format!(
+                "i64::checked_mul({}, {}).ok_or({:?})?",
+                left, right, overflow
+            )
+        }
+        (OperationType::Mul, Arithmetic::Wrapping) => {
+            format!("i64::wrapping_mul({}, {})", left, right)
+        }
+        (OperationType::Div, _) => format!("alu_div({}, {})?", left, right),
+        (OperationType::Mod, _) => format!("alu_mod({}, {})?", left, right),
+        (OperationType::Eql, _) => format!("({} == {}) as i64", left, right),
+    }
+}
+
+fn operation_helper_to_rust(arithmetic: Arithmetic, operation: OperationType) -> String {
+    let signature = "(left: i64, right: i64) -> Result<i64, &'static str>";
+    match operation {
+        OperationType::Div => {
+            let quotient = match arithmetic {
+                Arithmetic::Checked => format!(
+                    "left.checked_div(right).ok_or({:?})",
+                    ExecutionError::Overflow(0).reason()
+                ),
+                Arithmetic::Wrapping => String::from("Ok(left.wrapping_div(right))"),
+            };
+            format!(
+                "\nfn alu_div{} {{\n    if right == 0 {{\n        return Err({:?});\n    }}\n    {}\n}}\n",
+                signature,
+                ExecutionError::DivByZero(0).reason(),
+                quotient
+            )
+        }
+        OperationType::Mod => format!(
+            "\nfn alu_mod{} {{\n    if left < 0 {{\n        return Err({:?});\n    }}\n    \
+             if right <= 0 {{\n        return Err({:?});\n    }}\n    Ok(left % right)\n}}\n",
+            signature,
+            ExecutionError::ModNegativeDividend(0).reason(),
+            ExecutionError::ModNonPositiveModulus(0).reason()
+        ),
+        _ => String::new(),
+    }
+}
+
 fn usize_to_variable(v: usize) -> Variable {
     match v {
         0 => Variable::W,
@@ -531,15 +1587,28 @@ fn operation_to_string(value: OperationType) -> &'static str {
     }
 }
 
-fn program_to_string(program: &[Instruction]) -> String {
+pub fn program_to_string(program: &[Instruction]) -> String {
     let mut result = String::new();
     for instruction in program.iter() {
-        result = format!("{}{}\n", result, instruction_to_string(instruction))
+        writeln!(result, "{}", instruction_to_string(instruction)).unwrap();
+    }
+    result
+}
+
+pub fn program_to_string_with_lines(program: &[Instruction], lines: &[Line]) -> String {
+    let mut result = String::new();
+    let mut number = 0;
+    for (instruction, line) in program.iter().zip(lines.iter()) {
+        for _ in number + 1..line.number {
+            result.push('\n');
+        }
+        number = line.number;
+        writeln!(result, "{}", instruction_to_string(instruction)).unwrap();
     }
     result
 }
 
-fn instruction_to_string(instruction: &Instruction) -> String {
+pub fn instruction_to_string(instruction: &Instruction) -> String {
     match instruction {
         Instruction::Inp(variable) => {
             format!("inp {}", variable_to_char(*variable))
@@ -606,18 +1675,26 @@ fn variable_to_char(variable: Variable) -> char {
     }
 }
 
-type Value = i64;
+pub type Value = i64;
 
-#[derive(Eq, PartialEq, Debug, Default)]
-struct Alu {
+#[derive(Eq, PartialEq, Debug, Default, Clone, Hash)]
+pub struct Alu {
     w: Value,
     x: Value,
     y: Value,
     z: Value,
+    arithmetic: Arithmetic,
+}
+
+#[derive(Eq, PartialEq, Debug, Default, Copy, Clone, Hash)]
+pub enum Arithmetic {
+    #[default]
+    Checked,
+    Wrapping,
 }
 
 #[derive(Eq, PartialEq, Debug, Copy, Clone)]
-enum Variable {
+pub enum Variable {
     W,
     X,
     Y,
@@ -625,13 +1702,13 @@ enum Variable {
 }
 
 #[derive(Eq, PartialEq, Debug, Copy, Clone)]
-enum Operand {
+pub enum Operand {
     Variable(Variable),
     Value(Value),
 }
 
 #[derive(Eq, PartialEq, Debug, Copy, Clone)]
-enum Instruction {
+pub enum Instruction {
     Inp(Variable),
     Add(Variable, Variable),
     Mul(Variable, Variable),
@@ -645,206 +1722,778 @@ enum Instruction {
     EqlConst(Variable, Value),
 }
 
-type Program = Vec<Instruction>;
+pub type Program = Vec<Instruction>;
 
 #[derive(PartialEq, Debug)]
-enum ExecutionError {
+pub enum ExecutionError {
     Read(usize),
     DivByZero(usize),
     ModNegativeDividend(usize),
     ModNonPositiveModulus(usize),
+    Overflow(usize),
 }
 
-fn execute_program(
-    mut input: impl Read,
+pub fn execute_program(
+    mut input: impl AluInput,
     program: &[Instruction],
     alu: &mut Alu,
 ) -> Result<(), ExecutionError> {
     for (i, instruction) in program.iter().enumerate() {
-        match instruction {
-            Instruction::Inp(variable) => {
-                let mut buf = [0; 1];
-                match input.read(&mut buf) {
-                    Ok(v) => {
-                        if v == 0 {
-                            return Err(ExecutionError::Read(i));
-                        }
-                        alu.store(*variable, symbol_to_value(buf[0]));
-                    }
-                    Err(_) => return Err(ExecutionError::Read(i)),
-                }
-            }
-            Instruction::Add(left, right) => {
-                alu.store(*left, alu.load(*left) + alu.load(*right));
-            }
-            Instruction::Mul(left, right) => {
-                alu.store(*left, alu.load(*left) * alu.load(*right));
-            }
-            Instruction::Div(left, right) => {
-                alu.store(*left, checked_div(alu.load(*left), alu.load(*right), i)?);
-            }
-            Instruction::Mod(left, right) => {
-                alu.store(*left, checked_mod(alu.load(*left), alu.load(*right), i)?);
-            }
-            Instruction::Eql(left, right) => {
-                alu.store(*left, (alu.load(*left) == alu.load(*right)) as Value)
-            }
-            Instruction::AddConst(left, right) => {
-                alu.store(*left, alu.load(*left) + *right);
-            }
-            Instruction::MulConst(left, right) => {
-                alu.store(*left, alu.load(*left) * *right);
-            }
-            Instruction::DivConst(left, right) => {
-                alu.store(*left, checked_div(alu.load(*left), *right, i)?);
-            }
-            Instruction::ModConst(left, right) => {
-                alu.store(*left, checked_mod(alu.load(*left), *right, i)?);
-            }
-            Instruction::EqlConst(left, right) => {
-                alu.store(*left, (alu.load(*left) == *right) as Value)
-            }
-        }
+        execute_instruction(i, instruction, &mut input, alu)?;
     }
     Ok(())
 }
 
-fn checked_div(dividend: Value, divisor: Value, i: usize) -> Result<Value, ExecutionError> {
-    if divisor == 0 {
-        return Err(ExecutionError::DivByZero(i));
+fn execute_instruction(
+    i: usize,
+    instruction: &Instruction,
+    input: &mut impl AluInput,
+    alu: &mut Alu,
+) -> Result<(), ExecutionError> {
+    match instruction {
+        Instruction::Inp(variable) => match input.read_value() {
+            Some(value) => alu.store(*variable, value),
+            None => return Err(ExecutionError::Read(i)),
+        },
+        Instruction::Add(left, right) => {
+            alu.apply(OperationType::Add, *left, alu.load(*right), i)?
+        }
+        Instruction::Mul(left, right) => {
+            alu.apply(OperationType::Mul, *left, alu.load(*right), i)?
+        }
+        Instruction::Div(left, right) => {
+            alu.apply(OperationType::Div, *left, alu.load(*right), i)?
+        }
+        Instruction::Mod(left, right) => {
+            alu.apply(OperationType::Mod, *left, alu.load(*right), i)?
+        }
+        Instruction::Eql(left, right) => {
+            alu.apply(OperationType::Eql, *left, alu.load(*right), i)?
+        }
+        Instruction::AddConst(left, right) => alu.apply(OperationType::Add, *left, *right, i)?,
+        Instruction::MulConst(left, right) => alu.apply(OperationType::Mul, *left, *right, i)?,
+        Instruction::DivConst(left, right) => alu.apply(OperationType::Div, *left, *right, i)?,
+        Instruction::ModConst(left, right) => alu.apply(OperationType::Mod, *left, *right, i)?,
+        Instruction::EqlConst(left, right) => alu.apply(OperationType::Eql, *left, *right, i)?,
     }
-    Ok(dividend / divisor)
+    Ok(())
 }
 
-fn checked_mod(dividend: Value, modulus: Value, i: usize) -> Result<Value, ExecutionError> {
-    if dividend < 0 {
-        return Err(ExecutionError::ModNegativeDividend(i));
+impl ExecutionError {
+    pub fn index(&self) -> usize {
+        match self {
+            ExecutionError::Read(i)
+            | ExecutionError::DivByZero(i)
+            | ExecutionError::ModNegativeDividend(i)
+            | ExecutionError::ModNonPositiveModulus(i)
+            | ExecutionError::Overflow(i) => *i,
+        }
     }
-    if modulus <= 0 {
-        return Err(ExecutionError::ModNonPositiveModulus(i));
+
+    fn reason(&self) -> &'static str {
+        match self {
+            ExecutionError::Read(..) => "no input to read",
+            ExecutionError::DivByZero(..) => "division by zero",
+            ExecutionError::ModNegativeDividend(..) => "mod of negative dividend",
+            ExecutionError::ModNonPositiveModulus(..) => "mod by non-positive modulus",
+            ExecutionError::Overflow(..) => "arithmetic overflow",
+        }
     }
-    Ok(dividend % modulus)
 }
 
-fn symbol_to_value(symbol: u8) -> Value {
-    (symbol - b'0') as Value
+impl Display for ExecutionError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "instruction {}: {}", self.index(), self.reason())
+    }
 }
 
-impl Alu {
-    fn load(&self, variable: Variable) -> Value {
-        match variable {
-            Variable::W => self.w,
-            Variable::X => self.x,
-            Variable::Y => self.y,
-            Variable::Z => self.z,
-        }
+impl std::error::Error for ExecutionError {}
+
+pub fn format_execution_error(error: &ExecutionError, lines: &[Line]) -> String {
+    match lines.get(error.index()) {
+        Some(line) => format!(
+            "line {}: {}: {}",
+            line.number,
+            line.text.trim(),
+            error.reason()
+        ),
+        None => error.to_string(),
     }
+}
 
-    fn store(&mut self, variable: Variable, value: Value) {
-        match variable {
-            Variable::W => self.w = value,
-            Variable::X => self.x = value,
-            Variable::Y => self.y = value,
-            Variable::Z => self.z = value,
+#[derive(PartialEq, Debug)]
+pub struct TraceStep {
+    pub index: usize,
+    pub instruction: Instruction,
+    pub before: Alu,
+    pub after: Alu,
+}
+
+pub fn trace_program(
+    mut input: impl AluInput,
+    program: &[Instruction],
+    alu: &mut Alu,
+) -> (Vec<TraceStep>, Result<(), ExecutionError>) {
+    let mut trace = Vec::with_capacity(program.len());
+    for (i, instruction) in program.iter().enumerate() {
+        let before = alu.clone();
+        if let Err(error) = execute_instruction(i, instruction, &mut input, alu) {
+            return (trace, Err(error));
         }
+        trace.push(TraceStep {
+            index: i,
+            instruction: *instruction,
+            before,
+            after: alu.clone(),
+        });
     }
+    (trace, Ok(()))
 }
 
-fn compile_program(buffer: impl BufRead) -> Result<Program, String> {
-    let mut instructions = Vec::new();
-    for (i, line) in buffer.lines().enumerate() {
-        match line {
-            Ok(v) => {
-                if v.is_empty() {
-                    continue;
-                }
-                match parse_instruction(&v) {
-                    Ok(v) => instructions.push(v),
-                    Err(e) => return Err(format!("syntax error at line {}: {}", i + 1, e)),
-                }
-            }
-            Err(e) => return Err(format!("failed to read line {}: {}", i + 1, e)),
-        }
+pub fn trace_to_csv(trace: &[TraceStep]) -> String {
+    let mut result = String::from(
+        "index,instruction,before_w,before_x,before_y,before_z,after_w,after_x,after_y,after_z\n",
+    );
+    for step in trace.iter() {
+        let (before, after) = (&step.before, &step.after);
+        writeln!(
+            result,
+            "{},{},{},{},{},{},{},{},{},{}",
+            step.index,
+            instruction_to_string(&step.instruction),
+            before.w,
+            before.x,
+            before.y,
+            before.z,
+            after.w,
+            after.x,
+            after.y,
+            after.z
+        )
+        .unwrap();
     }
-    Ok(instructions)
+    result
 }
 
-fn parse_instruction(text: &str) -> Result<Instruction, String> {
-    let (command, operands) = if let Some(v) = text.split_once(' ') {
-        v
-    } else {
-        return Err("invalid instruction format".to_string());
+pub fn trace_to_json(trace: &[TraceStep]) -> String {
+    let registers = |alu: &Alu| {
+        format!(
+            "{{\"w\":{},\"x\":{},\"y\":{},\"z\":{}}}",
+            alu.w, alu.x, alu.y, alu.z
+        )
     };
-    match command {
-        "inp" => Ok(Instruction::Inp(parse_variable(operands)?)),
-        name => {
-            let (left, right) = parse_operands(operands)?;
-            match right {
-                Operand::Variable(variable) => make_in_out_instruction(name, left, variable),
-                Operand::Value(value) => make_const_in_out_instruction(name, left, value),
+    let steps: Vec<String> = trace
+        .iter()
+        .map(|step| {
+            format!(
+                "{{\"index\":{},\"instruction\":\"{}\",\"before\":{},\"after\":{}}}",
+                step.index,
+                instruction_to_string(&step.instruction),
+                registers(&step.before),
+                registers(&step.after)
+            )
+        })
+        .collect();
+    format!("[{}]", steps.join(","))
+}
+
+pub fn profile_comparisons(
+    program: &[Instruction],
+    runs: usize,
+    seed: u64,
+) -> Vec<(usize, [usize; 2])> {
+    let inputs = program
+        .iter()
+        .filter(|v| matches!(v, Instruction::Inp(..)))
+        .count();
+    let mut counts = vec![[0; 2]; program.len()];
+    let mut random = Random::new(seed);
+    for _ in 0..runs {
+        let mut input = (0..inputs)
+            .map(|_| random.range(1..=9))
+            .collect::<Vec<Value>>()
+            .into_iter();
+        let mut alu = Alu::default();
+        for (i, instruction) in program.iter().enumerate() {
+            if execute_instruction(i, instruction, &mut input, &mut alu).is_err() {
+                break;
+            }
+            if let Instruction::Eql(left, ..) | Instruction::EqlConst(left, ..) = instruction {
+                counts[i][alu.load(*left) as usize] += 1;
             }
         }
     }
+    counts
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| {
+            matches!(
+                program[*i],
+                Instruction::Eql(..) | Instruction::EqlConst(..)
+            )
+        })
+        .collect()
 }
 
-fn make_in_out_instruction(
-    name: &str,
-    left: Variable,
-    right: Variable,
-) -> Result<Instruction, String> {
+#[derive(PartialEq, Debug)]
+pub enum Stop {
+    Step,
+    Breakpoint(usize),
+    Input(usize),
+    Watchpoint(Variable, Value, Value),
+    Finished,
+    Error(ExecutionError),
+}
+
+pub struct Debugger<'a, R> {
+    program: &'a [Instruction],
+    input: R,
+    alu: Alu,
+    position: usize,
+    breakpoints: BTreeSet<usize>,
+    last_breakpoint: Option<usize>,
+    break_on_input: bool,
+    watchpoints: Vec<Variable>,
+}
+
+impl<'a, R: AluInput> Debugger<'a, R> {
+    pub fn new(program: &'a [Instruction], input: R) -> Self {
+        Self {
+            program,
+            input,
+            alu: Alu::default(),
+            position: 0,
+            breakpoints: BTreeSet::new(),
+            last_breakpoint: None,
+            break_on_input: false,
+            watchpoints: Vec::new(),
+        }
+    }
+
+    pub fn alu(&self) -> &Alu {
+        &self.alu
+    }
+
+    pub fn set_arithmetic(&mut self, arithmetic: Arithmetic) {
+        self.alu.arithmetic = arithmetic;
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.program.len()
+    }
+
+    pub fn breakpoints(&self) -> &BTreeSet<usize> {
+        &self.breakpoints
+    }
+
+    pub fn add_breakpoint(&mut self, index: usize) -> bool {
+        index < self.program.len() && self.breakpoints.insert(index)
+    }
+
+    pub fn remove_breakpoint(&mut self, index: usize) -> bool {
+        self.breakpoints.remove(&index)
+    }
+
+    pub fn set_break_on_input(&mut self, value: bool) {
+        self.break_on_input = value;
+    }
+
+    pub fn add_watchpoint(&mut self, variable: Variable) -> bool {
+        if self.watchpoints.contains(&variable) {
+            return false;
+        }
+        self.watchpoints.push(variable);
+        true
+    }
+
+    pub fn remove_watchpoint(&mut self, variable: Variable) -> bool {
+        let len = self.watchpoints.len();
+        self.watchpoints.retain(|v| *v != variable);
+        self.watchpoints.len() != len
+    }
+
+    pub fn step(&mut self) -> Stop {
+        if self.is_finished() {
+            return Stop::Finished;
+        }
+        let before = [Variable::W, Variable::X, Variable::Y, Variable::Z].map(|v| self.alu.load(v));
+        let instruction = &self.program[self.position];
+        if let Err(error) =
+            execute_instruction(self.position, instruction, &mut self.input, &mut self.alu)
+        {
+            return Stop::Error(error);
+        }
+        self.position += 1;
+        for variable in self.watchpoints.iter() {
+            let old = before[*variable as usize];
+            let new = self.alu.load(*variable);
+            if old != new {
+                return Stop::Watchpoint(*variable, old, new);
+            }
+        }
+        if self.is_finished() {
+            return Stop::Finished;
+        }
+        Stop::Step
+    }
+
+    pub fn resume(&mut self) -> Stop {
+        loop {
+            if self.breakpoints.contains(&self.position)
+                && self.last_breakpoint != Some(self.position)
+            {
+                self.last_breakpoint = Some(self.position);
+                return Stop::Breakpoint(self.position);
+            }
+            match self.step() {
+                Stop::Step => (),
+                stop => return stop,
+            }
+            if self.break_on_input && matches!(self.program[self.position], Instruction::Inp(..)) {
+                self.break_on_input = false;
+                return Stop::Input(self.position);
+            }
+        }
+    }
+}
+
+pub trait AluInput {
+    fn read_value(&mut self) -> Option<Value>;
+}
+
+impl AluInput for &[u8] {
+    fn read_value(&mut self) -> Option<Value> {
+        let (symbol, rest) = self.split_first()?;
+        *self = rest;
+        parse_digit(*symbol).map(|v| v as Value)
+    }
+}
+
+impl AluInput for std::vec::IntoIter<Value> {
+    fn read_value(&mut self) -> Option<Value> {
+        self.next()
+    }
+}
+
+pub fn parse_input_values(buffer: impl BufRead) -> Result<Vec<Value>, ParseError> {
+    let mut values = Vec::new();
+    for line in Lines::new(DAY, buffer) {
+        let line = line?;
+        for text in line.text.split_whitespace() {
+            values.push(line.parse(text, "integer")?);
+        }
+    }
+    Ok(values)
+}
+
+impl Alu {
+    pub fn new(arithmetic: Arithmetic) -> Self {
+        Self {
+            arithmetic,
+            ..Default::default()
+        }
+    }
+
+    pub fn arithmetic(&self) -> Arithmetic {
+        self.arithmetic
+    }
+
+    pub fn load(&self, variable: Variable) -> Value {
+        match variable {
+            Variable::W => self.w,
+            Variable::X => self.x,
+            Variable::Y => self.y,
+            Variable::Z => self.z,
+        }
+    }
+
+    fn store(&mut self, variable: Variable, value: Value) {
+        match variable {
+            Variable::W => self.w = value,
+            Variable::X => self.x = value,
+            Variable::Y => self.y = value,
+            Variable::Z => self.z = value,
+        }
+    }
+
+    fn apply(
+        &mut self,
+        operation: OperationType,
+        variable: Variable,
+        right: Value,
+        i: usize,
+    ) -> Result<(), ExecutionError> {
+        let value = perform_operation(self.arithmetic, operation, self.load(variable), right)
+            .map_err(|error| error(i))?;
+        self.store(variable, value);
+        Ok(())
+    }
+}
+
+impl Alu {
+    fn ranges(&self) -> [RangeInclusive<Value>; 4] {
+        [self.w, self.x, self.y, self.z].map(|v| v..=v)
+    }
+}
+
+impl Display for Alu {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            formatter,
+            "w={} x={} y={} z={}",
+            self.w, self.x, self.y, self.z
+        )
+    }
+}
+
+pub fn compile_program(buffer: impl BufRead) -> Result<Program, ParseError> {
+    Ok(compile_program_with_lines(buffer)?.0)
+}
+
+pub fn compile_program_with_lines(
+    buffer: impl BufRead,
+) -> Result<(Program, Vec<Line>), ParseError> {
+    let mut instructions = Vec::new();
+    let mut lines = Vec::new();
+    for line in Lines::new(DAY, buffer) {
+        let line = line?;
+        let text = line.text.split('#').next().unwrap().trim_end();
+        if text.is_empty() {
+            continue;
+        }
+        instructions.push(parse_instruction(&line, text)?);
+        lines.push(line);
+    }
+    Ok((instructions, lines))
+}
+
+fn parse_instruction(line: &Line, text: &str) -> Result<Instruction, ParseError> {
+    let (command, operands) = line.split_once(text, " ")?;
+    match command {
+        "inp" => Ok(Instruction::Inp(parse_variable(line, operands)?)),
+        name => {
+            let (left, right) = parse_operands(line, operands)?;
+            match right {
+                Operand::Variable(variable) => make_in_out_instruction(line, name, left, variable),
+                Operand::Value(value) => make_const_in_out_instruction(line, name, left, value),
+            }
+        }
+    }
+}
+
+const INSTRUCTION_NAMES: &str = "inp, add, mul, div, mod or eql";
+
+fn make_in_out_instruction(
+    line: &Line,
+    name: &str,
+    left: Variable,
+    right: Variable,
+) -> Result<Instruction, ParseError> {
     match name {
         "add" => Ok(Instruction::Add(left, right)),
         "mul" => Ok(Instruction::Mul(left, right)),
         "div" => Ok(Instruction::Div(left, right)),
         "mod" => Ok(Instruction::Mod(left, right)),
         "eql" => Ok(Instruction::Eql(left, right)),
-        v => Err(format!("invalid instruction name: {}", v)),
+        v => Err(line.error(v, INSTRUCTION_NAMES)),
     }
 }
 
 fn make_const_in_out_instruction(
+    line: &Line,
     name: &str,
     left: Variable,
     right: Value,
-) -> Result<Instruction, String> {
+) -> Result<Instruction, ParseError> {
     match name {
         "add" => Ok(Instruction::AddConst(left, right)),
         "mul" => Ok(Instruction::MulConst(left, right)),
         "div" => Ok(Instruction::DivConst(left, right)),
         "mod" => Ok(Instruction::ModConst(left, right)),
         "eql" => Ok(Instruction::EqlConst(left, right)),
-        v => Err(format!("invalid instruction name: {}", v)),
+        v => Err(line.error(v, INSTRUCTION_NAMES)),
     }
 }
 
-fn parse_operands(text: &str) -> Result<(Variable, Operand), String> {
-    if let Some((a, b)) = text.split_once(' ') {
-        Ok((parse_variable(a)?, parse_operand(b)?))
-    } else {
-        Err(format!("invalid command format: {}", text))
-    }
+fn parse_operands(line: &Line, text: &str) -> Result<(Variable, Operand), ParseError> {
+    let (a, b) = line.split_once(text, " ")?;
+    Ok((parse_variable(line, a)?, parse_operand(line, b)?))
 }
 
-fn parse_operand(text: &str) -> Result<Operand, String> {
-    match parse_variable(text) {
+fn parse_operand(line: &Line, text: &str) -> Result<Operand, ParseError> {
+    match parse_variable(line, text) {
         Ok(v) => Ok(Operand::Variable(v)),
-        Err(_) => match Value::from_str(text) {
-            Ok(v) => Ok(Operand::Value(v)),
-            Err(e) => Err(format!("invalid value \"{}\" format: {}", text, e)),
-        },
+        Err(_) => Ok(Operand::Value(line.parse(text, "variable or number")?)),
     }
 }
 
-fn parse_variable(text: &str) -> Result<Variable, String> {
+fn parse_variable(line: &Line, text: &str) -> Result<Variable, ParseError> {
     match text {
         "w" => Ok(Variable::W),
         "x" => Ok(Variable::X),
         "y" => Ok(Variable::Y),
         "z" => Ok(Variable::Z),
-        v => Err(format!("invalid variable name: {}", v)),
+        v => Err(line.error(v, "w, x, y or z")),
+    }
+}
+
+#[test]
+fn search_model_numbers_test() {
+    for seed in 0..3 {
+        let input = crate::generate::generate_input(DAY, seed, None).unwrap();
+        let program = compile_program(input.as_bytes()).unwrap();
+        let numbers = find_model_numbers_by_pairs(&program);
+        assert!(numbers.is_some());
+        assert_eq!(search_model_numbers(&program), numbers);
     }
 }
 
+#[test]
+fn find_the_meaning_of_monad_fallback_test() {
+    let code = r#"inp w
+inp x
+add w x
+eql w 12
+eql w 0
+add z w
+"#
+    .as_bytes();
+    assert_eq!(find_the_meaning_of_monad(code), Ok(Some((93, 39))));
+    assert_eq!(find_max_model_number(code), Ok(Some(93)));
+    assert_eq!(find_min_model_number(code), Ok(Some(39)));
+    assert_eq!(
+        find_the_meaning_of_monad("inp w\nadd z w\n".as_bytes()),
+        Ok(None)
+    );
+}
+
+#[test]
+fn data_flow_to_dot_test() {
+    let code = r#"inp w
+add z w
+mul z 26
+inp x
+eql x w
+add y x
+add z y
+"#
+    .as_bytes();
+    let data_flow = build_data_flow(
+        &compile_program(code).unwrap(),
+        DIGIT_INPUTS,
+        Arithmetic::Checked,
+    );
+    let dot = data_flow_to_dot(&data_flow, FlowView::All);
+    assert!(dot.starts_with(&format!("digraph data_flow {{\n{}", DOT_LEGEND)));
+    assert!(dot.contains(
+        r##"    subgraph cluster_0 {
+        label="inp 0";
+        node_4[label="#4 inp w : 0\n[1, 9]", shape=box];
+        node_5[label="#5 add z w ; z = 0\n[1, 9]"];
+"##
+    ));
+    assert!(!dot.contains("node_0"));
+    assert_eq!(
+        data_flow_to_dot(&data_flow, FlowView::Input(1)),
+        format!(
+            r##"digraph data_flow {{
+{}    node_12[label="#12 out x\n[0, 1]", shape=box];
+    node_13[label="#13 out y\n[0, 1]", shape=box];
+    node_14[label="#14 out z\n[26, 235]", shape=box];
+    subgraph cluster_1 {{
+        label="inp 1";
+        node_7[label="#7 inp x : 1\n[1, 9]", shape=box];
+        node_8[label="#8 eql x w\n[0, 1]"];
+        node_9[label="#9 add y x ; y = 0\n[0, 1]"];
+        node_10[label="#10 add z y\n[26, 235]"];
+    }}
+    node_7 -> node_8;
+    node_8 -> node_9[style=dashed];
+    node_9 -> node_10[style=dashed];
+    node_8 -> node_12;
+    node_9 -> node_13;
+    node_10 -> node_14;
+}}
+"##,
+            DOT_LEGEND
+        )
+    );
+    let dot = data_flow_to_dot(&data_flow, FlowView::Z);
+    assert!(dot.contains("node_14") && !dot.contains("node_11") && !dot.contains("node_12"));
+}
+
+#[test]
+fn transpile_data_flow_identity_test() {
+    let code = "inp w\nadd x w\nmul x 1\nadd x 2\nadd y x\ndiv y 1\nadd z y\n".as_bytes();
+    let data_flow = build_data_flow(
+        &compile_program(code).unwrap(),
+        DIGIT_INPUTS,
+        Arithmetic::Checked,
+    );
+    assert_eq!(
+        transpile_data_flow(&data_flow),
+        "fn monad(input: [i64; 1]) -> Result<i64, &'static str> {\n    let n4 = input[0];\n    \
+         let n7 = i64::checked_add(n4, 2).ok_or(\"arithmetic overflow\")?;\n    Ok(n7)\n}\n"
+    );
+}
+
+#[cfg(test)]
+fn run_transpiled(name: &str, data_flow: &DataFlow, numbers: &str) -> String {
+    let path =
+        std::env::temp_dir().join(format!("day_24_transpile_{}_{}", name, std::process::id()));
+    std::fs::create_dir_all(path.join("src")).unwrap();
+    std::fs::write(
+        path.join("Cargo.toml"),
+        "[package]\nname = \"monad\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    )
+    .unwrap();
+    let main = r#"
+fn main() {
+    for line in std::io::stdin().lines() {
+        let digits: Vec<i64> = line.unwrap().bytes().map(|v| (v - b'0') as i64).collect();
+        match monad(digits.try_into().unwrap()) {
+            Ok(z) => println!("{}", z),
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+"#;
+    let source = transpile_data_flow(data_flow) + main;
+    std::fs::write(path.join("src").join("main.rs"), source).unwrap();
+    let status = std::process::Command::new(env!("CARGO"))
+        .args(["build", "--quiet", "--offline"])
+        .current_dir(&path)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let mut child = std::process::Command::new(path.join("target").join("debug").join("monad"))
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::io::Write::write_all(&mut child.stdin.take().unwrap(), numbers.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    std::fs::remove_dir_all(&path).unwrap();
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn transpile_data_flow_test() {
+    let input = crate::generate::generate_input(DAY, 7, None).unwrap();
+    let program = compile_program(input.as_bytes()).unwrap();
+    let mut data_flow = build_data_flow(&program, DIGIT_INPUTS, Arithmetic::Checked);
+    for pass in DEFAULT_PASSES.iter() {
+        run_pass(*pass, &mut data_flow);
+    }
+    let mut random = crate::random::Random::new(42);
+    let mut numbers = String::new();
+    let mut expected = String::new();
+    for _ in 0..100 {
+        let number: String = (0..14)
+            .map(|_| (b'0' + random.range(1..=9) as u8) as char)
+            .collect();
+        let mut alu = Alu::default();
+        execute_program(number.as_bytes(), &program, &mut alu).unwrap();
+        writeln!(numbers, "{}", number).unwrap();
+        writeln!(expected, "{}", alu.z).unwrap();
+    }
+    assert_eq!(run_transpiled("monad", &data_flow, &numbers), expected);
+}
+
+#[test]
+fn transpile_data_flow_errors_test() {
+    let cases = [
+        (
+            "inp w\ninp x\nadd z w\nadd z -5\ndiv z x\nmod z x\ndiv z 0\n",
+            &["10", "12", "72"][..],
+            "division by zero\nmod of negative dividend\ndivision by zero\n",
+        ),
+        (
+            "inp w\ndiv x 0\nadd z w\n",
+            &["7"][..],
+            "division by zero\n",
+        ),
+    ];
+    for (i, (code, inputs, errors)) in cases.iter().enumerate() {
+        let program = compile_program(code.as_bytes()).unwrap();
+        let mut data_flow = build_data_flow(&program, DIGIT_INPUTS, Arithmetic::Checked);
+        for pass in DEFAULT_PASSES.iter() {
+            run_pass(*pass, &mut data_flow);
+        }
+        let mut numbers = String::new();
+        let mut expected = String::new();
+        for number in inputs.iter() {
+            let mut alu = Alu::default();
+            let error = execute_program(number.as_bytes(), &program, &mut alu).unwrap_err();
+            writeln!(numbers, "{}", number).unwrap();
+            writeln!(expected, "{}", error.reason()).unwrap();
+        }
+        assert_eq!(expected, *errors);
+        let name = format!("errors_{}", i);
+        assert_eq!(run_transpiled(&name, &data_flow, &numbers), expected);
+    }
+}
+
+#[test]
+fn execute_symbolic_test() {
+    let code = r#"inp w
+inp x
+add z w
+mul z x
+add z -10
+mod z 7
+eql y w
+"#
+    .as_bytes();
+    let paths = execute_symbolic(&compile_program(code).unwrap(), &DIGIT_INPUTS).unwrap();
+    assert_eq!(paths.len(), 1);
+    assert_eq!(paths[0].z.to_string(), "(in0 * in1 - 10) % 7");
+    assert_eq!(paths[0].z.evaluate(&[3, 5]), Some(5));
+    assert!(paths[0].constraints.is_empty());
+}
+
+#[test]
+fn execute_symbolic_monad_test() {
+    let input = crate::generate::generate_input(DAY, 3, None).unwrap();
+    let program = compile_program(input.as_bytes()).unwrap();
+    let paths = execute_symbolic(&program, &DIGIT_INPUTS).unwrap();
+    assert_eq!(paths.len(), 1);
+    assert_eq!(*paths[0].z, Expression::Const(0));
+    assert_eq!(paths[0].constraints.len(), 7);
+    let (max, min) = find_model_numbers_by_pairs(&program).unwrap();
+    for number in [max, min] {
+        let digits: Vec<Value> = number
+            .to_string()
+            .bytes()
+            .map(|v| (v - b'0') as Value)
+            .collect();
+        for constraint in paths[0].constraints.iter() {
+            assert!(constraint.equal);
+            assert_eq!(
+                constraint.left.evaluate(&digits),
+                constraint.right.evaluate(&digits),
+                "{}",
+                constraint
+            );
+        }
+    }
+}
+
+#[test]
+fn execute_symbolic_too_many_paths_test() {
+    let program = compile_program("inp w\neql w 5\n".repeat(13).as_bytes()).unwrap();
+    assert!(execute_symbolic(&program, &DIGIT_INPUTS).is_none());
+    let program = compile_program("inp w\neql w 5\n".repeat(11).as_bytes()).unwrap();
+    assert_eq!(
+        execute_symbolic(&program, &DIGIT_INPUTS).unwrap().len(),
+        1 << 11
+    );
+}
+
 #[test]
 fn compile_program_test() {
     let code = r#"inp w
@@ -874,6 +2523,114 @@ eql w -4
     );
 }
 
+#[test]
+fn compile_program_error_test() {
+    assert_eq!(
+        compile_program("inp w\nsub x 1\n".as_bytes()),
+        Err(ParseError::new(24, 2, 1, INSTRUCTION_NAMES))
+    );
+    assert_eq!(
+        compile_program("add x q\n".as_bytes()),
+        Err(ParseError::new(24, 1, 7, "variable or number"))
+    );
+    assert_eq!(
+        compile_program("inp\n".as_bytes()),
+        Err(ParseError::new(24, 1, 4, "\" \""))
+    );
+}
+
+#[test]
+fn compile_program_comments_test() {
+    let code = r#"# negate the input
+inp x # read
+
+mul x -1
+"#
+    .as_bytes();
+    let (program, lines) = compile_program_with_lines(code).unwrap();
+    assert_eq!(
+        program,
+        vec![
+            Instruction::Inp(Variable::X),
+            Instruction::MulConst(Variable::X, -1)
+        ]
+    );
+    assert_eq!(
+        program_to_string_with_lines(&program, &lines),
+        "\ninp x\n\nmul x -1\n"
+    );
+    assert_eq!(
+        compile_program("inp w # ok\nadd w #\n".as_bytes()),
+        Err(ParseError::new(24, 2, 6, "\" \""))
+    );
+}
+
+#[test]
+fn execute_program_values_test() {
+    let code = "inp x\ninp y\nmul x y\n".as_bytes();
+    let program = compile_program(code).unwrap();
+    let values = parse_input_values("-12\n 345 ".as_bytes()).unwrap();
+    assert_eq!(values, vec![-12, 345]);
+    let mut alu = Alu::default();
+    assert_eq!(
+        execute_program(values.into_iter(), &program, &mut alu),
+        Ok(())
+    );
+    assert_eq!(alu.x, -4140);
+    assert_eq!(
+        execute_program(vec![1].into_iter(), &program, &mut alu),
+        Err(ExecutionError::Read(1))
+    );
+    assert_eq!(
+        execute_program("1-".as_bytes(), &program, &mut alu),
+        Err(ExecutionError::Read(1))
+    );
+    assert_eq!(
+        parse_input_values("1 2\n3 -x\n".as_bytes()),
+        Err(ParseError::new(24, 2, 3, "integer"))
+    );
+}
+
+#[test]
+fn trace_program_test() {
+    let code = "inp x\nmul x -1\ndiv x y\n".as_bytes();
+    let program = compile_program(code).unwrap();
+    let mut alu = Alu::default();
+    let (trace, result) = trace_program("7".as_bytes(), &program, &mut alu);
+    assert_eq!(result, Err(ExecutionError::DivByZero(2)));
+    assert_eq!(
+        trace_to_csv(&trace),
+        "index,instruction,before_w,before_x,before_y,before_z,after_w,after_x,after_y,after_z\n\
+         0,inp x,0,0,0,0,0,7,0,0\n\
+         1,mul x -1,0,7,0,0,0,-7,0,0\n"
+    );
+    assert_eq!(
+        trace_to_json(&trace[1..]),
+        "[{\"index\":1,\"instruction\":\"mul x -1\",\
+         \"before\":{\"w\":0,\"x\":7,\"y\":0,\"z\":0},\
+         \"after\":{\"w\":0,\"x\":-7,\"y\":0,\"z\":0}}]"
+    );
+}
+
+#[test]
+fn profile_comparisons_test() {
+    let code = r#"inp w
+eql x w
+eql x 0
+add y w
+eql y 5
+"#
+    .as_bytes();
+    let program = compile_program(code).unwrap();
+    let profile = profile_comparisons(&program, 900, 42);
+    assert_eq!(profile.len(), 3);
+    assert_eq!(profile[0], (1, [900, 0]));
+    assert_eq!(profile[1], (2, [0, 900]));
+    assert_eq!(profile[2].0, 4);
+    assert_eq!(profile[2].1[0] + profile[2].1[1], 900);
+    assert!(profile[2].1[1] > 50 && profile[2].1[1] < 150);
+}
+
 #[test]
 fn execute_program_1_test() {
     let code = r#"inp x
@@ -892,6 +2649,7 @@ mul x -1
             x: -9,
             y: 0,
             z: 0,
+            ..Alu::default()
         }
     );
 }
@@ -916,6 +2674,7 @@ eql z x
             x: 9,
             y: 0,
             z: 1,
+            ..Alu::default()
         }
     );
 }
@@ -945,6 +2704,7 @@ mod w 2
             x: 0,
             y: 0,
             z: 1,
+            ..Alu::default()
         }
     );
     alu = Alu::default();
@@ -956,10 +2716,98 @@ mod w 2
             x: 1,
             y: 0,
             z: 1,
+            ..Alu::default()
         }
     );
 }
 
+#[test]
+fn execution_error_line_test() {
+    let code = "inp w\n\ndiv x w\n".as_bytes();
+    let (program, lines) = compile_program_with_lines(code).unwrap();
+    let mut alu = Alu::default();
+    let error = execute_program("0".as_bytes(), &program, &mut alu).unwrap_err();
+    assert_eq!(error, ExecutionError::DivByZero(1));
+    assert_eq!(
+        format_execution_error(&error, &lines),
+        "line 3: div x w: division by zero"
+    );
+}
+
+#[test]
+fn execute_program_overflow_test() {
+    let code = "inp w\nadd x 9223372036854775807\nadd x w\nmul w -1\n".as_bytes();
+    let program = compile_program(code).unwrap();
+    let mut alu = Alu::default();
+    assert_eq!(
+        execute_program("1".as_bytes(), &program, &mut alu),
+        Err(ExecutionError::Overflow(2))
+    );
+    assert_eq!(alu.load(Variable::X), Value::MAX);
+    let mut alu = Alu::new(Arithmetic::Wrapping);
+    assert_eq!(execute_program("1".as_bytes(), &program, &mut alu), Ok(()));
+    assert_eq!(alu.load(Variable::X), Value::MIN);
+    assert_eq!(alu.load(Variable::W), -1);
+    let code = "inp w\nadd x -9223372036854775807\nadd x -1\ndiv x -1\n".as_bytes();
+    let program = compile_program(code).unwrap();
+    let mut alu = Alu::default();
+    assert_eq!(
+        execute_program("1".as_bytes(), &program, &mut alu),
+        Err(ExecutionError::Overflow(3))
+    );
+    let mut alu = Alu::new(Arithmetic::Wrapping);
+    assert_eq!(execute_program("1".as_bytes(), &program, &mut alu), Ok(()));
+    assert_eq!(alu.load(Variable::X), Value::MIN);
+}
+
+#[test]
+fn debugger_test() {
+    let code = r#"inp w
+add z w
+mul z 3
+inp x
+add y x
+eql z y
+"#
+    .as_bytes();
+    let program = compile_program(code).unwrap();
+    let mut debugger = Debugger::new(&program, "39".as_bytes());
+    assert_eq!(debugger.step(), Stop::Step);
+    assert_eq!(debugger.position(), 1);
+    debugger.set_break_on_input(true);
+    assert_eq!(debugger.resume(), Stop::Input(3));
+    assert_eq!(debugger.alu().load(Variable::Z), 9);
+    assert!(debugger.add_watchpoint(Variable::Y));
+    assert_eq!(debugger.resume(), Stop::Watchpoint(Variable::Y, 0, 9));
+    assert!(debugger.remove_watchpoint(Variable::Y));
+    assert_eq!(debugger.resume(), Stop::Finished);
+    assert_eq!(debugger.alu().to_string(), "w=3 x=9 y=9 z=1");
+    assert_eq!(debugger.step(), Stop::Finished);
+}
+
+#[test]
+fn debugger_breakpoint_test() {
+    let code = r#"inp w
+add z w
+div z x
+add y 1
+"#
+    .as_bytes();
+    let program = compile_program(code).unwrap();
+    let mut debugger = Debugger::new(&program, "5".as_bytes());
+    assert!(debugger.add_breakpoint(2));
+    assert!(!debugger.add_breakpoint(4));
+    assert_eq!(debugger.resume(), Stop::Breakpoint(2));
+    assert_eq!(debugger.resume(), Stop::Error(ExecutionError::DivByZero(2)));
+    assert_eq!(debugger.position(), 2);
+    let mut debugger = Debugger::new(&program, "5".as_bytes());
+    assert!(debugger.add_breakpoint(0));
+    assert!(debugger.add_breakpoint(1));
+    assert_eq!(debugger.resume(), Stop::Breakpoint(0));
+    assert_eq!(debugger.resume(), Stop::Breakpoint(1));
+    assert_eq!(debugger.alu().load(Variable::W), 5);
+}
+
 #[test]
 fn generate_program_1_test() {
     let code = r#"inp z
@@ -969,6 +2817,337 @@ eql z x
 "#
     .as_bytes();
     let program = compile_program(code).unwrap();
-    let data_flow = build_data_flow(&program);
+    let data_flow = build_data_flow(&program, DIGIT_INPUTS, Arithmetic::Checked);
     assert_eq!(generate_program(&data_flow), program);
 }
+
+#[test]
+fn simplify_operations_test() {
+    let code = r#"inp w
+add x w
+mul x 1
+div x 1
+add x 0
+add z x
+mul y x
+mul y 0
+add z y
+inp w
+eql w 10
+add z w
+"#
+    .as_bytes();
+    let program = compile_program(code).unwrap();
+    let (optimized, removed) = optimize_program(
+        &program,
+        DIGIT_INPUTS,
+        Arithmetic::Checked,
+        &[Pass::Simplify, Pass::Constants, Pass::Simplify],
+    );
+    assert_eq!(
+        program_to_string(&optimized),
+        "inp w\nadd x w\nadd z x\ninp w\nmul w 0\n"
+    );
+    assert_eq!(
+        removed,
+        vec![
+            (Pass::Simplify, 5),
+            (Pass::Constants, 0),
+            (Pass::Simplify, 2)
+        ]
+    );
+}
+
+#[test]
+fn optimize_program_inputs_test() {
+    let program = compile_program("inp x\neql x 10\nadd z x\n".as_bytes()).unwrap();
+    let (optimized, _) =
+        optimize_program(&program, DIGIT_INPUTS, Arithmetic::Checked, &DEFAULT_PASSES);
+    assert_eq!(program_to_string(&optimized), "inp x\n");
+    let (optimized, _) = optimize_program(
+        &program,
+        INTEGER_INPUTS,
+        Arithmetic::Checked,
+        &DEFAULT_PASSES,
+    );
+    assert_eq!(program_to_string(&optimized), "inp x\neql x 10\nadd z x\n");
+    let paths = execute_symbolic(&program, &INTEGER_INPUTS).unwrap();
+    assert_eq!(paths.len(), 1);
+    assert_eq!(paths[0].constraints[0].to_string(), "in0 != 10");
+}
+
+#[test]
+fn eliminate_common_subexpressions_test() {
+    let code = r#"inp w
+mul x 0
+add x w
+add y x
+mul x 0
+add x w
+add z x
+"#
+    .as_bytes();
+    let program = compile_program(code).unwrap();
+    let (optimized, removed) =
+        optimize_program(&program, DIGIT_INPUTS, Arithmetic::Checked, &DEFAULT_PASSES);
+    assert_eq!(program_to_string(&optimized), "inp w\nadd x w\nadd z x\n");
+    assert_eq!(
+        removed,
+        vec![
+            (Pass::Constants, 1),
+            (Pass::Simplify, 0),
+            (Pass::Constants, 0),
+            (Pass::CommonSubexpressions, 2),
+            (Pass::DeadNodes, 1),
+        ]
+    );
+}
+
+#[test]
+fn find_value_ranges_test() {
+    let code = r#"inp w
+mul w 100
+mod w 26
+inp z
+add x z
+add x 12
+eql x z
+div y w
+"#
+    .as_bytes();
+    let data_flow = build_data_flow(
+        &compile_program(code).unwrap(),
+        DIGIT_INPUTS,
+        Arithmetic::Checked,
+    );
+    assert_eq!(
+        data_flow.ranges[VARIABLE_COUNT..VARIABLE_COUNT + 8],
+        [
+            1..=9,
+            100..=900,
+            0..=25,
+            1..=9,
+            1..=9,
+            13..=21,
+            0..=0,
+            0..=0
+        ]
+    );
+    assert_eq!(
+        find_possible_errors(&data_flow),
+        vec![ExecutionError::DivByZero(7)]
+    );
+}
+
+#[test]
+fn propagate_ranges_test() {
+    let code = r#"inp w
+add x w
+add x 12
+eql x w
+eql x 0
+mul z x
+mod w -1
+"#
+    .as_bytes();
+    let program = compile_program(code).unwrap();
+    let mut data_flow = build_data_flow(&program, DIGIT_INPUTS, Arithmetic::Checked);
+    assert_eq!(
+        find_possible_errors(&data_flow),
+        vec![ExecutionError::ModNonPositiveModulus(6)]
+    );
+    propagate_constants(&mut data_flow);
+    assert_eq!(
+        program_to_string(&generate_program(&data_flow)),
+        "inp w\nadd x 1\nmod w -1\n"
+    );
+}
+
+#[test]
+fn propagate_constants_overflow_test() {
+    let code = r#"inp w
+add x 9223372036854775807
+add x 1
+div y 0
+add z 5
+add z w
+"#
+    .as_bytes();
+    let program = compile_program(code).unwrap();
+    let mut data_flow = build_data_flow(&program, DIGIT_INPUTS, Arithmetic::Checked);
+    propagate_constants(&mut data_flow);
+    let optimized = generate_program(&data_flow);
+    assert_eq!(
+        program_to_string(&optimized),
+        "inp w\nadd x 9223372036854775807\nadd x 1\ndiv y 0\nadd z 5\nadd z w\n"
+    );
+    for arithmetic in [Arithmetic::Checked, Arithmetic::Wrapping] {
+        let mut original = Alu::new(arithmetic);
+        let mut alu = Alu::new(arithmetic);
+        assert_eq!(
+            execute_program("1".as_bytes(), &optimized, &mut alu),
+            execute_program("1".as_bytes(), &program, &mut original)
+        );
+        assert_eq!(alu, original);
+    }
+}
+
+#[test]
+fn optimize_program_errors_test() {
+    let program = compile_program("inp w\nadd w -5\ndiv z w\n".as_bytes()).unwrap();
+    let (optimized, _) =
+        optimize_program(&program, DIGIT_INPUTS, Arithmetic::Checked, &DEFAULT_PASSES);
+    assert_eq!(program_to_string(&optimized), "inp w\nadd w -5\ndiv z w\n");
+    assert_eq!(
+        execute_program("5".as_bytes(), &optimized, &mut Alu::default()),
+        Err(ExecutionError::DivByZero(2))
+    );
+    let code = "inp w\nadd z 9223372036854775807\nadd z w\nmul z 0\n";
+    let program = compile_program(code.as_bytes()).unwrap();
+    let (optimized, _) =
+        optimize_program(&program, DIGIT_INPUTS, Arithmetic::Checked, &DEFAULT_PASSES);
+    assert_eq!(program_to_string(&optimized), code);
+    let (optimized, _) = optimize_program(
+        &program,
+        DIGIT_INPUTS,
+        Arithmetic::Wrapping,
+        &DEFAULT_PASSES,
+    );
+    assert_eq!(program_to_string(&optimized), "inp w\n");
+}
+
+#[cfg(test)]
+fn arbitrary_variable() -> impl Strategy<Value = Variable> {
+    prop_oneof![
+        Just(Variable::W),
+        Just(Variable::X),
+        Just(Variable::Y),
+        Just(Variable::Z),
+    ]
+}
+
+#[cfg(test)]
+fn arbitrary_instruction() -> impl Strategy<Value = Instruction> {
+    let variable = arbitrary_variable;
+    prop_oneof![
+        3 => variable().prop_map(Instruction::Inp),
+        3 => (variable(), variable()).prop_map(|(l, r)| Instruction::Add(l, r)),
+        3 => (variable(), variable()).prop_map(|(l, r)| Instruction::Mul(l, r)),
+        1 => (variable(), variable()).prop_map(|(l, r)| Instruction::Div(l, r)),
+        1 => (variable(), variable()).prop_map(|(l, r)| Instruction::Mod(l, r)),
+        3 => (variable(), variable()).prop_map(|(l, r)| Instruction::Eql(l, r)),
+        3 => (variable(), -5..=30i64).prop_map(|(l, r)| Instruction::AddConst(l, r)),
+        3 => (variable(), -5..=30i64).prop_map(|(l, r)| Instruction::MulConst(l, r)),
+        3 => (variable(), 1..=30i64).prop_map(|(l, r)| Instruction::DivConst(l, r)),
+        3 => (variable(), 1..=30i64).prop_map(|(l, r)| Instruction::ModConst(l, r)),
+        3 => (variable(), -5..=30i64).prop_map(|(l, r)| Instruction::EqlConst(l, r)),
+    ]
+}
+
+#[cfg(test)]
+fn arbitrary_non_faulting_instruction() -> impl Strategy<Value = Instruction> {
+    let variable = arbitrary_variable;
+    prop_oneof![
+        3 => variable().prop_map(Instruction::Inp),
+        3 => (variable(), variable()).prop_map(|(l, r)| Instruction::Add(l, r)),
+        3 => (variable(), variable()).prop_map(|(l, r)| Instruction::Eql(l, r)),
+        3 => (variable(), 0..=30i64).prop_map(|(l, r)| Instruction::AddConst(l, r)),
+        3 => (variable(), 0..=5i64).prop_map(|(l, r)| Instruction::MulConst(l, r)),
+        3 => (variable(), 1..=30i64).prop_map(|(l, r)| Instruction::DivConst(l, r)),
+        3 => (variable(), 1..=30i64).prop_map(|(l, r)| Instruction::ModConst(l, r)),
+        3 => (variable(), -5..=30i64).prop_map(|(l, r)| Instruction::EqlConst(l, r)),
+    ]
+}
+
+#[cfg(test)]
+fn arbitrary_overflowing_instruction() -> impl Strategy<Value = Instruction> {
+    let variable = arbitrary_variable;
+    prop_oneof![
+        30 => arbitrary_instruction(),
+        1 => (variable(), any::<Value>()).prop_map(|(l, r)| Instruction::AddConst(l, r)),
+        1 => (variable(), any::<Value>()).prop_map(|(l, r)| Instruction::MulConst(l, r)),
+    ]
+}
+
+#[cfg(test)]
+fn arbitrary_arithmetic() -> impl Strategy<Value = Arithmetic> {
+    prop_oneof![Just(Arithmetic::Checked), Just(Arithmetic::Wrapping)]
+}
+
+#[cfg(test)]
+fn execute_with_error_kind(
+    input: &[u8],
+    program: &[Instruction],
+    arithmetic: Arithmetic,
+) -> Result<Alu, std::mem::Discriminant<ExecutionError>> {
+    let mut alu = Alu::new(arithmetic);
+    match execute_program(input, program, &mut alu) {
+        Ok(()) => Ok(alu),
+        Err(error) => Err(std::mem::discriminant(&error)),
+    }
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn optimized_program_matches_original_test(
+        program in prop::collection::vec(arbitrary_overflowing_instruction(), 1..24),
+        input in prop::collection::vec(b'1'..=b'9', 24),
+        arithmetic in arbitrary_arithmetic(),
+    ) {
+        let original = execute_with_error_kind(&input, &program, arithmetic);
+        let mut data_flow = build_data_flow(&program, DIGIT_INPUTS, arithmetic);
+        propagate_constants(&mut data_flow);
+        let optimized = generate_program(&data_flow);
+        prop_assert_eq!(execute_with_error_kind(&input, &optimized, arithmetic), original);
+    }
+
+    #[test]
+    fn optimization_passes_match_original_test(
+        program in prop::collection::vec(arbitrary_overflowing_instruction(), 1..24),
+        input in prop::collection::vec(b'1'..=b'9', 24),
+        arithmetic in arbitrary_arithmetic(),
+    ) {
+        let original = execute_with_error_kind(&input, &program, arithmetic);
+        let passes = &DEFAULT_PASSES[..DEFAULT_PASSES.len() - 1];
+        let (optimized, _) = optimize_program(&program, DIGIT_INPUTS, arithmetic, passes);
+        prop_assert_eq!(execute_with_error_kind(&input, &optimized, arithmetic), original);
+    }
+
+    #[test]
+    fn dead_node_elimination_preserves_z_test(
+        program in prop::collection::vec(arbitrary_overflowing_instruction(), 1..24),
+        input in prop::collection::vec(b'1'..=b'9', 24),
+        arithmetic in arbitrary_arithmetic(),
+    ) {
+        let original = execute_with_error_kind(&input, &program, arithmetic);
+        let (optimized, _) = optimize_program(&program, DIGIT_INPUTS, arithmetic, &DEFAULT_PASSES);
+        let z = execute_with_error_kind(&input, &optimized, arithmetic).map(|v| v.z);
+        prop_assert_eq!(z, original.map(|v| v.z));
+    }
+
+    #[test]
+    fn symbolic_paths_match_execution_test(
+        program in prop::collection::vec(arbitrary_non_faulting_instruction(), 1..24),
+        input in prop::collection::vec(b'1'..=b'9', 24),
+    ) {
+        let original = execute_with_error_kind(&input, &program, Arithmetic::Checked);
+        prop_assert!(original.is_ok());
+        let paths = execute_symbolic(&program, &DIGIT_INPUTS);
+        prop_assume!(paths.is_some());
+        let z = original.unwrap().z;
+        let digits: Vec<Value> = input.iter().map(|v| (v - b'0') as Value).collect();
+        let holds = |constraint: &Constraint| {
+            let left = constraint.left.evaluate(&digits);
+            left.is_some() && (left == constraint.right.evaluate(&digits)) == constraint.equal
+        };
+        let mut found = false;
+        for path in paths.unwrap().iter() {
+            if path.constraints.iter().all(holds) {
+                prop_assert_eq!(path.z.evaluate(&digits), Some(z));
+                found = true;
+            }
+        }
+        prop_assert!(found || z != 0);
+    }
+}
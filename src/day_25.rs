@@ -1,15 +1,15 @@
+use crate::grid::Grid;
+use crate::parse::ParseError;
 use std::io::BufRead;
 
-fn main() {
-    println!("{:?}", move_sea_cucumbers(std::io::stdin().lock()));
-}
+const DAY: u8 = 25;
 
-fn move_sea_cucumbers(buffer: impl BufRead) -> usize {
-    let mut grid = parse_grid(buffer);
+pub fn move_sea_cucumbers(buffer: impl BufRead) -> Result<usize, ParseError> {
+    let mut grid = parse_grid(buffer)?;
     let mut step = 0;
     loop {
         step += 1;
-        if [b'>', b'v']
+        if ['>', 'v']
             .iter()
             .map(|direction| move_half_step(*direction, &mut grid) as usize)
             .sum::<usize>()
@@ -18,32 +18,38 @@ fn move_sea_cucumbers(buffer: impl BufRead) -> usize {
             break;
         }
     }
-    step
+    Ok(step)
+}
+
+fn parse_grid(buffer: impl BufRead) -> Result<Grid<char>, ParseError> {
+    Grid::parse(DAY, buffer, "\">\", \"v\" or \".\"", |v| match v {
+        b'>' | b'v' | b'.' => Some(char::from(v)),
+        _ => None,
+    })
 }
 
-fn move_half_step(direction: u8, grid: &mut Grid) -> bool {
-    let mut moved = grid.clone();
-    moved.values.fill(0);
+fn move_half_step(direction: char, grid: &mut Grid<char>) -> bool {
+    let mut moved = Grid::new(grid.width, grid.height, false);
     let mut has_moved = false;
     for y in 0..grid.height {
         for x in 0..grid.width {
-            if moved.get(x, y) > 0 {
+            if *moved.get(x, y) {
                 continue;
             }
             let (next_x, next_y) = match direction {
-                b'>' => ((x + 1) % grid.width, y),
-                b'v' => (x, (y + 1) % grid.height),
+                '>' => grid.wrap(x as isize + 1, y as isize),
+                'v' => grid.wrap(x as isize, y as isize + 1),
                 _ => unreachable!(),
             };
-            if moved.get(next_x, next_y) > 0 {
+            if *moved.get(next_x, next_y) {
                 continue;
             }
-            let current = grid.get(x, y);
-            if current == direction && grid.get(next_x, next_y) == b'.' {
-                grid.set(x, y, b'.');
-                moved.set(x, y, 1);
+            let current = *grid.get(x, y);
+            if current == direction && *grid.get(next_x, next_y) == '.' {
+                grid.set(x, y, '.');
+                moved.set(x, y, true);
                 grid.set(next_x, next_y, current);
-                moved.set(next_x, next_y, 1);
+                moved.set(next_x, next_y, true);
                 has_moved = true;
             }
         }
@@ -51,82 +57,23 @@ fn move_half_step(direction: u8, grid: &mut Grid) -> bool {
     has_moved
 }
 
-#[cfg(test)]
-fn grid_to_string(grid: &Grid) -> String {
-    let mut result = String::new();
-    for y in 0..grid.height {
-        for x in 0..grid.width {
-            result.push(grid.get(x, y) as char);
-        }
-        result.push('\n');
-    }
-    result
-}
-
-fn parse_grid(buffer: impl BufRead) -> Grid {
-    let mut x = 0;
-    let mut height = 0;
-    let mut width = 0;
-    let mut values = Vec::new();
-    for symbol in buffer.bytes() {
-        match symbol.unwrap() {
-            b'\n' => {
-                width = width.max(x);
-                height += 1;
-                x = 0;
-            }
-            b'\r' => (),
-            v => {
-                x += 1;
-                values.push(v);
-            }
-        }
-    }
-    Grid {
-        width,
-        height,
-        values,
-    }
-}
-
-#[derive(Clone)]
-struct Grid {
-    values: Vec<u8>,
-    width: usize,
-    height: usize,
-}
-
-impl Grid {
-    fn index(&self, x: usize, y: usize) -> usize {
-        x + y * self.width
-    }
-
-    fn get(&self, x: usize, y: usize) -> u8 {
-        self.values[self.index(x, y)]
-    }
-
-    fn set(&mut self, x: usize, y: usize, value: u8) {
-        let i = self.index(x, y);
-        self.values[i] = value;
-    }
-}
-
 #[test]
 fn move_half_step_0_test() {
     let mut grid = parse_grid(
         r#"...>>>>>...
 "#
         .as_bytes(),
-    );
-    move_half_step(b'>', &mut grid);
+    )
+    .unwrap();
+    move_half_step('>', &mut grid);
     assert_eq!(
-        grid_to_string(&grid),
+        grid.to_string(),
         r#"...>>>>.>..
 "#
     );
-    move_half_step(b'>', &mut grid);
+    move_half_step('>', &mut grid);
     assert_eq!(
-        grid_to_string(&grid),
+        grid.to_string(),
         r#"...>>>.>.>.
 "#
     );
@@ -144,10 +91,11 @@ v.....>
 ..vvv..
 "#
         .as_bytes(),
-    );
-    move_half_step(b'>', &mut grid);
+    )
+    .unwrap();
+    move_half_step('>', &mut grid);
     assert_eq!(
-        grid_to_string(&grid),
+        grid.to_string(),
         r#"....>..
 .......
 >......
@@ -157,9 +105,9 @@ v.....>
 ..vvv..
 "#
     );
-    move_half_step(b'v', &mut grid);
+    move_half_step('v', &mut grid);
     assert_eq!(
-        grid_to_string(&grid),
+        grid.to_string(),
         r#"..vv>..
 .......
 >......
@@ -169,10 +117,10 @@ v.....>
 ....v..
 "#
     );
-    move_half_step(b'>', &mut grid);
-    move_half_step(b'v', &mut grid);
+    move_half_step('>', &mut grid);
+    move_half_step('v', &mut grid);
     assert_eq!(
-        grid_to_string(&grid),
+        grid.to_string(),
         r#"....v>.
 ..vv...
 .>.....
@@ -182,10 +130,10 @@ v>.....
 .......
 "#
     );
-    move_half_step(b'>', &mut grid);
-    move_half_step(b'v', &mut grid);
+    move_half_step('>', &mut grid);
+    move_half_step('v', &mut grid);
     assert_eq!(
-        grid_to_string(&grid),
+        grid.to_string(),
         r#"......>
 ..v.v..
 ..>v...
@@ -195,10 +143,10 @@ v......
 .......
 "#
     );
-    move_half_step(b'>', &mut grid);
-    move_half_step(b'v', &mut grid);
+    move_half_step('>', &mut grid);
+    move_half_step('v', &mut grid);
     assert_eq!(
-        grid_to_string(&grid),
+        grid.to_string(),
         r#">......
 ..v....
 ..>.v..
@@ -224,11 +172,12 @@ v.v..>>v.v
 ....v..v.>
 "#
         .as_bytes(),
-    );
-    move_half_step(b'>', &mut grid);
-    move_half_step(b'v', &mut grid);
+    )
+    .unwrap();
+    move_half_step('>', &mut grid);
+    move_half_step('v', &mut grid);
     assert_eq!(
-        grid_to_string(&grid),
+        grid.to_string(),
         r#"....>.>v.>
 v.v>.>v.v.
 >v>>..>v..
@@ -240,10 +189,10 @@ vv...>>vv.
 >.v.v..v.v
 "#
     );
-    move_half_step(b'>', &mut grid);
-    move_half_step(b'v', &mut grid);
+    move_half_step('>', &mut grid);
+    move_half_step('v', &mut grid);
     assert_eq!(
-        grid_to_string(&grid),
+        grid.to_string(),
         r#">.v.v>>..v
 v.v.>>vv..
 >v>.>.>.v.
@@ -270,5 +219,5 @@ v.v..>>v.v
 ....v..v.>
 "#
     .as_bytes();
-    assert_eq!(move_sea_cucumbers(buffer), 58);
+    assert_eq!(move_sea_cucumbers(buffer), Ok(58));
 }
@@ -1,38 +1,34 @@
+use crate::parse::{Line, Lines, ParseError};
 use std::io::BufRead;
-use std::str::FromStr;
 
-fn main() {
-    let position = move_submarine(std::io::stdin().lock());
-    println!("{:?}", position);
-    println!("{}", product(&position));
-}
+const DAY: u8 = 2;
 
-fn product(value: &Position) -> i64 {
+pub fn product(value: &Position) -> i64 {
     value.horizontal * value.depth
 }
 
 #[derive(Debug, Eq, PartialEq)]
-struct Position {
-    horizontal: i64,
-    depth: i64,
+pub struct Position {
+    pub horizontal: i64,
+    pub depth: i64,
 }
 
-fn move_submarine(buffer: impl BufRead) -> Position {
+pub fn move_submarine(buffer: impl BufRead) -> Result<Position, ParseError> {
     let mut position = Position {
         horizontal: 0,
         depth: 0,
     };
-    for line in buffer.lines() {
-        let command_str = line.unwrap();
-        let command = parse_command(command_str.as_str());
+    for line in Lines::new(DAY, buffer) {
+        let line = line?;
+        let command = parse_command(&line)?;
         match command.direction {
             "forward" => position.horizontal += command.shift,
             "down" => position.depth += command.shift,
             "up" => position.depth -= command.shift,
-            _ => (),
+            _ => unreachable!(),
         }
     }
-    position
+    Ok(position)
 }
 
 struct Command<'a> {
@@ -40,12 +36,15 @@ struct Command<'a> {
     shift: i64,
 }
 
-fn parse_command(value: &str) -> Command {
-    let separator = value.find(' ').unwrap();
-    Command {
-        direction: &value[0..separator],
-        shift: i64::from_str(&value[separator + 1..value.len()]).unwrap(),
+fn parse_command(line: &Line) -> Result<Command<'_>, ParseError> {
+    let (direction, shift) = line.split_once(&line.text, " ")?;
+    if !matches!(direction, "forward" | "down" | "up") {
+        return Err(line.error(direction, "forward, down or up"));
     }
+    Ok(Command {
+        direction,
+        shift: line.parse(shift, "number")?,
+    })
 }
 
 #[test]
@@ -58,7 +57,7 @@ down 8
 forward 2
 "#
     .as_bytes();
-    let position = move_submarine(buffer);
+    let position = move_submarine(buffer).unwrap();
     assert_eq!(
         position,
         Position {
@@ -68,3 +67,22 @@ forward 2
     );
     assert_eq!(product(&position), 150);
 }
+
+#[test]
+fn invalid_command_test() {
+    let buffer = r#"forward 5
+back 5
+"#
+    .as_bytes();
+    assert_eq!(
+        move_submarine(buffer),
+        Err(ParseError::new(2, 2, 1, "forward, down or up"))
+    );
+    let buffer = r#"forward x
+"#
+    .as_bytes();
+    assert_eq!(
+        move_submarine(buffer),
+        Err(ParseError::new(2, 1, 9, "number"))
+    );
+}
@@ -1,32 +1,28 @@
+use crate::parse::{Line, Lines, ParseError};
 use std::io::BufRead;
-use std::str::FromStr;
 
-fn main() {
-    let state = move_submarine_with_aim(std::io::stdin().lock());
-    println!("{:?}", state);
-    println!("{}", position_product(&state));
-}
+const DAY: u8 = 2;
 
-fn position_product(value: &State) -> i64 {
+pub fn position_product(value: &State) -> i64 {
     value.horizontal * value.depth
 }
 
 #[derive(Debug, Eq, PartialEq)]
-struct State {
-    horizontal: i64,
-    depth: i64,
+pub struct State {
+    pub horizontal: i64,
+    pub depth: i64,
     aim: i64,
 }
 
-fn move_submarine_with_aim(buffer: impl BufRead) -> State {
+pub fn move_submarine_with_aim(buffer: impl BufRead) -> Result<State, ParseError> {
     let mut state = State {
         horizontal: 0,
         depth: 0,
         aim: 0,
     };
-    for line in buffer.lines() {
-        let command_str = line.unwrap();
-        let command = parse_command(command_str.as_str());
+    for line in Lines::new(DAY, buffer) {
+        let line = line?;
+        let command = parse_command(&line)?;
         match command.direction {
             "forward" => {
                 state.horizontal += command.shift;
@@ -34,10 +30,10 @@ fn move_submarine_with_aim(buffer: impl BufRead) -> State {
             }
             "down" => state.aim += command.shift,
             "up" => state.aim -= command.shift,
-            _ => (),
+            _ => unreachable!(),
         }
     }
-    state
+    Ok(state)
 }
 
 struct Command<'a> {
@@ -45,12 +41,15 @@ struct Command<'a> {
     shift: i64,
 }
 
-fn parse_command(value: &str) -> Command {
-    let separator = value.find(' ').unwrap();
-    Command {
-        direction: &value[0..separator],
-        shift: i64::from_str(&value[separator + 1..value.len()]).unwrap(),
+fn parse_command(line: &Line) -> Result<Command<'_>, ParseError> {
+    let (direction, shift) = line.split_once(&line.text, " ")?;
+    if !matches!(direction, "forward" | "down" | "up") {
+        return Err(line.error(direction, "forward, down or up"));
     }
+    Ok(Command {
+        direction,
+        shift: line.parse(shift, "number")?,
+    })
 }
 
 #[test]
@@ -63,7 +62,7 @@ down 8
 forward 2
 "#
     .as_bytes();
-    let position = move_submarine_with_aim(buffer);
+    let position = move_submarine_with_aim(buffer).unwrap();
     assert_eq!(
         position,
         State {
@@ -1,26 +1,35 @@
+use crate::parse::{Lines, ParseError};
 use std::io::BufRead;
 
-fn main() {
-    println!("{}", calculate_power_consumption(std::io::stdin().lock()));
-}
+const DAY: u8 = 3;
 
-fn calculate_power_consumption(buffer: impl BufRead) -> u64 {
+pub fn calculate_power_consumption(buffer: impl BufRead) -> Result<u64, ParseError> {
     let mut zero_bit_counters: [usize; 64] = [0; 64];
     let mut one_bit_counters: [usize; 64] = [0; 64];
     let mut length = 0;
-    for line in buffer.lines() {
-        let measurement = line.unwrap();
+    for line in Lines::new(DAY, buffer) {
+        let line = line?;
+        let measurement = line.text.as_str();
+        if measurement.len() > zero_bit_counters.len() {
+            return Err(line.error_at_char(
+                measurement,
+                zero_bit_counters.len(),
+                "at most 64 binary digits",
+            ));
+        }
         length = length.max(measurement.len());
         for (index, bit) in measurement.char_indices() {
             match bit {
                 '0' => zero_bit_counters[index] += 1,
                 '1' => one_bit_counters[index] += 1,
-                _ => (),
+                _ => return Err(line.error(&measurement[index..], "binary digit")),
             }
         }
     }
-    get_gamma_rate(&zero_bit_counters[0..length], &one_bit_counters[0..length])
-        * get_epsilon_rate(&zero_bit_counters[0..length], &one_bit_counters[0..length])
+    Ok(
+        get_gamma_rate(&zero_bit_counters[0..length], &one_bit_counters[0..length])
+            * get_epsilon_rate(&zero_bit_counters[0..length], &one_bit_counters[0..length]),
+    )
 }
 
 fn get_gamma_rate(zero_bit_counters: &[usize], one_bit_counters: &[usize]) -> u64 {
@@ -74,5 +83,5 @@ fn example_test() {
 01010
 "#
     .as_bytes();
-    assert_eq!(calculate_power_consumption(buffer), 198);
+    assert_eq!(calculate_power_consumption(buffer), Ok(198));
 }
@@ -1,15 +1,29 @@
+use crate::parse::{Lines, ParseError};
 use std::io::BufRead;
 
-fn main() {
-    println!("{}", calculate_life_support_rating(std::io::stdin().lock()));
-}
+const DAY: u8 = 3;
 
-fn calculate_life_support_rating(buffer: impl BufRead) -> u64 {
-    let mut values = Vec::new();
-    for line in buffer.lines() {
-        values.push(Vec::from(line.unwrap().as_bytes()));
+pub fn calculate_life_support_rating(buffer: impl BufRead) -> Result<u64, ParseError> {
+    let mut values: Vec<Vec<u8>> = Vec::new();
+    for line in Lines::new(DAY, buffer) {
+        let line = line?;
+        if line.text.is_empty() {
+            return Err(line.error(&line.text, "binary digit"));
+        }
+        let length = values.first().map(|v| v.len()).unwrap_or(64);
+        if line.text.len() > length || (!values.is_empty() && line.text.len() < length) {
+            return Err(line.error_at_char(
+                &line.text,
+                length,
+                format!("{} binary digits", length),
+            ));
+        }
+        if let Some(index) = line.text.find(|v| v != '0' && v != '1') {
+            return Err(line.error(&line.text[index..], "binary digit"));
+        }
+        values.push(line.text.into_bytes());
     }
-    oxygen_generator_rating(values.clone()) * co2_scrubber_rating(values)
+    Ok(oxygen_generator_rating(values.clone()) * co2_scrubber_rating(values))
 }
 
 fn oxygen_generator_rating(values: Vec<Vec<u8>>) -> u64 {
@@ -22,7 +36,7 @@ fn co2_scrubber_rating(values: Vec<Vec<u8>>) -> u64 {
 
 fn filter_values(get_bit: &impl Fn(usize, usize) -> bool, mut values: Vec<Vec<u8>>) -> u64 {
     let mut index = 0;
-    while values.len() > 1 {
+    while values.len() > 1 && index < values[0].len() {
         let (zero_count, one_count) = count_bits_at(&values, index);
         let bit = if get_bit(zero_count, one_count) {
             b'1'
@@ -67,5 +81,23 @@ fn example_test() {
 01010
 "#
     .as_bytes();
-    assert_eq!(calculate_life_support_rating(buffer), 230);
+    assert_eq!(calculate_life_support_rating(buffer), Ok(230));
+}
+
+#[test]
+fn parse_error_test() {
+    let buffer = "0101\na\u{e9}\u{e9}\u{e9}\n".as_bytes();
+    assert_eq!(
+        calculate_life_support_rating(buffer),
+        Err(ParseError::new(DAY, 2, 8, "4 binary digits"))
+    );
+    assert_eq!(
+        calculate_life_support_rating("\n\n".as_bytes()),
+        Err(ParseError::new(DAY, 1, 1, "binary digit"))
+    );
+}
+
+#[test]
+fn equal_values_test() {
+    assert_eq!(calculate_life_support_rating("01\n01\n".as_bytes()), Ok(0));
 }
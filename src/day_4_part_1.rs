@@ -1,12 +1,7 @@
+use crate::parse::{read_numbers, Lines, ParseError};
 use std::io::BufRead;
-use std::str::FromStr;
 
-fn main() {
-    println!(
-        "{:?}",
-        find_first_bingo_winner_score(std::io::stdin().lock())
-    );
-}
+const DAY: u8 = 4;
 
 const ROW_SIZE: usize = 5;
 const COLUMN_SIZE: usize = ROW_SIZE;
@@ -20,18 +15,19 @@ struct Board {
     marked: [bool; BOARD_SIZE],
 }
 
-fn find_first_bingo_winner_score(mut buffer: impl BufRead) -> Option<u64> {
-    let numbers = read_numbers(&mut buffer);
-    let mut boards = read_boards(buffer);
+pub fn find_first_bingo_winner_score(buffer: impl BufRead) -> Result<Option<u64>, ParseError> {
+    let mut lines = Lines::new(DAY, buffer);
+    let numbers: Vec<u8> = read_numbers(&mut lines)?;
+    let mut boards = read_boards(&mut lines)?;
     for number in numbers.iter() {
         for board in boards.iter_mut() {
             mark_number(*number, board);
             if is_winner(board) {
-                return Some(*number as u64 * get_unmarked_sum(board));
+                return Ok(Some(*number as u64 * get_unmarked_sum(board)));
             }
         }
     }
-    None
+    Ok(None)
 }
 
 fn mark_number(number: u8, board: &mut Board) {
@@ -55,7 +51,6 @@ fn is_winner(board: &Board) -> bool {
 
 fn get_unmarked_sum(board: &Board) -> u64 {
     (0..BOARD_SIZE)
-        .into_iter()
         .filter(|i| !board.marked[*i])
         .map(|i| board.numbers[i] as u64)
         .sum()
@@ -69,51 +64,32 @@ fn get_column_index(index: usize) -> usize {
     index % COLUMN_SIZE
 }
 
-fn read_numbers(buffer: &mut impl BufRead) -> Vec<u8> {
-    let mut line = String::new();
-    buffer.read_line(&mut line).unwrap();
-    strip_line_break(&mut line);
-    line.split(',')
-        .by_ref()
-        .map(|v| u8::from_str(v).unwrap())
-        .collect()
-}
-
-fn strip_line_break(line: &mut String) {
-    if line.ends_with('\n') {
-        line.pop();
-    }
-    if line.ends_with('\r') {
-        line.pop();
-    }
-}
-
-fn read_boards(buffer: impl BufRead) -> Vec<Board> {
+fn read_boards(lines: &mut Lines<impl BufRead>) -> Result<Vec<Board>, ParseError> {
     let mut result = Vec::new();
-    let mut board = Board {
-        numbers: [0; BOARD_SIZE],
-        column_counters: [0; 5],
-        row_counters: [0; 5],
-        marked: [false; BOARD_SIZE],
-    };
-    let mut index = 0;
-    for line in buffer.lines() {
-        let row = line.unwrap();
-        if row.is_empty() {
-            if index > 0 {
-                result.push(board.clone());
-                board.numbers.fill(0);
-                index = 0;
-            }
+    while let Some(line) = lines.next() {
+        let mut line = line?;
+        if line.text.is_empty() {
             continue;
         }
-        row.split(' ').filter(|v| !v.is_empty()).for_each(|v| {
-            board.numbers[index] = u8::from_str(v).unwrap();
-            index += 1;
-        });
+        let mut board = Board {
+            numbers: [0; BOARD_SIZE],
+            column_counters: [0; 5],
+            row_counters: [0; 5],
+            marked: [false; BOARD_SIZE],
+        };
+        for row in 0..COLUMN_SIZE {
+            if row > 0 {
+                line = lines.next_line("board row")?;
+            }
+            let numbers: Vec<u8> = line.parse_numbers(&line.text, ' ')?;
+            if numbers.len() != ROW_SIZE {
+                return Err(line.error_at_end(&line.text, format!("{} numbers", ROW_SIZE)));
+            }
+            board.numbers[row * ROW_SIZE..(row + 1) * ROW_SIZE].copy_from_slice(&numbers);
+        }
+        result.push(board);
     }
-    result.push(board.clone());
-    result
+    Ok(result)
 }
 
 #[test]
@@ -139,5 +115,5 @@ fn example_test() {
  2  0 12  3  7
 "#
     .as_bytes();
-    assert_eq!(find_first_bingo_winner_score(buffer), Some(4512));
+    assert_eq!(find_first_bingo_winner_score(buffer), Ok(Some(4512)));
 }
@@ -1,17 +1,15 @@
+use crate::parse::{Line, Lines, ParseError};
 use std::collections::BTreeMap;
 use std::io::BufRead;
-use std::str::FromStr;
 
-fn main() {
-    println!("{}", count_points_of_intersection(std::io::stdin().lock()));
-}
+const DAY: u8 = 5;
 
-fn count_points_of_intersection(buffer: impl BufRead) -> usize {
+pub fn count_points_of_intersection(buffer: impl BufRead) -> Result<usize, ParseError> {
     let mut area: BTreeMap<Point, usize> = BTreeMap::new();
-    for line in buffer.lines() {
-        fill_segment(&parse_segment(&line.unwrap()), &mut area);
+    for line in Lines::new(DAY, buffer) {
+        fill_segment(&parse_segment(&line?)?, &mut area);
     }
-    area.values().filter(|v| **v >= 2).count()
+    Ok(area.values().filter(|v| **v >= 2).count())
 }
 
 fn fill_segment(segment: &Segment, area: &mut BTreeMap<Point, usize>) {
@@ -53,23 +51,20 @@ struct Segment {
     end: Point,
 }
 
-fn parse_segment(line: &str) -> Segment {
-    let mut segment: [[i64; 2]; 2] = [[0; 2], [0; 2]];
-    for (point_str, point) in line.split(" -> ").zip(segment.iter_mut()) {
-        for (str, coordinate) in point_str.split(',').zip(point.iter_mut()) {
-            *coordinate = i64::from_str(str).unwrap();
-        }
-    }
-    Segment {
-        begin: Point {
-            x: segment[0][0],
-            y: segment[0][1],
-        },
-        end: Point {
-            x: segment[1][0],
-            y: segment[1][1],
-        },
-    }
+fn parse_segment(line: &Line) -> Result<Segment, ParseError> {
+    let (begin, end) = line.split_once(&line.text, " -> ")?;
+    Ok(Segment {
+        begin: parse_point(line, begin)?,
+        end: parse_point(line, end)?,
+    })
+}
+
+fn parse_point(line: &Line, text: &str) -> Result<Point, ParseError> {
+    let (x, y) = line.split_once(text, ",")?;
+    Ok(Point {
+        x: line.parse(x, "number")?,
+        y: line.parse(y, "number")?,
+    })
 }
 
 #[test]
@@ -86,5 +81,5 @@ fn example_test() {
 5,5 -> 8,2
 "#
     .as_bytes();
-    assert_eq!(count_points_of_intersection(buffer), 5);
+    assert_eq!(count_points_of_intersection(buffer), Ok(5));
 }
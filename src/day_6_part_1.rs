@@ -1,24 +1,41 @@
+use crate::parse::{Lines, ParseError};
+#[cfg(test)]
+use proptest::prelude::*;
 use std::io::BufRead;
-use std::str::FromStr;
 
-fn main() {
-    println!(
-        "{}",
-        simulate_lanternfish_population(std::io::stdin().lock())
-    );
-}
+const DAY: u8 = 6;
 
 const MAX_DAYS: usize = 80;
 const OLD_FISH_DAYS: usize = 6;
 const NEW_FISH_DAYS: usize = 8;
 
-fn simulate_lanternfish_population(buffer: impl BufRead) -> u64 {
+pub fn simulate_lanternfish_population(buffer: impl BufRead) -> Result<u64, ParseError> {
+    Ok(count_fish(&read_timers(buffer)?, MAX_DAYS))
+}
+
+pub fn simulate_lanternfish_population_per_fish(buffer: impl BufRead) -> Result<u64, ParseError> {
+    Ok(count_fish_per_fish(&read_timers(buffer)?, MAX_DAYS))
+}
+
+fn read_timers(buffer: impl BufRead) -> Result<Vec<usize>, ParseError> {
+    let line = Lines::new(DAY, buffer).next_line("comma separated numbers")?;
+    let mut timers = Vec::new();
+    for text in line.text.split(',') {
+        let days: usize = line.parse(text, "number")?;
+        if days > NEW_FISH_DAYS {
+            return Err(line.error(text, format!("at most {} days", NEW_FISH_DAYS)));
+        }
+        timers.push(days);
+    }
+    Ok(timers)
+}
+
+fn count_fish(timers: &[usize], days: usize) -> u64 {
     let mut fish_counters: [u64; NEW_FISH_DAYS + 1] = [0; NEW_FISH_DAYS + 1];
-    for value in buffer.lines().next().unwrap().unwrap().split(',') {
-        let days = u8::from_str(value).unwrap();
-        fish_counters[days as usize] += 1;
+    for timer in timers.iter() {
+        fish_counters[*timer] += 1;
     }
-    for day in 0..MAX_DAYS {
+    for day in 0..days {
         let zero_day_index = day % fish_counters.len();
         let zero_day_fish = fish_counters[zero_day_index];
         fish_counters[zero_day_index] = 0;
@@ -28,10 +45,46 @@ fn simulate_lanternfish_population(buffer: impl BufRead) -> u64 {
     fish_counters.iter().sum()
 }
 
+fn count_fish_per_fish(timers: &[usize], days: usize) -> u64 {
+    let mut fish = timers.to_vec();
+    for _ in 0..days {
+        let mut new_fish = 0;
+        for timer in fish.iter_mut() {
+            if *timer == 0 {
+                *timer = OLD_FISH_DAYS;
+                new_fish += 1;
+            } else {
+                *timer -= 1;
+            }
+        }
+        fish.extend(std::iter::repeat_n(NEW_FISH_DAYS, new_fish));
+    }
+    fish.len() as u64
+}
+
 #[test]
 fn example_test() {
     let buffer = r#"3,4,3,1,2
 "#
     .as_bytes();
-    assert_eq!(simulate_lanternfish_population(buffer), 5934);
+    assert_eq!(simulate_lanternfish_population(buffer), Ok(5934));
+}
+
+#[test]
+fn example_per_fish_test() {
+    let buffer = r#"3,4,3,1,2
+"#
+    .as_bytes();
+    assert_eq!(simulate_lanternfish_population_per_fish(buffer), Ok(5934));
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn count_fish_matches_per_fish_test(
+        timers in prop::collection::vec(0..=NEW_FISH_DAYS, 1..20),
+        days in 0..MAX_DAYS,
+    ) {
+        prop_assert_eq!(count_fish(&timers, days), count_fish_per_fish(&timers, days));
+    }
 }
@@ -1,22 +1,21 @@
+use crate::parse::{Lines, ParseError};
 use std::io::BufRead;
-use std::str::FromStr;
 
-fn main() {
-    println!(
-        "{}",
-        simulate_lanternfish_population(std::io::stdin().lock())
-    );
-}
+const DAY: u8 = 6;
 
 const MAX_DAYS: usize = 256;
 const OLD_FISH_DAYS: usize = 6;
 const NEW_FISH_DAYS: usize = 8;
 
-fn simulate_lanternfish_population(buffer: impl BufRead) -> u64 {
+pub fn simulate_lanternfish_population(buffer: impl BufRead) -> Result<u64, ParseError> {
     let mut fish_counters: [u64; NEW_FISH_DAYS + 1] = [0; NEW_FISH_DAYS + 1];
-    for value in buffer.lines().next().unwrap().unwrap().split(',') {
-        let days = u8::from_str(value).unwrap();
-        fish_counters[days as usize] += 1;
+    let line = Lines::new(DAY, buffer).next_line("comma separated numbers")?;
+    for text in line.text.split(',') {
+        let days: usize = line.parse(text, "number")?;
+        if days > NEW_FISH_DAYS {
+            return Err(line.error(text, format!("at most {} days", NEW_FISH_DAYS)));
+        }
+        fish_counters[days] += 1;
     }
     for day in 0..MAX_DAYS {
         let zero_day_index = day % fish_counters.len();
@@ -25,7 +24,7 @@ fn simulate_lanternfish_population(buffer: impl BufRead) -> u64 {
         fish_counters[(OLD_FISH_DAYS + day + 1) % fish_counters.len()] += zero_day_fish;
         fish_counters[(NEW_FISH_DAYS + day + 1) % fish_counters.len()] += zero_day_fish;
     }
-    fish_counters.iter().sum()
+    Ok(fish_counters.iter().sum())
 }
 
 #[test]
@@ -33,5 +32,5 @@ fn example_test() {
     let buffer = r#"3,4,3,1,2
 "#
     .as_bytes();
-    assert_eq!(simulate_lanternfish_population(buffer), 26984457539);
+    assert_eq!(simulate_lanternfish_population(buffer), Ok(26984457539));
 }
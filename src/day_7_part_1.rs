@@ -1,26 +1,17 @@
+use crate::parse::{read_numbers, Lines, ParseError};
 use std::io::BufRead;
-use std::str::FromStr;
 
-fn main() {
-    println!("{}", align_crabs_position(std::io::stdin().lock()));
-}
+const DAY: u8 = 7;
 
-fn align_crabs_position(buffer: impl BufRead) -> i64 {
-    let mut positions: Vec<i64> = buffer
-        .lines()
-        .next()
-        .unwrap()
-        .unwrap()
-        .split(',')
-        .map(|v| i64::from_str(v).unwrap())
-        .collect();
+pub fn align_crabs_position(buffer: impl BufRead) -> Result<i64, ParseError> {
+    let mut positions: Vec<i64> = read_numbers(&mut Lines::new(DAY, buffer))?;
     positions.sort_unstable();
     let target = positions[positions.len() / 2];
     if positions.len() % 2 == 1 {
-        return calculate_fuel_consumption(target, &positions);
+        return Ok(calculate_fuel_consumption(target, &positions));
     }
-    calculate_fuel_consumption(target, &positions)
-        .min(calculate_fuel_consumption(target + 1, &positions))
+    Ok(calculate_fuel_consumption(target, &positions)
+        .min(calculate_fuel_consumption(target + 1, &positions)))
 }
 
 fn calculate_fuel_consumption(target: i64, positions: &[i64]) -> i64 {
@@ -32,5 +23,5 @@ fn example_test() {
     let buffer = r#"16,1,2,0,4,2,7,1,2,14
 "#
     .as_bytes();
-    assert_eq!(align_crabs_position(buffer), 37);
+    assert_eq!(align_crabs_position(buffer), Ok(37));
 }
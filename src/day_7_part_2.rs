@@ -1,37 +1,34 @@
+use crate::parse::{read_numbers, Lines, ParseError};
+#[cfg(test)]
+use proptest::prelude::*;
 use std::io::BufRead;
-use std::str::FromStr;
 
-fn main() {
-    println!("{}", align_crabs_position(std::io::stdin().lock()));
+const DAY: u8 = 7;
+
+type FindMin = fn(i64, i64, &dyn Fn(i64) -> i64) -> i64;
+
+pub fn align_crabs_position(buffer: impl BufRead) -> Result<i64, ParseError> {
+    align_crabs_position_with(buffer, find_min_linear)
 }
 
-fn align_crabs_position(buffer: impl BufRead) -> i64 {
-    let positions: Vec<i64> = buffer
-        .lines()
-        .next()
-        .unwrap()
-        .unwrap()
-        .split(',')
-        .map(|v| i64::from_str(v).unwrap())
-        .collect();
-    find_optimal_fuel_consumption(
-        *positions.iter().min().unwrap(),
-        *positions.iter().max().unwrap(),
-        &positions,
-    )
+pub fn align_crabs_position_with_ternary_search(buffer: impl BufRead) -> Result<i64, ParseError> {
+    align_crabs_position_with(buffer, find_min_ternary)
 }
 
-fn find_optimal_fuel_consumption(min: i64, max: i64, positions: &[i64]) -> i64 {
-    find_min(min, max, |v| calculate_fuel_consumption(v, positions))
+fn align_crabs_position_with(buffer: impl BufRead, find_min: FindMin) -> Result<i64, ParseError> {
+    let positions: Vec<i64> = read_numbers(&mut Lines::new(DAY, buffer))?;
+    Ok(find_min(
+        *positions.iter().min().unwrap(),
+        *positions.iter().max().unwrap(),
+        &|v| calculate_fuel_consumption(v, &positions),
+    ))
 }
 
-#[cfg(not(feature = "day_7_part_2_log"))]
-fn find_min(min: i64, max: i64, f: impl Fn(i64) -> i64) -> i64 {
+fn find_min_linear(min: i64, max: i64, f: &dyn Fn(i64) -> i64) -> i64 {
     (min..=max).map(f).min().unwrap()
 }
 
-#[cfg(feature = "day_7_part_2_log")]
-fn find_min(mut min: i64, mut max: i64, f: impl Fn(i64) -> i64) -> i64 {
+fn find_min_ternary(mut min: i64, mut max: i64, f: &dyn Fn(i64) -> i64) -> i64 {
     let mut min_value = f(min).min(f(max));
     loop {
         let section = ((max - min) / 3).max(1);
@@ -73,5 +70,26 @@ fn example_test() {
     let buffer = r#"16,1,2,0,4,2,7,1,2,14
 "#
     .as_bytes();
-    assert_eq!(align_crabs_position(buffer), 168);
+    assert_eq!(align_crabs_position(buffer), Ok(168));
+}
+
+#[test]
+fn example_with_ternary_search_test() {
+    let buffer = r#"16,1,2,0,4,2,7,1,2,14
+"#
+    .as_bytes();
+    assert_eq!(align_crabs_position_with_ternary_search(buffer), Ok(168));
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn find_min_linear_matches_ternary_test(
+        positions in prop::collection::vec(0i64..2000, 1..50),
+    ) {
+        let min = *positions.iter().min().unwrap();
+        let max = *positions.iter().max().unwrap();
+        let f = |v| calculate_fuel_consumption(v, &positions);
+        prop_assert_eq!(find_min_linear(min, max, &f), find_min_ternary(min, max, &f));
+    }
 }
@@ -1,14 +1,13 @@
+use crate::parse::{Lines, ParseError};
 use std::io::BufRead;
 
-fn main() {
-    println!("{}", count_digits(std::io::stdin().lock()));
-}
+const DAY: u8 = 8;
 
-fn count_digits(buffer: impl BufRead) -> usize {
+pub fn count_digits(buffer: impl BufRead) -> Result<usize, ParseError> {
     let mut result = 0;
-    for line in buffer.lines() {
-        let line_str = line.unwrap();
-        let (_, output) = line_str.split_once(" | ").unwrap();
+    for line in Lines::new(DAY, buffer) {
+        let line = line?;
+        let (_, output) = line.split_once(&line.text, " | ")?;
         for word in output.split(' ') {
             match word.len() {
                 2 | 4 | 3 | 7 => result += 1,
@@ -16,7 +15,7 @@ fn count_digits(buffer: impl BufRead) -> usize {
             }
         }
     }
-    result
+    Ok(result)
 }
 
 #[test]
@@ -34,5 +33,5 @@ egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb
 gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
 "#
         .as_bytes();
-    assert_eq!(count_digits(buffer), 26);
+    assert_eq!(count_digits(buffer), Ok(26));
 }
@@ -1,17 +1,19 @@
+use crate::parse::{Line, Lines, ParseError};
 use std::collections::BTreeMap;
 use std::io::BufRead;
-use std::str::FromStr;
 
-fn main() {
-    println!("{}", sum_decoded_numbers(std::io::stdin().lock()));
-}
+const DAY: u8 = 8;
 
-fn sum_decoded_numbers(buffer: impl BufRead) -> u64 {
-    buffer.lines().map(|v| decode_line(&v.unwrap())).sum()
+pub fn sum_decoded_numbers(buffer: impl BufRead) -> Result<u64, ParseError> {
+    let mut result = 0;
+    for line in Lines::new(DAY, buffer) {
+        result += decode_line(&line?)?;
+    }
+    Ok(result)
 }
 
-fn decode_line(line: &str) -> u64 {
-    let (signal, output) = line.split_once(" | ").unwrap();
+fn decode_line(line: &Line) -> Result<u64, ParseError> {
+    let (signal, output) = line.split_once(&line.text, " | ")?;
     let mut signals = BTreeMap::new();
     let mut digits = [None; 10];
     for word in signal.split(' ') {
@@ -20,12 +22,16 @@ fn decode_line(line: &str) -> u64 {
             digits[digit as usize] = Some(word.as_bytes());
         }
     }
+    let (one, four) = match (digits[1], digits[4]) {
+        (Some(one), Some(four)) => (one, four),
+        _ => return Err(line.error(signal, "signal patterns for digits 1 and 4")),
+    };
     for word in signal.split(' ') {
         match word.len() {
             5 => {
-                let digit = if contains_bytes(word.as_bytes(), digits[1].unwrap()) {
+                let digit = if contains_bytes(word.as_bytes(), one) {
                     3
-                } else if contains_at_least_n_bytes(word.as_bytes(), digits[4].unwrap(), 3) {
+                } else if contains_at_least_n_bytes(word.as_bytes(), four, 3) {
                     5
                 } else {
                     2
@@ -33,9 +39,9 @@ fn decode_line(line: &str) -> u64 {
                 signals.insert(make_word_key(word), digit);
             }
             6 => {
-                let digit = if contains_bytes(word.as_bytes(), digits[4].unwrap()) {
+                let digit = if contains_bytes(word.as_bytes(), four) {
                     9
-                } else if contains_bytes(word.as_bytes(), digits[1].unwrap()) {
+                } else if contains_bytes(word.as_bytes(), one) {
                     0
                 } else {
                     6
@@ -45,13 +51,14 @@ fn decode_line(line: &str) -> u64 {
             _ => (),
         }
     }
-    u64::from_str(
-        &output
-            .split(' ')
-            .map(|word| (signals[&make_word_key(word)] + b'0') as char)
-            .collect::<String>(),
-    )
-    .unwrap()
+    let mut result = 0;
+    for word in output.split(' ') {
+        match signals.get(&make_word_key(word)) {
+            Some(digit) => result = result * 10 + *digit as u64,
+            None => return Err(line.error(word, "known signal pattern")),
+        }
+    }
+    Ok(result)
 }
 
 fn get_digit_by_signal_len(value: usize) -> Option<u8> {
@@ -71,7 +78,7 @@ fn make_word_key(word: &str) -> Vec<u8> {
 }
 
 fn contains_bytes(word: &[u8], bytes: &[u8]) -> bool {
-    bytes.iter().all(|w| word.iter().any(|v| *v == *w))
+    bytes.iter().all(|w| word.contains(w))
 }
 
 fn contains_at_least_n_bytes(word: &[u8], bytes: &[u8], n: usize) -> bool {
@@ -97,5 +104,5 @@ egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb
 gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
 "#
         .as_bytes();
-    assert_eq!(sum_decoded_numbers(buffer), 61229);
+    assert_eq!(sum_decoded_numbers(buffer), Ok(61229));
 }
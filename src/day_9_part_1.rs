@@ -1,11 +1,11 @@
+use crate::grid::Grid;
+use crate::parse::{parse_digit, ParseError};
 use std::io::BufRead;
 
-fn main() {
-    println!("{}", calculate_total_risk(std::io::stdin().lock()));
-}
+const DAY: u8 = 9;
 
-fn calculate_total_risk(buffer: impl BufRead) -> u64 {
-    let heightmap = parse_heightmap(buffer);
+pub fn calculate_total_risk(buffer: impl BufRead) -> Result<u64, ParseError> {
+    let heightmap = Grid::parse(DAY, buffer, "digit", parse_digit)?;
     let mut risk = 0;
     for y in 0..heightmap.height {
         for x in 0..heightmap.width {
@@ -14,59 +14,20 @@ fn calculate_total_risk(buffer: impl BufRead) -> u64 {
             }
         }
     }
-    risk
+    Ok(risk)
 }
 
-fn get_low_point(heightmap: &Heightmap, x: usize, y: usize) -> Option<u8> {
-    let value = heightmap.value(x, y);
-    if x > 0 && heightmap.value(x - 1, y) <= value {
-        return None;
-    }
-    if y > 0 && heightmap.value(x, y - 1) <= value {
-        return None;
-    }
-    if y + 1 < heightmap.height && heightmap.value(x, y + 1) <= value {
-        return None;
-    }
-    if x + 1 < heightmap.width && heightmap.value(x + 1, y) <= value {
+fn get_low_point(heightmap: &Grid<u8>, x: usize, y: usize) -> Option<u8> {
+    let value = *heightmap.get(x, y);
+    if heightmap
+        .neighbours_4(x, y)
+        .any(|(neighbour_x, neighbour_y)| *heightmap.get(neighbour_x, neighbour_y) <= value)
+    {
         return None;
     }
     Some(value)
 }
 
-struct Heightmap {
-    values: Vec<u8>,
-    width: usize,
-    height: usize,
-}
-
-impl Heightmap {
-    fn index(&self, x: usize, y: usize) -> usize {
-        x + y * self.width
-    }
-
-    fn value(&self, x: usize, y: usize) -> u8 {
-        self.values[self.index(x, y)]
-    }
-}
-
-fn parse_heightmap(buffer: impl BufRead) -> Heightmap {
-    let mut values = Vec::new();
-    let mut height = 0;
-    for symbol in buffer.bytes() {
-        match symbol.unwrap() {
-            b'\r' => (),
-            b'\n' => height += 1,
-            v => values.push(v - b'0'),
-        }
-    }
-    Heightmap {
-        width: values.len() / height,
-        height,
-        values,
-    }
-}
-
 #[test]
 fn example_test() {
     let buffer = r#"2199943210
@@ -76,5 +37,5 @@ fn example_test() {
 9899965678
 "#
     .as_bytes();
-    assert_eq!(calculate_total_risk(buffer), 15);
+    assert_eq!(calculate_total_risk(buffer), Ok(15));
 }
@@ -0,0 +1,882 @@
+use crate::big_uint::BigUint;
+use crate::day_11_part_2::update_octopus_energy;
+use crate::day_16_part_2::{encode_packet, sub_packets, Expression, LengthType, Packet};
+use crate::grid::Grid;
+use crate::random::Random;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+pub struct Generator {
+    pub day: u8,
+    pub size: usize,
+    pub generate: fn(&mut Random, usize) -> String,
+}
+
+pub const GENERATORS: &[Generator] = &[
+    Generator {
+        day: 1,
+        size: 2000,
+        generate: generate_depths,
+    },
+    Generator {
+        day: 2,
+        size: 1000,
+        generate: generate_commands,
+    },
+    Generator {
+        day: 3,
+        size: 1000,
+        generate: generate_diagnostic_report,
+    },
+    Generator {
+        day: 4,
+        size: 100,
+        generate: generate_bingo,
+    },
+    Generator {
+        day: 5,
+        size: 500,
+        generate: generate_vent_lines,
+    },
+    Generator {
+        day: 6,
+        size: 300,
+        generate: generate_lanternfish,
+    },
+    Generator {
+        day: 7,
+        size: 1000,
+        generate: generate_crabs,
+    },
+    Generator {
+        day: 8,
+        size: 200,
+        generate: generate_signal_patterns,
+    },
+    Generator {
+        day: 9,
+        size: 100,
+        generate: generate_heightmap,
+    },
+    Generator {
+        day: 10,
+        size: 100,
+        generate: generate_navigation_subsystem,
+    },
+    Generator {
+        day: 11,
+        size: 10,
+        generate: generate_octopus_grid,
+    },
+    Generator {
+        day: 12,
+        size: 20,
+        generate: generate_cave_graph,
+    },
+    Generator {
+        day: 13,
+        size: 800,
+        generate: generate_transparent_paper,
+    },
+    Generator {
+        day: 14,
+        size: 20,
+        generate: generate_polymer,
+    },
+    Generator {
+        day: 15,
+        size: 100,
+        generate: generate_risk_map,
+    },
+    Generator {
+        day: 16,
+        size: 50,
+        generate: generate_bits_packet,
+    },
+    Generator {
+        day: 17,
+        size: 150,
+        generate: generate_target_area,
+    },
+    Generator {
+        day: 18,
+        size: 100,
+        generate: generate_snailfish_numbers,
+    },
+    Generator {
+        day: 19,
+        size: 30,
+        generate: generate_scanners,
+    },
+    Generator {
+        day: 20,
+        size: 100,
+        generate: generate_image,
+    },
+    Generator {
+        day: 21,
+        size: 10,
+        generate: generate_starting_positions,
+    },
+    Generator {
+        day: 22,
+        size: 420,
+        generate: generate_cuboids,
+    },
+    Generator {
+        day: 23,
+        size: 2,
+        generate: generate_burrow,
+    },
+    Generator {
+        day: 24,
+        size: 14,
+        generate: generate_monad,
+    },
+    Generator {
+        day: 25,
+        size: 137,
+        generate: generate_sea_cucumbers,
+    },
+];
+
+pub fn find_generator(day: u8) -> Option<&'static Generator> {
+    GENERATORS.iter().find(|v| v.day == day)
+}
+
+pub fn generate_input(day: u8, seed: u64, size: Option<usize>) -> Option<String> {
+    let generator = find_generator(day)?;
+    let mut random = Random::new(seed);
+    Some((generator.generate)(
+        &mut random,
+        size.unwrap_or(generator.size).max(1),
+    ))
+}
+
+fn join_numbers(values: impl Iterator<Item = impl ToString>, separator: &str) -> String {
+    values
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn generate_digit_grid(random: &mut Random, width: usize, height: usize, min: i64) -> String {
+    let mut result = String::with_capacity((width + 1) * height);
+    for _ in 0..height {
+        for _ in 0..width {
+            result.push((b'0' + random.range(min..=9) as u8) as char);
+        }
+        result.push('\n');
+    }
+    result
+}
+
+fn generate_depths(random: &mut Random, size: usize) -> String {
+    let mut depth = random.range(100..=200);
+    let mut result = String::new();
+    for _ in 0..size {
+        writeln!(result, "{}", depth).unwrap();
+        depth = (depth + random.range(-10..=30)).max(0);
+    }
+    result
+}
+
+fn generate_commands(random: &mut Random, size: usize) -> String {
+    let mut depth = 0;
+    let mut result = String::new();
+    for _ in 0..size {
+        let shift = random.range(1..=9);
+        let direction = match random.index(3) {
+            0 => "forward",
+            1 => "down",
+            _ if depth >= shift => "up",
+            _ => "down",
+        };
+        match direction {
+            "down" => depth += shift,
+            "up" => depth -= shift,
+            _ => (),
+        }
+        writeln!(result, "{} {}", direction, shift).unwrap();
+    }
+    result
+}
+
+fn generate_diagnostic_report(random: &mut Random, size: usize) -> String {
+    let width = (usize::BITS - size.leading_zeros()).max(12) as usize;
+    let mut values = BTreeSet::new();
+    while values.len() < size {
+        values.insert(random.next_u64() & ((1 << width) - 1));
+    }
+    let mut values: Vec<u64> = values.into_iter().collect();
+    random.shuffle(&mut values);
+    let mut result = String::new();
+    for value in values {
+        writeln!(result, "{:0width$b}", value, width = width).unwrap();
+    }
+    result
+}
+
+fn generate_bingo(random: &mut Random, size: usize) -> String {
+    let mut numbers: Vec<u8> = (0..100).collect();
+    random.shuffle(&mut numbers);
+    let mut result = join_numbers(numbers.iter(), ",");
+    result.push('\n');
+    for _ in 0..size {
+        random.shuffle(&mut numbers);
+        result.push('\n');
+        for row in numbers[0..25].chunks(5) {
+            let row: Vec<String> = row.iter().map(|v| format!("{:2}", v)).collect();
+            writeln!(result, "{}", row.join(" ")).unwrap();
+        }
+    }
+    result
+}
+
+fn generate_vent_lines(random: &mut Random, size: usize) -> String {
+    let mut result = String::new();
+    for _ in 0..size {
+        let (x1, y1) = (random.range(10..=989), random.range(10..=989));
+        let (x2, y2) = match random.index(3) {
+            0 => (random.range(10..=989), y1),
+            1 => (x1, random.range(10..=989)),
+            _ => {
+                let length = random.range(1..=x1.min(y1).min(989 - x1).min(989 - y1).max(1));
+                let dx = if random.chance(1, 2) { 1 } else { -1 };
+                let dy = if random.chance(1, 2) { 1 } else { -1 };
+                (x1 + dx * length, y1 + dy * length)
+            }
+        };
+        writeln!(result, "{},{} -> {},{}", x1, y1, x2, y2).unwrap();
+    }
+    result
+}
+
+fn generate_lanternfish(random: &mut Random, size: usize) -> String {
+    join_numbers((0..size).map(|_| random.range(1..=5)), ",") + "\n"
+}
+
+fn generate_crabs(random: &mut Random, size: usize) -> String {
+    let max_position = (size as i64 * 2).max(10);
+    join_numbers((0..size).map(|_| random.range(0..=max_position)), ",") + "\n"
+}
+
+const SEGMENTS: [&str; 10] = [
+    "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+];
+
+fn generate_signal_patterns(random: &mut Random, size: usize) -> String {
+    let mut result = String::new();
+    for _ in 0..size {
+        let mut wires: Vec<u8> = (b'a'..=b'g').collect();
+        random.shuffle(&mut wires);
+        let scramble = |random: &mut Random, digit: usize| {
+            let mut pattern: Vec<u8> = SEGMENTS[digit]
+                .bytes()
+                .map(|v| wires[(v - b'a') as usize])
+                .collect();
+            random.shuffle(&mut pattern);
+            String::from_utf8(pattern).unwrap()
+        };
+        let mut digits: Vec<usize> = (0..10).collect();
+        random.shuffle(&mut digits);
+        let signal: Vec<String> = digits.iter().map(|v| scramble(random, *v)).collect();
+        let output: Vec<String> = (0..4)
+            .map(|_| {
+                let digit = random.index(10);
+                scramble(random, digit)
+            })
+            .collect();
+        writeln!(result, "{} | {}", signal.join(" "), output.join(" ")).unwrap();
+    }
+    result
+}
+
+fn generate_heightmap(random: &mut Random, size: usize) -> String {
+    generate_digit_grid(random, size, size, 0)
+}
+
+const OPEN_BRACKETS: &[u8; 4] = b"([{<";
+const CLOSE_BRACKETS: &[u8; 4] = b")]}>";
+
+fn generate_navigation_subsystem(random: &mut Random, size: usize) -> String {
+    let mut result = String::new();
+    for i in 0..size {
+        let length = random.range(20..=110) as usize;
+        let corrupted = i % 2 == 1 && random.chance(2, 3);
+        let mut open = Vec::new();
+        let mut line = Vec::with_capacity(length);
+        while line.len() < length {
+            if !open.is_empty() && random.chance(2, 5) {
+                let index: usize = open.pop().unwrap();
+                line.push(CLOSE_BRACKETS[index]);
+            } else {
+                let index = random.index(4);
+                open.push(index);
+                line.push(OPEN_BRACKETS[index]);
+            }
+        }
+        if corrupted {
+            let index = open.last().copied().unwrap_or(0);
+            line.push(CLOSE_BRACKETS[(index + random.range(1..=3) as usize) % 4]);
+        } else if open.is_empty() {
+            line.push(OPEN_BRACKETS[random.index(4)]);
+        }
+        result.push_str(std::str::from_utf8(&line).unwrap());
+        result.push('\n');
+    }
+    result
+}
+
+fn generate_octopus_grid(random: &mut Random, size: usize) -> String {
+    // A grid where every octopus flashes in the first step keeps flashing in
+    // sync; lowering all energies by the same shift delays that step.
+    let shift = random.range(0..=5) as u8;
+    let mut grid = Grid {
+        values: (0..size * size)
+            .map(|_| random.range(shift as i64..=9) as u8)
+            .collect(),
+        width: size,
+        height: size,
+    };
+    let mut simulated = grid.clone();
+    if update_octopus_energy(&mut simulated) < simulated.values.len() {
+        for (value, energy) in grid.values.iter_mut().zip(simulated.values.iter()) {
+            if *energy != 0 {
+                *value = 9;
+            }
+        }
+    }
+    for value in grid.values.iter_mut() {
+        *value -= shift;
+    }
+    grid.to_string()
+}
+
+fn generate_cave_graph(random: &mut Random, size: usize) -> String {
+    let mut small = Vec::new();
+    let mut big = Vec::new();
+    let mut names = BTreeSet::new();
+    let mut make_name = |random: &mut Random, base: u8| loop {
+        let name: String = (0..2)
+            .map(|_| (base + random.index(26) as u8) as char)
+            .collect();
+        if names.insert(name.to_ascii_lowercase()) {
+            return name;
+        }
+    };
+    for _ in 0..(size / 3).max(2) {
+        small.push(make_name(random, b'a'));
+    }
+    for _ in 0..(size / 8).max(1) {
+        big.push(make_name(random, b'A'));
+    }
+    let caves: Vec<String> = small.iter().chain(big.iter()).cloned().collect();
+    let mut edges = BTreeSet::new();
+    add_cave_edge(&mut edges, "start", &caves[random.index(caves.len())]);
+    add_cave_edge(&mut edges, &caves[random.index(caves.len())], "end");
+    for cave in caves.iter().skip(1) {
+        add_cave_edge(&mut edges, cave, &small[random.index(small.len())]);
+    }
+    while edges.len() < size.max(caves.len() + 2) {
+        let a = match random.index(8) {
+            0 => "start",
+            1 => "end",
+            _ => &caves[random.index(caves.len())],
+        };
+        add_cave_edge(&mut edges, a, &small[random.index(small.len())]);
+    }
+    let mut edges: Vec<(String, String)> = edges.into_iter().collect();
+    random.shuffle(&mut edges);
+    let mut result = String::new();
+    for (a, b) in edges {
+        if random.chance(1, 2) {
+            writeln!(result, "{}-{}", a, b).unwrap();
+        } else {
+            writeln!(result, "{}-{}", b, a).unwrap();
+        }
+    }
+    result
+}
+
+fn add_cave_edge(edges: &mut BTreeSet<(String, String)>, a: &str, b: &str) {
+    let edge = if a < b { (a, b) } else { (b, a) };
+    if edge.0 != edge.1 {
+        edges.insert((String::from(edge.0), String::from(edge.1)));
+    }
+}
+
+fn generate_transparent_paper(random: &mut Random, size: usize) -> String {
+    let mut folds: Vec<char> = std::iter::repeat_n('x', 5)
+        .chain(std::iter::repeat_n('y', 7))
+        .collect();
+    random.shuffle(&mut folds);
+    let (mut width, mut height) = (40, 6);
+    let mut coordinates = Vec::with_capacity(folds.len());
+    for axis in folds.iter().rev() {
+        if *axis == 'x' {
+            coordinates.push(width);
+            width = 2 * width + 1;
+        } else {
+            coordinates.push(height);
+            height = 2 * height + 1;
+        }
+    }
+    coordinates.reverse();
+    let mut dots = BTreeSet::new();
+    while dots.len() < size.min(width * height / 2) {
+        let (mut x, mut y) = (random.index(40), random.index(6));
+        for (axis, coordinate) in folds.iter().zip(coordinates.iter()).rev() {
+            if random.chance(1, 2) {
+                match axis {
+                    'x' => x = 2 * coordinate - x,
+                    _ => y = 2 * coordinate - y,
+                }
+            }
+        }
+        dots.insert((x, y));
+    }
+    let mut dots: Vec<(usize, usize)> = dots.into_iter().collect();
+    random.shuffle(&mut dots);
+    let mut result = String::new();
+    for (x, y) in dots {
+        writeln!(result, "{},{}", x, y).unwrap();
+    }
+    result.push('\n');
+    for (axis, coordinate) in folds.iter().zip(coordinates.iter()) {
+        writeln!(result, "fold along {}={}", axis, coordinate).unwrap();
+    }
+    result
+}
+
+fn generate_polymer(random: &mut Random, size: usize) -> String {
+    let mut letters: Vec<u8> = (b'A'..=b'Z').collect();
+    random.shuffle(&mut letters);
+    let elements = &letters[0..10];
+    let template: Vec<u8> = (0..size.max(2)).map(|_| *random.choose(elements)).collect();
+    let mut result = String::from_utf8(template).unwrap();
+    result.push_str("\n\n");
+    for a in elements {
+        for b in elements {
+            let insert = *random.choose(elements);
+            writeln!(result, "{}{} -> {}", *a as char, *b as char, insert as char).unwrap();
+        }
+    }
+    result
+}
+
+fn generate_risk_map(random: &mut Random, size: usize) -> String {
+    generate_digit_grid(random, size, size, 1)
+}
+
+fn generate_bits_packet(random: &mut Random, size: usize) -> String {
+    let mut budget = size;
+    let (packet, _) = generate_bits_sub_packet(random, &mut budget, 0);
+    let mut choose_length_type = |packet: &Packet| {
+        let length: usize = sub_packets(&packet.data).iter().map(max_bits_length).sum();
+        if length < 1 << 15 && random.chance(1, 2) {
+            LengthType::Bits
+        } else {
+            LengthType::Count
+        }
+    };
+    encode_packet(&packet, &mut choose_length_type).unwrap() + "\n"
+}
+
+const MAX_SUB_PACKETS: usize = (1 << 11) - 1;
+
+fn generate_bits_sub_packet(
+    random: &mut Random,
+    budget: &mut usize,
+    depth: usize,
+) -> (Packet, u64) {
+    *budget = budget.saturating_sub(1);
+    let version = random.range(0..=7) as u8;
+    if *budget == 0 || depth >= 10 || (depth > 0 && random.chance(1, 3)) {
+        let value = random.next_u64() >> random.range(28..=63);
+        let data = Expression::LiteralValue(BigUint::from(value));
+        return (Packet { version, data }, value);
+    }
+    let count = if depth == 0 {
+        MAX_SUB_PACKETS
+    } else if random.chance(1, 3) {
+        2
+    } else {
+        random.range(1..=(*budget as i64).clamp(1, 5)) as usize
+    };
+    let mut sub_packets = Vec::new();
+    let mut values = Vec::new();
+    while values.len() < count && (values.is_empty() || *budget > 0) {
+        let (sub_packet, value) = generate_bits_sub_packet(random, budget, depth + 1);
+        sub_packets.push(sub_packet);
+        values.push(value);
+    }
+    let product = values
+        .iter()
+        .try_fold(1u64, |product, v| product.checked_mul(*v))
+        .filter(|v| *v < 1 << 48);
+    let mut type_ids = vec![0, 2, 3];
+    if product.is_some() {
+        type_ids.push(1);
+    }
+    if values.len() == 2 {
+        type_ids.extend_from_slice(&[5, 6, 7]);
+    }
+    let (data, value) = match *random.choose(&type_ids) {
+        0 => (Expression::Sum(sub_packets), values.iter().sum()),
+        1 => (Expression::Product(sub_packets), product.unwrap()),
+        2 => (
+            Expression::Minimum(sub_packets),
+            *values.iter().min().unwrap(),
+        ),
+        3 => (
+            Expression::Maximum(sub_packets),
+            *values.iter().max().unwrap(),
+        ),
+        5 => (
+            Expression::GreaterThan(sub_packets),
+            (values[0] > values[1]) as u64,
+        ),
+        6 => (
+            Expression::LessThan(sub_packets),
+            (values[0] < values[1]) as u64,
+        ),
+        _ => (
+            Expression::EqualTo(sub_packets),
+            (values[0] == values[1]) as u64,
+        ),
+    };
+    (Packet { version, data }, value)
+}
+
+fn max_bits_length(packet: &Packet) -> usize {
+    match &packet.data {
+        Expression::LiteralValue(value) => 6 + 5 * value.bits().div_ceil(4).max(1),
+        data => 22 + sub_packets(data).iter().map(max_bits_length).sum::<usize>(),
+    }
+}
+
+fn generate_target_area(random: &mut Random, size: usize) -> String {
+    let size = size.max(10) as i64;
+    let min_x = random.range(size / 2..=size);
+    let max_x = min_x + random.range(size / 10..=size / 3);
+    let min_y = -random.range(size / 2..=size);
+    let max_y = (min_y + random.range(size / 10..=size / 3)).min(-1);
+    format!(
+        "target area: x={}..{}, y={}..{}\n",
+        min_x, max_x, min_y, max_y
+    )
+}
+
+fn generate_snailfish_numbers(random: &mut Random, size: usize) -> String {
+    let mut result = String::new();
+    for _ in 0..size {
+        generate_snailfish_pair(random, 1, &mut result);
+        result.push('\n');
+    }
+    result
+}
+
+fn generate_snailfish_pair(random: &mut Random, depth: usize, result: &mut String) {
+    result.push('[');
+    for i in 0..2 {
+        if i > 0 {
+            result.push(',');
+        }
+        if depth < 4 && random.chance(3, 4 + depth as u64) {
+            generate_snailfish_pair(random, depth + 1, result);
+        } else {
+            write!(result, "{}", random.range(0..=9)).unwrap();
+        }
+    }
+    result.push(']');
+}
+
+const SCANNER_RANGE: i64 = 1000;
+const SCANNER_POSITION_LIMIT: i64 = 15000;
+const SHARED_BEACONS: usize = 12;
+const MAX_SCANNERS: usize = 1000;
+
+fn generate_scanners(random: &mut Random, size: usize) -> String {
+    let mut scanners = vec![[0i64; 3]];
+    let mut beacons = BTreeSet::new();
+    while scanners.len() < size.clamp(2, MAX_SCANNERS) {
+        let base = *random.choose(&scanners);
+        let axis = random.index(3);
+        let mut position = base;
+        position[axis] += random.range(1100..=1200) * if random.chance(1, 2) { 1 } else { -1 };
+        if position[axis].abs() > SCANNER_POSITION_LIMIT
+            || scanners
+                .iter()
+                .any(|scanner| (0..3).all(|i| (scanner[i] - position[i]).abs() <= SCANNER_RANGE))
+        {
+            continue;
+        }
+        let mut shared = 0;
+        while shared < SHARED_BEACONS {
+            let mut beacon = [0; 3];
+            for (i, value) in beacon.iter_mut().enumerate() {
+                let min = base[i].max(position[i]) - SCANNER_RANGE;
+                let max = base[i].min(position[i]) + SCANNER_RANGE;
+                *value = random.range(min..=max);
+            }
+            shared += beacons.insert(beacon) as usize;
+        }
+        for _ in 0..random.range(5..=15) {
+            let mut beacon = position;
+            for value in beacon.iter_mut() {
+                *value += random.range(-SCANNER_RANGE..=SCANNER_RANGE);
+            }
+            beacons.insert(beacon);
+        }
+        scanners.push(position);
+    }
+    let rotations = generate_rotations();
+    let mut result = String::new();
+    for (index, scanner) in scanners.iter().enumerate() {
+        if index > 0 {
+            result.push('\n');
+        }
+        writeln!(result, "--- scanner {} ---", index).unwrap();
+        let rotation = if index == 0 {
+            &rotations[0]
+        } else {
+            random.choose(&rotations)
+        };
+        let mut visible: Vec<[i64; 3]> = beacons
+            .iter()
+            .filter(|beacon| (0..3).all(|i| (beacon[i] - scanner[i]).abs() <= SCANNER_RANGE))
+            .map(|beacon| {
+                let relative = [
+                    beacon[0] - scanner[0],
+                    beacon[1] - scanner[1],
+                    beacon[2] - scanner[2],
+                ];
+                let mut rotated = [0; 3];
+                for (i, value) in rotated.iter_mut().enumerate() {
+                    *value = (0..3).map(|j| rotation[i][j] * relative[j]).sum();
+                }
+                rotated
+            })
+            .collect();
+        random.shuffle(&mut visible);
+        for beacon in visible {
+            writeln!(result, "{},{},{}", beacon[0], beacon[1], beacon[2]).unwrap();
+        }
+    }
+    result
+}
+
+fn generate_rotations() -> Vec<[[i64; 3]; 3]> {
+    let permutations = [
+        ([0, 1, 2], 1),
+        ([1, 2, 0], 1),
+        ([2, 0, 1], 1),
+        ([0, 2, 1], -1),
+        ([2, 1, 0], -1),
+        ([1, 0, 2], -1),
+    ];
+    let mut result = Vec::with_capacity(24);
+    for (permutation, parity) in permutations.iter() {
+        for signs in 0..8 {
+            let sign = |i: usize| if signs & (1 << i) == 0 { 1 } else { -1 };
+            if sign(0) * sign(1) * sign(2) != *parity {
+                continue;
+            }
+            let mut rotation = [[0; 3]; 3];
+            for i in 0..3 {
+                rotation[i][permutation[i]] = sign(i);
+            }
+            result.push(rotation);
+        }
+    }
+    result
+}
+
+fn generate_image(random: &mut Random, size: usize) -> String {
+    let mut enhancement: Vec<u8> = (0..512)
+        .map(|_| if random.chance(1, 2) { b'#' } else { b'.' })
+        .collect();
+    if enhancement[0] == b'#' {
+        enhancement[511] = b'.';
+    }
+    let mut result = String::from_utf8(enhancement).unwrap();
+    result.push_str("\n\n");
+    for _ in 0..size {
+        for _ in 0..size {
+            result.push(if random.chance(1, 2) { '#' } else { '.' });
+        }
+        result.push('\n');
+    }
+    result
+}
+
+fn generate_starting_positions(random: &mut Random, size: usize) -> String {
+    let size = size.clamp(1, 10) as i64;
+    format!(
+        "Player 1 starting position: {}\nPlayer 2 starting position: {}\n",
+        random.range(1..=size),
+        random.range(1..=size)
+    )
+}
+
+fn generate_cuboids(random: &mut Random, size: usize) -> String {
+    let mut result = String::new();
+    for i in 0..size {
+        let (limit, min_length, max_length) = if i < 20 {
+            (50, 20, 50)
+        } else {
+            (100000, 5000, 40000)
+        };
+        let state = if i == 0 || random.chance(3, 4) {
+            "on"
+        } else {
+            "off"
+        };
+        let ranges = loop {
+            let ranges: Vec<(i64, i64)> = (0..3)
+                .map(|_| {
+                    let length = random.range(min_length..=max_length);
+                    let min = random.range(-limit..=limit - length);
+                    (min, min + length)
+                })
+                .collect();
+            if i < 20 || ranges.iter().any(|(min, max)| *max < -50 || *min > 50) {
+                break ranges;
+            }
+        };
+        writeln!(
+            result,
+            "{} x={}..{},y={}..{},z={}..{}",
+            state, ranges[0].0, ranges[0].1, ranges[1].0, ranges[1].1, ranges[2].0, ranges[2].1
+        )
+        .unwrap();
+    }
+    result
+}
+
+fn generate_burrow(random: &mut Random, size: usize) -> String {
+    let depth = size.clamp(1, 2);
+    let mut amphipods: Vec<u8> = (0..depth).flat_map(|_| b"ABCD".iter().copied()).collect();
+    random.shuffle(&mut amphipods);
+    let mut result = String::from("#############\n#...........#\n");
+    for (row, kinds) in amphipods.chunks(4).enumerate() {
+        let kinds: Vec<String> = kinds.iter().map(|v| (*v as char).to_string()).collect();
+        if row == 0 {
+            writeln!(result, "###{}###", kinds.join("#")).unwrap();
+        } else {
+            writeln!(result, "  #{}#", kinds.join("#")).unwrap();
+        }
+    }
+    result.push_str("  #########\n");
+    result
+}
+
+fn generate_monad(random: &mut Random, size: usize) -> String {
+    let pairs = (size / 2).clamp(1, 7);
+    let mut blocks: Vec<bool> = Vec::with_capacity(pairs * 2);
+    let (mut pushed, mut depth) = (0, 0);
+    while blocks.len() < pairs * 2 {
+        let push = depth == 0 || (pushed < pairs && random.chance(1, 2));
+        if push {
+            pushed += 1;
+            depth += 1;
+        } else {
+            depth -= 1;
+        }
+        blocks.push(push);
+    }
+    let mut stack = Vec::new();
+    let mut result = String::new();
+    for push in blocks {
+        let y_term = random.range(1..=16);
+        let (z_divisor, x_term) = if push {
+            stack.push(y_term);
+            (1, random.range(10..=16))
+        } else {
+            (26, random.range(-8..=8) - stack.pop().unwrap())
+        };
+        write!(
+            result,
+            "inp w\nmul x 0\nadd x z\nmod x 26\ndiv z {}\nadd x {}\neql x w\neql x 0\n\
+             mul y 0\nadd y 25\nmul y x\nadd y 1\nmul z y\nmul y 0\nadd y w\nadd y {}\n\
+             mul y x\nadd z y\n",
+            z_divisor, x_term, y_term
+        )
+        .unwrap();
+    }
+    result
+}
+
+fn generate_sea_cucumbers(random: &mut Random, size: usize) -> String {
+    let size = size.max(2);
+    let (wall_x, wall_y) = (random.index(size), random.index(size));
+    let mut result = String::with_capacity((size + 1) * size);
+    for y in 0..size {
+        for x in 0..size {
+            result.push(if x == wall_x {
+                'v'
+            } else if y == wall_y {
+                '>'
+            } else {
+                *random.choose(&['.', '.', '>', 'v'])
+            });
+        }
+        result.push('\n');
+    }
+    result
+}
+
+#[test]
+fn generate_input_test() {
+    for generator in GENERATORS {
+        let size = if generator.day == 23 {
+            1
+        } else {
+            generator.size.min(10)
+        };
+        let input = generate_input(generator.day, 42, Some(size)).unwrap();
+        assert_eq!(
+            generate_input(generator.day, 42, Some(size)),
+            Some(input.clone())
+        );
+        for solver in crate::registry::find_day_solvers(generator.day) {
+            assert!(
+                (solver.solve)(&mut input.as_bytes()).is_ok(),
+                "day {} part {}:\n{}",
+                solver.day,
+                solver.part,
+                input
+            );
+        }
+    }
+}
+
+#[test]
+fn generate_octopus_grid_test() {
+    for seed in 0..20 {
+        let input = generate_input(11, seed, Some(20)).unwrap();
+        let step = crate::day_11_part_2::find_first_step_of_synchronous_flash(input.as_bytes());
+        assert!(matches!(step, Ok(1..=6)), "{:?}\n{}", step, input);
+    }
+}
+
+#[test]
+fn generate_burrow_test() {
+    let inputs: BTreeSet<String> = (1..=4)
+        .map(|size| generate_input(23, 42, Some(size)).unwrap())
+        .collect();
+    assert_eq!(inputs.len(), 2);
+    for input in inputs {
+        let (folded, unfolded) = crate::day_23::relocate_amphipods(input.as_bytes()).unwrap();
+        assert_ne!(folded, u32::MAX, "{}", input);
+        assert_ne!(unfolded, u32::MAX, "{}", input);
+    }
+}
+
+#[test]
+fn generate_rotations_test() {
+    let rotations = generate_rotations();
+    assert_eq!(rotations.len(), 24);
+    assert_eq!(rotations[0], [[1, 0, 0], [0, 1, 0], [0, 0, 1]]);
+}
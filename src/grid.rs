@@ -0,0 +1,211 @@
+use crate::parse::{Lines, ParseError};
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+
+pub const NEIGHBOURS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (1, 0), (0, 1)];
+
+pub const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grid<T> {
+    pub values: Vec<T>,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self {
+            values: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn parse(
+        day: u8,
+        buffer: impl BufRead,
+        expected: &str,
+        f: impl Fn(u8) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let mut values = Vec::new();
+        let mut width = 0;
+        let mut height = 0;
+        for line in Lines::new(day, buffer) {
+            let line = line?;
+            if height > 0 && line.text.len() != width {
+                return Err(line.error_at_char(
+                    &line.text,
+                    width,
+                    format!("row of width {}", width),
+                ));
+            }
+            for (x, byte) in line.text.bytes().enumerate() {
+                values.push(f(byte).ok_or_else(|| line.error(&line.text[x..], expected))?);
+            }
+            width = line.text.len();
+            height += 1;
+        }
+        Ok(Self {
+            values,
+            width,
+            height,
+        })
+    }
+
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        x + y * self.width
+    }
+
+    pub fn position(&self, index: usize) -> (usize, usize) {
+        (index % self.width, index / self.width)
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> &T {
+        &self.values[self.index(x, y)]
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> &mut T {
+        let index = self.index(x, y);
+        &mut self.values[index]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: T) {
+        *self.get_mut(x, y) = value;
+    }
+
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        x >= 0 && (x as usize) < self.width && y >= 0 && (y as usize) < self.height
+    }
+
+    pub fn wrap(&self, x: isize, y: isize) -> (usize, usize) {
+        (
+            x.rem_euclid(self.width as isize) as usize,
+            y.rem_euclid(self.height as isize) as usize,
+        )
+    }
+
+    pub fn neighbours<'a>(
+        &'a self,
+        x: usize,
+        y: usize,
+        shifts: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        shifts.iter().filter_map(move |(shift_x, shift_y)| {
+            let (neighbour_x, neighbour_y) = (x as isize + shift_x, y as isize + shift_y);
+            if self.contains(neighbour_x, neighbour_y) {
+                Some((neighbour_x as usize, neighbour_y as usize))
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn torus_neighbours<'a>(
+        &'a self,
+        x: usize,
+        y: usize,
+        shifts: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        shifts
+            .iter()
+            .map(move |(shift_x, shift_y)| self.wrap(x as isize + shift_x, y as isize + shift_y))
+    }
+
+    pub fn neighbours_4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(x, y, &NEIGHBOURS_4)
+    }
+
+    pub fn neighbours_8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(x, y, &NEIGHBOURS_8)
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.width == 0 {
+            return Ok(());
+        }
+        for row in self.values.chunks(self.width) {
+            for value in row {
+                write!(formatter, "{}", value)?;
+            }
+            writeln!(formatter)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn parse_test() {
+    let buffer = r#"123
+456
+"#
+    .as_bytes();
+    let grid = Grid::parse(0, buffer, "digit", crate::parse::parse_digit).unwrap();
+    assert_eq!(grid.width, 3);
+    assert_eq!(grid.height, 2);
+    assert_eq!(*grid.get(1, 1), 5);
+    assert_eq!(grid.position(grid.index(2, 1)), (2, 1));
+    assert_eq!(grid.to_string(), "123\n456\n");
+    assert_eq!(Grid::new(0, 0, 0).to_string(), "");
+}
+
+#[test]
+fn parse_error_test() {
+    let buffer = r#"123
+4x6
+"#
+    .as_bytes();
+    assert_eq!(
+        Grid::parse(9, buffer, "digit", crate::parse::parse_digit),
+        Err(ParseError::new(9, 2, 2, "digit"))
+    );
+    let buffer = r#"123
+45
+"#
+    .as_bytes();
+    assert_eq!(
+        Grid::parse(9, buffer, "digit", crate::parse::parse_digit),
+        Err(ParseError::new(9, 2, 3, "row of width 3"))
+    );
+    let buffer = "123\n\u{e9}\u{e9}\u{e9}\n".as_bytes();
+    assert_eq!(
+        Grid::parse(9, buffer, "digit", crate::parse::parse_digit),
+        Err(ParseError::new(9, 2, 7, "row of width 3"))
+    );
+}
+
+#[test]
+fn neighbours_test() {
+    let grid = Grid::new(3, 2, 0);
+    assert_eq!(
+        grid.neighbours_4(0, 0).collect::<Vec<_>>(),
+        vec![(1, 0), (0, 1)]
+    );
+    assert_eq!(
+        grid.neighbours_8(1, 1).collect::<Vec<_>>(),
+        vec![(0, 0), (1, 0), (2, 0), (0, 1), (2, 1)]
+    );
+}
+
+#[test]
+fn torus_neighbours_test() {
+    let grid = Grid::new(3, 2, 0);
+    assert_eq!(
+        grid.torus_neighbours(0, 0, &NEIGHBOURS_4)
+            .collect::<Vec<_>>(),
+        vec![(2, 0), (0, 1), (1, 0), (0, 1)]
+    );
+}
@@ -0,0 +1,145 @@
+use crate::parse::ParseError;
+use crate::registry::get_input_path;
+use std::io::{BufRead, Read};
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Input {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Input {
+    pub fn name(&self) -> String {
+        match self {
+            Input::Stdin => String::from("-"),
+            Input::File(path) => path.display().to_string(),
+        }
+    }
+
+    pub fn read(&self) -> std::io::Result<Vec<u8>> {
+        match self {
+            Input::Stdin => {
+                let mut buffer = Vec::new();
+                std::io::stdin().lock().read_to_end(&mut buffer)?;
+                Ok(buffer)
+            }
+            Input::File(path) => std::fs::read(path),
+        }
+    }
+}
+
+pub fn is_default_input(day: u8, input: &Input) -> bool {
+    match input {
+        Input::Stdin => false,
+        Input::File(path) => match (
+            std::fs::canonicalize(path),
+            std::fs::canonicalize(get_input_path(day)),
+        ) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        },
+    }
+}
+
+pub fn is_directory(path: Option<&str>) -> bool {
+    path.map(|v| Path::new(v).is_dir()).unwrap_or(false)
+}
+
+pub fn find_inputs(day: u8, path: Option<&str>) -> std::io::Result<Vec<Input>> {
+    let path = match path {
+        Some("-") => return Ok(vec![Input::Stdin]),
+        Some(v) => PathBuf::from(v),
+        None => PathBuf::from(get_input_path(day)),
+    };
+    if !path.is_dir() {
+        return Ok(vec![Input::File(path)]);
+    }
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(&path)? {
+        let entry_path = entry?.path();
+        if entry_path.is_file() {
+            paths.push(entry_path);
+        }
+    }
+    paths.sort_unstable();
+    Ok(paths.into_iter().map(Input::File).collect())
+}
+
+pub fn find_inputs_or_exit(day: u8, path: Option<&str>) -> Vec<Input> {
+    match find_inputs(day, path) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("failed to list {}: {}", path.unwrap_or("inputs"), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+pub fn read_or_exit(input: &Input) -> Vec<u8> {
+    match input.read() {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("failed to read {}: {}", input.name(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+pub fn for_each_input(
+    day: u8,
+    path: Option<&str>,
+    mut f: impl FnMut(&mut dyn BufRead) -> Result<(), ParseError>,
+) {
+    let directory = is_directory(path);
+    let mut failed = false;
+    for input in find_inputs_or_exit(day, path) {
+        if directory {
+            println!("{}:", input.name());
+        }
+        let data = match input.read() {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("failed to read {}: {}", input.name(), e);
+                failed = true;
+                continue;
+            }
+        };
+        if let Err(e) = f(&mut data.as_slice()) {
+            eprintln!("{}: {}", input.name(), e);
+            failed = true;
+        }
+    }
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+#[test]
+fn find_inputs_test() {
+    assert_eq!(find_inputs(1, Some("-")).unwrap(), vec![Input::Stdin]);
+    assert_eq!(
+        find_inputs(1, None).unwrap(),
+        vec![Input::File(PathBuf::from("var/day_1.txt"))]
+    );
+    let inputs = find_inputs(1, Some("var")).unwrap();
+    assert!(inputs.contains(&Input::File(PathBuf::from("var/day_1.txt"))));
+    assert!(inputs.windows(2).all(|v| v[0].name() < v[1].name()));
+}
+
+#[test]
+fn is_default_input_test() {
+    assert!(is_default_input(
+        1,
+        &Input::File(PathBuf::from("var/day_1.txt"))
+    ));
+    assert!(is_default_input(
+        1,
+        &Input::File(PathBuf::from("./var/day_1.txt"))
+    ));
+    assert!(!is_default_input(
+        2,
+        &Input::File(PathBuf::from("var/day_1.txt"))
+    ));
+    assert!(!is_default_input(1, &Input::Stdin));
+}
@@ -0,0 +1,57 @@
+pub mod answers;
+pub mod args;
+pub mod bench;
+pub mod big_uint;
+pub mod bits;
+pub mod day_10_part_1;
+pub mod day_10_part_2;
+pub mod day_11_part_1;
+pub mod day_11_part_2;
+pub mod day_12_part_1;
+pub mod day_12_part_2;
+pub mod day_13_part_1;
+pub mod day_13_part_2;
+pub mod day_14_part_1;
+pub mod day_14_part_2;
+pub mod day_15_part_1;
+pub mod day_15_part_2;
+pub mod day_16_part_1;
+pub mod day_16_part_2;
+pub mod day_17_part_1;
+pub mod day_17_part_2;
+pub mod day_18_part_1;
+pub mod day_18_part_2;
+pub mod day_19_part_1;
+pub mod day_19_part_2;
+pub mod day_1_part_1;
+pub mod day_1_part_2;
+pub mod day_20;
+pub mod day_21;
+pub mod day_22;
+pub mod day_23;
+pub mod day_24;
+pub mod day_25;
+pub mod day_2_part_1;
+pub mod day_2_part_2;
+pub mod day_3_part_1;
+pub mod day_3_part_2;
+pub mod day_4_part_1;
+pub mod day_4_part_2;
+pub mod day_5_part_1;
+pub mod day_5_part_2;
+pub mod day_6_part_1;
+pub mod day_6_part_2;
+pub mod day_7_part_1;
+pub mod day_7_part_2;
+pub mod day_8_part_1;
+pub mod day_8_part_2;
+pub mod day_9_part_1;
+pub mod day_9_part_2;
+pub mod generate;
+pub mod grid;
+pub mod input;
+pub mod parse;
+pub mod pool;
+pub mod random;
+pub mod registry;
+pub mod vector;
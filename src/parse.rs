@@ -0,0 +1,194 @@
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(day: u8, line: usize, column: usize, expected: impl Into<String>) -> Self {
+        Self {
+            day,
+            line,
+            column,
+            expected: expected.into(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            formatter,
+            "day {}: syntax error at line {}, column {}: expected {}",
+            self.day, self.line, self.column, self.expected
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug)]
+pub struct Line {
+    pub day: u8,
+    pub number: usize,
+    pub text: String,
+}
+
+impl Line {
+    pub fn new(day: u8, number: usize, text: impl Into<String>) -> Self {
+        Self {
+            day,
+            number,
+            text: text.into(),
+        }
+    }
+
+    pub fn error(&self, at: &str, expected: impl Into<String>) -> ParseError {
+        let offset = at.as_ptr() as usize - self.text.as_ptr() as usize;
+        debug_assert!(offset <= self.text.len());
+        ParseError::new(self.day, self.number, offset + 1, expected)
+    }
+
+    pub fn error_at_end(&self, text: &str, expected: impl Into<String>) -> ParseError {
+        self.error(&text[text.len()..], expected)
+    }
+
+    pub fn error_at_char(
+        &self,
+        text: &str,
+        index: usize,
+        expected: impl Into<String>,
+    ) -> ParseError {
+        match text.char_indices().nth(index) {
+            Some((offset, _)) => self.error(&text[offset..], expected),
+            None => self.error_at_end(text, expected),
+        }
+    }
+
+    pub fn split_once<'a>(
+        &self,
+        text: &'a str,
+        separator: &str,
+    ) -> Result<(&'a str, &'a str), ParseError> {
+        text.split_once(separator)
+            .ok_or_else(|| self.error_at_end(text, format!("{:?}", separator)))
+    }
+
+    pub fn strip_prefix<'a>(&self, text: &'a str, prefix: &str) -> Result<&'a str, ParseError> {
+        text.strip_prefix(prefix)
+            .ok_or_else(|| self.error(text, format!("{:?}", prefix)))
+    }
+
+    pub fn parse<T: FromStr>(&self, text: &str, expected: &str) -> Result<T, ParseError> {
+        T::from_str(text).map_err(|_| self.error(text, expected))
+    }
+
+    pub fn parse_numbers<T: FromStr>(
+        &self,
+        text: &str,
+        separator: char,
+    ) -> Result<Vec<T>, ParseError> {
+        text.split(separator)
+            .filter(|v| separator != ' ' || !v.is_empty())
+            .map(|v| self.parse(v, "number"))
+            .collect()
+    }
+}
+
+pub struct Lines<R> {
+    day: u8,
+    number: usize,
+    lines: std::io::Lines<R>,
+}
+
+impl<R: BufRead> Lines<R> {
+    pub fn new(day: u8, buffer: R) -> Self {
+        Self {
+            day,
+            number: 0,
+            lines: buffer.lines(),
+        }
+    }
+
+    pub fn next_line(&mut self, expected: &str) -> Result<Line, ParseError> {
+        match self.next() {
+            Some(v) => v,
+            None => Err(self.error_at_end(expected)),
+        }
+    }
+
+    pub fn error_at_end(&self, expected: impl Into<String>) -> ParseError {
+        ParseError::new(self.day, self.number + 1, 1, expected)
+    }
+}
+
+impl<R: BufRead> Iterator for Lines<R> {
+    type Item = Result<Line, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = self.lines.next()?;
+        self.number += 1;
+        Some(match line {
+            Ok(v) => Ok(Line::new(self.day, self.number, v)),
+            Err(_) => Err(ParseError::new(self.day, self.number, 1, "UTF-8 text")),
+        })
+    }
+}
+
+pub fn read_numbers<T: FromStr>(lines: &mut Lines<impl BufRead>) -> Result<Vec<T>, ParseError> {
+    let line = lines.next_line("comma separated numbers")?;
+    line.parse_numbers(&line.text, ',')
+}
+
+pub fn parse_digit(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(10).map(|v| v as u8)
+}
+
+pub fn unwrap_or_exit<T>(result: Result<T, ParseError>) -> T {
+    match result {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[test]
+fn read_numbers_test() {
+    let mut lines = Lines::new(6, "3,4,3,1,2\r\nnext\n".as_bytes());
+    assert_eq!(read_numbers::<u8>(&mut lines), Ok(vec![3, 4, 3, 1, 2]));
+    assert_eq!(lines.next_line("line").unwrap().text, "next");
+}
+
+#[test]
+fn read_numbers_error_test() {
+    let mut lines = Lines::new(6, "3,4,x,1,2\n".as_bytes());
+    assert_eq!(
+        read_numbers::<u8>(&mut lines),
+        Err(ParseError::new(6, 1, 5, "number"))
+    );
+    assert_eq!(
+        read_numbers::<u8>(&mut lines),
+        Err(ParseError::new(6, 2, 1, "comma separated numbers"))
+    );
+}
+
+#[test]
+fn split_once_error_test() {
+    let line = Line::new(2, 3, "forward5");
+    assert_eq!(
+        line.split_once(&line.text, " "),
+        Err(ParseError::new(2, 3, 9, "\" \""))
+    );
+    assert_eq!(
+        line.split_once(&line.text, " ").unwrap_err().to_string(),
+        "day 2: syntax error at line 3, column 9: expected \" \""
+    );
+}
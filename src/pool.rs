@@ -0,0 +1,80 @@
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+thread_local! {
+    static JOBS: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+pub fn get_available_jobs() -> usize {
+    std::thread::available_parallelism()
+        .map(|v| v.get())
+        .unwrap_or(1)
+}
+
+pub fn get_jobs() -> usize {
+    JOBS.with(|v| v.get()).unwrap_or_else(get_available_jobs)
+}
+
+pub fn with_jobs<T>(jobs: usize, f: impl FnOnce() -> T) -> T {
+    let previous = JOBS.with(|v| v.replace(Some(jobs)));
+    let result = f();
+    JOBS.with(|v| v.set(previous));
+    result
+}
+
+pub fn map_parallel<T: Send>(jobs: usize, count: usize, f: impl Fn(usize) -> T + Sync) -> Vec<T> {
+    let jobs = jobs.clamp(1, count.max(1));
+    if jobs == 1 {
+        return (0..count).map(f).collect();
+    }
+    let worker_jobs = (get_jobs() / jobs).max(1);
+    let next = AtomicUsize::new(0);
+    let results: Vec<Mutex<Option<T>>> = (0..count).map(|_| Mutex::new(None)).collect();
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| {
+                with_jobs(worker_jobs, || loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    if index >= count {
+                        break;
+                    }
+                    let result = f(index);
+                    *results[index].lock().unwrap() = Some(result);
+                })
+            });
+        }
+    });
+    results
+        .into_iter()
+        .map(|v| v.into_inner().unwrap().unwrap())
+        .collect()
+}
+
+#[test]
+fn map_parallel_test() {
+    for jobs in [1, 4] {
+        assert_eq!(
+            map_parallel(jobs, 100, |i| i * i),
+            (0..100).map(|i| i * i).collect::<Vec<_>>()
+        );
+    }
+    assert_eq!(map_parallel(4, 0, |i| i), Vec::<usize>::new());
+}
+
+#[test]
+fn with_jobs_test() {
+    assert_eq!(with_jobs(3, get_jobs), 3);
+    assert_eq!(
+        with_jobs(8, || map_parallel(4, 4, |_| get_jobs())),
+        vec![2; 4]
+    );
+    assert_eq!(
+        with_jobs(2, || map_parallel(4, 4, |_| get_jobs())),
+        vec![1; 4]
+    );
+    assert_eq!(
+        with_jobs(8, || map_parallel(1, 4, |_| get_jobs())),
+        vec![8; 4]
+    );
+}
@@ -0,0 +1,75 @@
+use std::ops::RangeInclusive;
+
+#[derive(Clone, Debug)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94d049bb133111eb);
+        value ^ (value >> 31)
+    }
+
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (min, max) = range.into_inner();
+        assert!(min <= max);
+        let span = (max - min) as u64 + 1;
+        min + (self.next_u64() % span) as i64
+    }
+
+    pub fn index(&mut self, len: usize) -> usize {
+        assert!(len > 0);
+        (self.next_u64() % len as u64) as usize
+    }
+
+    pub fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.next_u64() % denominator < numerator
+    }
+
+    pub fn choose<'a, T>(&mut self, values: &'a [T]) -> &'a T {
+        &values[self.index(values.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, values: &mut [T]) {
+        for i in (1..values.len()).rev() {
+            values.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+#[test]
+fn range_test() {
+    let mut random = Random::new(42);
+    for _ in 0..1000 {
+        let value = random.range(-3..=5);
+        assert!((-3..=5).contains(&value));
+    }
+    assert_eq!(random.range(7..=7), 7);
+}
+
+#[test]
+fn seed_test() {
+    let values = |seed| {
+        let mut random = Random::new(seed);
+        (0..10).map(|_| random.next_u64()).collect::<Vec<_>>()
+    };
+    assert_eq!(values(1), values(1));
+    assert_ne!(values(1), values(2));
+}
+
+#[test]
+fn shuffle_test() {
+    let mut values: Vec<usize> = (0..100).collect();
+    Random::new(42).shuffle(&mut values);
+    assert_ne!(values, (0..100).collect::<Vec<_>>());
+    values.sort_unstable();
+    assert_eq!(values, (0..100).collect::<Vec<_>>());
+}
@@ -0,0 +1,218 @@
+use crate::parse::ParseError;
+use crate::*;
+use std::fmt::Display;
+use std::io::BufRead;
+
+pub struct Solver {
+    pub day: u8,
+    pub part: u8,
+    pub name: &'static str,
+    pub solve: fn(&mut dyn BufRead) -> Result<Option<Solution>, ParseError>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Solution {
+    pub answer: String,
+    pub parts: Vec<(&'static str, String)>,
+}
+
+impl Solution {
+    pub fn new(answer: impl Display) -> Self {
+        Self::with_parts(answer, Vec::new())
+    }
+
+    pub fn with_parts(answer: impl Display, parts: Vec<(&'static str, String)>) -> Self {
+        Self {
+            answer: answer.to_string(),
+            parts,
+        }
+    }
+}
+
+macro_rules! solver {
+    ($day:expr, $part:expr, $module:ident::$function:ident) => {
+        solver!($day, $part, $module::$function, Some)
+    };
+    ($day:expr, $part:expr, $module:ident::$function:ident, solution $solution:expr) => {
+        Solver {
+            day: $day,
+            part: $part,
+            name: stringify!($function),
+            solve: |buffer| $module::$function(buffer).map($solution),
+        }
+    };
+    ($day:expr, $part:expr, $module:ident::$function:ident, $answer:expr) => {
+        solver!(
+            $day,
+            $part,
+            $module::$function,
+            solution | v | { ($answer)(v).map(Solution::new) }
+        )
+    };
+}
+
+pub const SOLVERS: &[Solver] = &[
+    solver!(1, 1, day_1_part_1::count_increased_height),
+    solver!(1, 2, day_1_part_2::count_increased_height_with_window),
+    solver!(
+        2,
+        1,
+        day_2_part_1::move_submarine,
+        solution | v | {
+            Some(Solution::with_parts(
+                day_2_part_1::product(&v),
+                vec![
+                    ("position", v.horizontal.to_string()),
+                    ("depth", v.depth.to_string()),
+                ],
+            ))
+        }
+    ),
+    solver!(
+        2,
+        2,
+        day_2_part_2::move_submarine_with_aim,
+        solution | v | {
+            Some(Solution::with_parts(
+                day_2_part_2::position_product(&v),
+                vec![
+                    ("position", v.horizontal.to_string()),
+                    ("depth", v.depth.to_string()),
+                ],
+            ))
+        }
+    ),
+    solver!(3, 1, day_3_part_1::calculate_power_consumption),
+    solver!(3, 2, day_3_part_2::calculate_life_support_rating),
+    solver!(4, 1, day_4_part_1::find_first_bingo_winner_score, |v| v),
+    solver!(4, 2, day_4_part_2::find_last_bingo_winner_score, |v| v),
+    solver!(5, 1, day_5_part_1::count_points_of_intersection),
+    solver!(5, 2, day_5_part_2::count_points_of_intersection),
+    solver!(6, 1, day_6_part_1::simulate_lanternfish_population),
+    solver!(6, 2, day_6_part_2::simulate_lanternfish_population),
+    solver!(7, 1, day_7_part_1::align_crabs_position),
+    solver!(7, 2, day_7_part_2::align_crabs_position),
+    solver!(8, 1, day_8_part_1::count_digits),
+    solver!(8, 2, day_8_part_2::sum_decoded_numbers),
+    solver!(9, 1, day_9_part_1::calculate_total_risk),
+    solver!(9, 2, day_9_part_2::product_basin_top_3_sizes),
+    solver!(10, 1, day_10_part_1::calculate_total_syntax_error_score),
+    solver!(10, 2, day_10_part_2::calculate_total_completion_score),
+    solver!(11, 1, day_11_part_1::count_octopus_flashes),
+    solver!(11, 2, day_11_part_2::find_first_step_of_synchronous_flash),
+    solver!(12, 1, day_12_part_1::count_cave_paths),
+    solver!(12, 2, day_12_part_2::count_cave_paths),
+    solver!(13, 1, day_13_part_1::fold_transparent_paper),
+    solver!(13, 2, day_13_part_2::fold_transparent_paper),
+    solver!(14, 1, day_14_part_1::build_polymer),
+    solver!(14, 2, day_14_part_2::build_polymer),
+    solver!(15, 1, day_15_part_1::calculate_lowest_total_risk),
+    solver!(15, 2, day_15_part_2::calculate_lowest_total_risk),
+    solver!(16, 1, day_16_part_1::sum_packet_versions),
+    solver!(16, 2, day_16_part_2::evaluate_packet),
+    solver!(17, 1, day_17_part_1::find_max_y_for_probe, |v| v),
+    solver!(17, 2, day_17_part_2::count_initial_velocities_to_hit),
+    solver!(18, 1, day_18_part_1::calculate_snailfish_magnitude),
+    solver!(18, 2, day_18_part_2::find_max_sum_snailfish_magnitude),
+    solver!(19, 1, day_19_part_1::count_beacons),
+    solver!(19, 2, day_19_part_2::find_max_distance_between_scanners),
+    solver!(20, 1, day_20::count_enhanced_light_pixels_2_times),
+    solver!(20, 2, day_20::count_enhanced_light_pixels_50_times),
+    solver!(21, 1, day_21::play_dirac_dice_deterministic),
+    solver!(21, 2, day_21::play_dirac_dice_quantum),
+    solver!(22, 1, day_22::reboot_reactor_init_region),
+    solver!(22, 2, day_22::reboot_reactor_full),
+    solver!(23, 1, day_23::relocate_amphipods_folded),
+    solver!(23, 2, day_23::relocate_amphipods_unfolded),
+    solver!(24, 1, day_24::find_max_model_number, |v| v),
+    solver!(24, 2, day_24::find_min_model_number, |v| v),
+    solver!(25, 1, day_25::move_sea_cucumbers),
+];
+
+pub const VARIANTS: &[Solver] = &[
+    solver!(6, 1, day_6_part_1::simulate_lanternfish_population_per_fish),
+    solver!(7, 2, day_7_part_2::align_crabs_position_with_ternary_search),
+];
+
+pub fn find_solver(day: u8, part: u8) -> Option<&'static Solver> {
+    SOLVERS.iter().find(|v| v.day == day && v.part == part)
+}
+
+pub fn find_day_solvers(day: u8) -> impl Iterator<Item = &'static Solver> {
+    SOLVERS.iter().filter(move |v| v.day == day)
+}
+
+pub fn find_variants(day: u8, part: u8) -> impl Iterator<Item = &'static Solver> {
+    VARIANTS
+        .iter()
+        .filter(move |v| v.day == day && v.part == part)
+}
+
+pub fn get_input_path(day: u8) -> String {
+    format!("var/day_{}.txt", day)
+}
+
+#[test]
+fn solvers_are_unique_and_ordered_test() {
+    for (prev, next) in SOLVERS.iter().zip(SOLVERS.iter().skip(1)) {
+        assert!((prev.day, prev.part) < (next.day, next.part));
+    }
+}
+
+#[test]
+fn every_day_has_solver_test() {
+    for day in 1..=25 {
+        assert!(find_solver(day, 1).is_some());
+    }
+}
+
+#[test]
+fn every_variant_has_solver_test() {
+    for variant in VARIANTS {
+        assert!(find_solver(variant.day, variant.part).is_some());
+    }
+}
+
+#[test]
+fn solve_test() {
+    let solver = find_solver(21, 1).unwrap();
+    let mut buffer = r#"Player 1 starting position: 4
+Player 2 starting position: 8
+"#
+    .as_bytes();
+    assert_eq!((solver.solve)(&mut buffer), Ok(Some(Solution::new(739785))));
+}
+
+#[test]
+fn solve_parts_test() {
+    let solver = find_solver(2, 1).unwrap();
+    let mut buffer = r#"forward 5
+down 5
+forward 8
+up 3
+down 8
+forward 2
+"#
+    .as_bytes();
+    assert_eq!(
+        (solver.solve)(&mut buffer),
+        Ok(Some(Solution::with_parts(
+            150,
+            vec![
+                ("position", String::from("15")),
+                ("depth", String::from("10"))
+            ],
+        )))
+    );
+}
+
+#[test]
+fn solve_empty_input_test() {
+    for solver in SOLVERS {
+        let _ = (solver.solve)(&mut "".as_bytes());
+    }
+    assert_eq!(
+        (find_solver(18, 1).unwrap().solve)(&mut "".as_bytes()),
+        Err(ParseError::new(18, 1, 1, "snailfish number"))
+    );
+}
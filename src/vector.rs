@@ -0,0 +1,72 @@
+use std::ops::{AddAssign, Mul, SubAssign};
+
+pub type Vec2<T> = [T; 2];
+pub type Vec3<T> = [T; 3];
+pub type Mat3<T> = [Vec3<T>; 3];
+
+pub fn add<T: Copy + AddAssign, const N: usize>(mut a: [T; N], b: [T; N]) -> [T; N] {
+    for (a, b) in a.iter_mut().zip(b.iter()) {
+        *a += *b;
+    }
+    a
+}
+
+pub fn sub<T: Copy + SubAssign, const N: usize>(mut a: [T; N], b: [T; N]) -> [T; N] {
+    for (a, b) in a.iter_mut().zip(b.iter()) {
+        *a -= *b;
+    }
+    a
+}
+
+pub fn dot_product<T, const N: usize>(a: [T; N], b: [T; N]) -> T
+where
+    T: Copy + Default + AddAssign + Mul<Output = T>,
+{
+    let mut result = T::default();
+    for (a, b) in a.iter().zip(b.iter()) {
+        result += *a * *b;
+    }
+    result
+}
+
+pub fn mat3_vec3_product<T>(mat: &Mat3<T>, vec: Vec3<T>) -> Vec3<T>
+where
+    T: Copy + Default + AddAssign + Mul<Output = T>,
+{
+    let mut result: Vec3<T> = [T::default(); 3];
+    for (value, row) in result.iter_mut().zip(mat.iter()) {
+        *value = dot_product(*row, vec);
+    }
+    result
+}
+
+pub fn mat3_product<T>(a: &Mat3<T>, b: &Mat3<T>) -> Mat3<T>
+where
+    T: Copy + Default + AddAssign + Mul<Output = T>,
+{
+    let mut result: Mat3<T> = [[T::default(); 3]; 3];
+    for (i, row) in result.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            for (k, bv) in b.iter().enumerate() {
+                *value += a[i][k] * bv[j];
+            }
+        }
+    }
+    result
+}
+
+#[test]
+fn add_sub_test() {
+    assert_eq!(add([1, 2, 3], [4, 5, 6]), [5, 7, 9]);
+    assert_eq!(sub([1, 2], [4, 6]), [-3, -4]);
+}
+
+#[test]
+fn mat3_test() {
+    let rot: Mat3<i16> = [[0, -1, 0], [1, 0, 0], [0, 0, 1]];
+    assert_eq!(mat3_vec3_product(&rot, [1, 2, 3]), [-2, 1, 3]);
+    assert_eq!(
+        mat3_product(&rot, &rot),
+        [[-1, 0, 0], [0, -1, 0], [0, 0, 1]]
+    );
+}